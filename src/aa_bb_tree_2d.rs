@@ -50,6 +50,19 @@ where
         Self::new_rec(data, maxdepth, allowed_bucket_size, 0)
     }
 
+    /// Creates a new tree by splitting with the surface area heuristic (SAH)
+    /// In contrast to new, elements are never duplicated
+    pub fn new_sah(data: Vec<HB>, allowed_bucket_size: usize) -> Self {
+        let mut items = data
+            .iter()
+            .enumerate()
+            .map(|(i, x)| SahItem2D::new(i, x.bounding_box()))
+            .collect::<Vec<_>>();
+        let mut data = data.into_iter().map(Some).collect::<Vec<_>>();
+
+        Self::new_sah_rec(&mut items, &mut data, allowed_bucket_size)
+    }
+
     pub fn any<'a>(&'a self, f: &dyn Fn(&HB) -> bool) -> bool {
         match self {
             Self::Empty => false,
@@ -140,6 +153,37 @@ where
         }
     }
 
+    fn new_sah_rec(
+        items: &mut [SahItem2D],
+        data: &mut Vec<Option<HB>>,
+        allowed_bucket_size: usize,
+    ) -> Self {
+        let bb = match bb_of_items_2d(items) {
+            None => return AABBTree2D::Empty,
+            Some(bb) => bb,
+        };
+
+        let n_left = if items.len() > allowed_bucket_size {
+            sah_partition_2d(items)
+        } else {
+            None
+        };
+
+        match n_left {
+            None => {
+                let leaf_data = items.iter().filter_map(|x| data[x.index].take()).collect();
+                AABBTree2D::Leaf(AABBTree2DLeaf::new(leaf_data, bb))
+            }
+            Some(n_left) => {
+                let (ileft, iright) = items.split_at_mut(n_left);
+                let left = Box::new(Self::new_sah_rec(ileft, data, allowed_bucket_size));
+                let right = Box::new(Self::new_sah_rec(iright, data, allowed_bucket_size));
+
+                AABBTree2D::Branch(AABBTree2DBranch::new(left, right, bb))
+            }
+        }
+    }
+
     fn is_left_of(compx: bool, bb: &BoundingBox2D, center: &Point2D) -> bool {
         if compx {
             bb.min_p().x() < center.x()
@@ -50,6 +50,19 @@ where
         Self::new_rec(data, maxdepth, allowed_bucket_size, 0)
     }
 
+    /// Creates a new tree by splitting with the surface area heuristic (SAH)
+    /// In contrast to new, elements are never duplicated
+    pub fn new_sah(data: Vec<HB>, allowed_bucket_size: usize) -> Self {
+        let mut items = data
+            .iter()
+            .enumerate()
+            .map(|(i, x)| SahItem3D::new(i, x.bounding_box()))
+            .collect::<Vec<_>>();
+        let mut data = data.into_iter().map(Some).collect::<Vec<_>>();
+
+        Self::new_sah_rec(&mut items, &mut data, allowed_bucket_size)
+    }
+
     pub fn any<'a>(&'a self, f: &dyn Fn(&HB) -> bool) -> bool {
         match self {
             Self::Empty => false,
@@ -82,6 +95,42 @@ where
         }
     }
 
+    /// Returns the element with the smallest squared distance to the point and that distance
+    /// sqr_dist should return the squared distance of an element, which must not be smaller than the one of its bounding box
+    pub fn nearest<'a, P>(
+        &'a self,
+        point: &P,
+        sqr_dist: &dyn Fn(&HB) -> f64,
+    ) -> Option<(&'a HB, f64)>
+    where
+        P: Is3D,
+    {
+        let mut best = None;
+        self.nearest_rec(&Point3D::new_from(point), sqr_dist, &mut best);
+        best
+    }
+
+    fn nearest_rec<'a>(
+        &'a self,
+        point: &Point3D,
+        sqr_dist: &dyn Fn(&HB) -> f64,
+        best: &mut Option<(&'a HB, f64)>,
+    ) {
+        match self {
+            Self::Empty => (),
+            Self::Leaf(leaf) => leaf.nearest(point, sqr_dist, best),
+            Self::Branch(branch) => branch.nearest(point, sqr_dist, best),
+        }
+    }
+
+    fn sqr_distance_bb(&self, point: &Point3D) -> Option<f64> {
+        match self {
+            Self::Empty => None,
+            Self::Leaf(leaf) => Some(*leaf.bb.sqr_distance(point)),
+            Self::Branch(branch) => Some(*branch.bb.sqr_distance(point)),
+        }
+    }
+
     pub fn bb_crossing_x_value<'a>(&'a self, x: f64, result: &mut Vec<&'a HB>) {
         match self {
             Self::Empty => (),
@@ -160,6 +209,37 @@ where
         }
     }
 
+    fn new_sah_rec(
+        items: &mut [SahItem3D],
+        data: &mut Vec<Option<HB>>,
+        allowed_bucket_size: usize,
+    ) -> Self {
+        let bb = match bb_of_items_3d(items) {
+            None => return AABBTree3D::Empty,
+            Some(bb) => bb,
+        };
+
+        let n_left = if items.len() > allowed_bucket_size {
+            sah_partition_3d(items)
+        } else {
+            None
+        };
+
+        match n_left {
+            None => {
+                let leaf_data = items.iter().filter_map(|x| data[x.index].take()).collect();
+                AABBTree3D::Leaf(AABBTree3DLeaf::new(leaf_data, bb))
+            }
+            Some(n_left) => {
+                let (ileft, iright) = items.split_at_mut(n_left);
+                let left = Box::new(Self::new_sah_rec(ileft, data, allowed_bucket_size));
+                let right = Box::new(Self::new_sah_rec(iright, data, allowed_bucket_size));
+
+                AABBTree3D::Branch(AABBTree3DBranch::new(left, right, bb))
+            }
+        }
+    }
+
     fn is_left_of(comp: &Compare, bb: &BoundingBox3D, center: &Point3D) -> bool {
         match comp {
             Compare::X => bb.min_p().x() < center.x(),
@@ -189,6 +269,59 @@ where
     }
 }
 
+#[cfg(feature = "rayon")]
+impl<HB> AABBTree3D<HB>
+where
+    HB: HasBoundingBox3D + Clone + Send + Sync,
+{
+    /// Creates a new tree, building the subtrees in parallel
+    pub fn new_par(data: Vec<HB>, maxdepth: usize, allowed_bucket_size: usize) -> Self {
+        Self::new_par_rec(data, maxdepth, allowed_bucket_size, 0)
+    }
+
+    fn new_par_rec(
+        data: Vec<HB>,
+        maxdepth: usize,
+        allowed_bucket_size: usize,
+        depth: usize,
+    ) -> Self {
+        if data.len() < PARALLEL_MIN_SIZE || depth >= maxdepth || data.len() <= allowed_bucket_size
+        {
+            return Self::new_rec(data, maxdepth, allowed_bucket_size, depth);
+        }
+
+        let comp = match depth % 3 {
+            0 => Compare::X,
+            1 => Compare::Y,
+            _ => Compare::Z,
+        };
+        let bb = Self::bb_of(&data).unwrap(); //unwrap fine due to data not empty
+        let center = bb.center_bb();
+
+        let dleft = data
+            .iter()
+            .filter(|x| Self::is_left_of(&comp, &x.bounding_box(), &center))
+            .cloned()
+            .collect::<Vec<_>>();
+        let dright = data
+            .iter()
+            .filter(|x| Self::is_right_of(&comp, &x.bounding_box(), &center))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if (dleft.len() == dright.len()) && dleft.len() == data.len() {
+            AABBTree3D::Leaf(AABBTree3DLeaf::new(data, bb))
+        } else {
+            let (left, right) = rayon::join(
+                || Self::new_par_rec(dleft, maxdepth, allowed_bucket_size, depth + 1),
+                || Self::new_par_rec(dright, maxdepth, allowed_bucket_size, depth + 1),
+            );
+
+            AABBTree3D::Branch(AABBTree3DBranch::new(Box::new(left), Box::new(right), bb))
+        }
+    }
+}
+
 //------------------------------------------------------------------------------
 
 enum Compare {
@@ -252,6 +385,26 @@ where
         }
     }
 
+    pub fn nearest<'a>(
+        &'a self,
+        point: &Point3D,
+        sqr_dist: &dyn Fn(&HB) -> f64,
+        best: &mut Option<(&'a HB, f64)>,
+    ) {
+        if !is_closer(*self.bb.sqr_distance(point), best) {
+            return;
+        }
+        for x in self.data.iter() {
+            if !is_closer(*x.bounding_box().sqr_distance(point), best) {
+                continue;
+            }
+            let d = sqr_dist(x);
+            if is_closer(d, best) {
+                *best = Some((x, d));
+            }
+        }
+    }
+
     pub fn bb_colliding<'a>(&'a self, bb: &BoundingBox3D, result: &mut Vec<&'a HB>) {
         if self.bb.collides_with(bb) {
             for x in self.data.iter() {
@@ -342,6 +495,28 @@ where
         self.right.for_each_collision_candidate(bb, f);
     }
 
+    pub fn nearest<'a>(
+        &'a self,
+        point: &Point3D,
+        sqr_dist: &dyn Fn(&HB) -> f64,
+        best: &mut Option<(&'a HB, f64)>,
+    ) {
+        if !is_closer(*self.bb.sqr_distance(point), best) {
+            return;
+        }
+
+        // the closer child first, to prune more of the other one
+        let dl = self.left.sqr_distance_bb(point).unwrap_or(f64::MAX);
+        let dr = self.right.sqr_distance_bb(point).unwrap_or(f64::MAX);
+        let (first, second) = if dl <= dr {
+            (&self.left, &self.right)
+        } else {
+            (&self.right, &self.left)
+        };
+        first.nearest_rec(point, sqr_dist, best);
+        second.nearest_rec(point, sqr_dist, best);
+    }
+
     pub fn bb_colliding<'a>(&'a self, bb: &BoundingBox3D, result: &mut Vec<&'a HB>) {
         if self.bb.collides_with(bb) {
             self.left.bb_colliding(bb, result);
@@ -373,6 +548,15 @@ where
 
 //------------------------------------------------------------------------------
 
+fn is_closer<HB>(sqr_dist: f64, best: &Option<(&HB, f64)>) -> bool {
+    match best {
+        None => true,
+        Some((_, d)) => sqr_dist < *d,
+    }
+}
+
+//------------------------------------------------------------------------------
+
 impl<HB> IsColliderContainer3D for AABBTree3D<HB>
 where
     HB: Clone + HasColliders3D + Sized,
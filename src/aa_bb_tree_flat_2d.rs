@@ -0,0 +1,224 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! AABBTreeFlat2D, an axis aligned bounding box tree in 2D stored within a single node array
+
+use crate::*;
+
+//------------------------------------------------------------------------------
+
+#[derive(Clone)]
+/// AABBTreeFlat2D, an axis aligned bounding box tree in 2D stored within a single node array
+/// Built via the surface area heuristic (SAH), supports refitting after the elements were changed
+pub struct AABBTreeFlat2D<HB>
+where
+    HB: HasBoundingBox2D,
+{
+    data: Vec<HB>,
+    order: Vec<usize>,
+    nodes: Vec<FlatNode2D>,
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Clone)]
+// Nodes are stored depth first, the left child of a branch is always the next node
+struct FlatNode2D {
+    bb: BoundingBox2D,
+    kind: FlatNodeKind,
+}
+
+#[derive(Clone)]
+enum FlatNodeKind {
+    Leaf { start: usize, count: usize },
+    Branch { right: usize },
+}
+
+//------------------------------------------------------------------------------
+
+impl<HB> AABBTreeFlat2D<HB>
+where
+    HB: HasBoundingBox2D,
+{
+    pub fn new(data: Vec<HB>, allowed_bucket_size: usize) -> Self {
+        let mut items = data
+            .iter()
+            .enumerate()
+            .map(|(i, x)| SahItem2D::new(i, x.bounding_box()))
+            .collect::<Vec<_>>();
+
+        let mut nodes = Vec::new();
+        Self::build_rec(&mut items, 0, allowed_bucket_size, &mut nodes);
+        let order = items.iter().map(|x| x.index).collect();
+
+        Self { data, order, nodes }
+    }
+    /// The elements in their original order
+    pub fn data(&self) -> &Vec<HB> {
+        &self.data
+    }
+    /// Mutable access to the elements, refit must be called after changing their bounding boxes
+    /// Elements can only be edited in place, since the tree refers to them by index
+    pub fn data_mut(&mut self) -> &mut [HB] {
+        &mut self.data
+    }
+    /// Returns the element at the given index (original order)
+    pub fn get(&self, index: usize) -> Option<&HB> {
+        self.data.get(index)
+    }
+    /// Mutable access to the element at the given index, refit must be called after changing its bounding box
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut HB> {
+        self.data.get_mut(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+    /// Recalculates all bounding boxes of the tree, keeping its structure
+    /// Should be called after the elements were deformed or moved
+    pub fn refit(&mut self) {
+        for i in (0..self.nodes.len()).rev() {
+            let bb = match self.nodes[i].kind {
+                FlatNodeKind::Leaf { start, count } => {
+                    let mut bb = self.data[self.order[start]].bounding_box();
+                    for j in start + 1..start + count {
+                        bb.consume(self.data[self.order[j]].bounding_box());
+                    }
+                    bb
+                }
+                FlatNodeKind::Branch { right } => {
+                    let mut bb = self.nodes[i + 1].bb.clone();
+                    bb.consume(self.nodes[right].bb.clone());
+                    bb
+                }
+            };
+            self.nodes[i].bb = bb;
+        }
+    }
+
+    pub fn any<'a>(&'a self, f: &dyn Fn(&HB) -> bool) -> bool {
+        self.data.iter().any(f)
+    }
+
+    pub fn for_each_collision_candidate<'a>(&'a self, bb: &BoundingBox2D, f: &mut dyn FnMut(&HB)) {
+        self.for_each_hit(&|x| x.collides_with(bb), &mut |x| f(x))
+    }
+
+    pub fn bb_colliding<'a>(&'a self, bb: &BoundingBox2D, result: &mut Vec<&'a HB>) {
+        self.for_each_hit(&|x| x.collides_with(bb), &mut |x| result.push(x))
+    }
+
+    pub fn bb_crossing_x_value<'a>(&'a self, x: f64, result: &mut Vec<&'a HB>) {
+        self.for_each_hit(&|bb| bb.crossing_x_value(x), &mut |d| result.push(d))
+    }
+
+    pub fn bb_crossing_y_value<'a>(&'a self, y: f64, result: &mut Vec<&'a HB>) {
+        self.for_each_hit(&|bb| bb.crossing_y_value(y), &mut |d| result.push(d))
+    }
+
+    fn for_each_hit<'a>(
+        &'a self,
+        is_hit: &dyn Fn(&BoundingBox2D) -> bool,
+        f: &mut dyn FnMut(&'a HB),
+    ) {
+        if self.nodes.is_empty() {
+            return;
+        }
+
+        let mut stack = vec![0];
+        while let Some(i) = stack.pop() {
+            let node = &self.nodes[i];
+            if !is_hit(&node.bb) {
+                continue;
+            }
+            match node.kind {
+                FlatNodeKind::Leaf { start, count } => {
+                    for j in start..start + count {
+                        let x = &self.data[self.order[j]];
+                        if is_hit(&x.bounding_box()) {
+                            f(x)
+                        }
+                    }
+                }
+                FlatNodeKind::Branch { right } => {
+                    stack.push(right);
+                    stack.push(i + 1);
+                }
+            }
+        }
+    }
+
+    fn build_rec(
+        items: &mut [SahItem2D],
+        start: usize,
+        allowed_bucket_size: usize,
+        nodes: &mut Vec<FlatNode2D>,
+    ) {
+        let bb = match bb_of_items_2d(items) {
+            None => return,
+            Some(bb) => bb,
+        };
+
+        let n_left = if items.len() > allowed_bucket_size {
+            sah_partition_2d(items)
+        } else {
+            None
+        };
+
+        match n_left {
+            None => nodes.push(FlatNode2D {
+                bb,
+                kind: FlatNodeKind::Leaf {
+                    start,
+                    count: items.len(),
+                },
+            }),
+            Some(n_left) => {
+                let i = nodes.len();
+                nodes.push(FlatNode2D {
+                    bb,
+                    kind: FlatNodeKind::Branch { right: 0 },
+                });
+                let (ileft, iright) = items.split_at_mut(n_left);
+                Self::build_rec(ileft, start, allowed_bucket_size, nodes);
+                let right = nodes.len();
+                Self::build_rec(iright, start + n_left, allowed_bucket_size, nodes);
+                nodes[i].kind = FlatNodeKind::Branch { right };
+            }
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+impl<HB> HasBoundingBox2DMaybe for AABBTreeFlat2D<HB>
+where
+    HB: HasBoundingBox2D,
+{
+    fn bounding_box_maybe(&self) -> Option<BoundingBox2D> {
+        self.nodes.first().map(|x| x.bb.clone())
+    }
+}
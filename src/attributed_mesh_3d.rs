@@ -0,0 +1,233 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! AttributedMesh3D, a mesh with tri-faces within 3D space and named attribute channels for its vertices and faces
+
+use crate::*;
+
+//------------------------------------------------------------------------------
+
+#[derive(Default, Debug, PartialEq, Clone)]
+/// AttributedMesh3D, a mesh with tri-faces within 3D space and named attribute channels for its vertices and faces
+/// Algorithms rebuilding meshes (e.g. heal_mesh, unify_faces or the subdivisions) carry the attributes over, interpolating those of new vertices
+pub struct AttributedMesh3D<P, ID, IC>
+where
+    P: Is3D,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+{
+    mesh: Mesh3D<P, ID, IC>,
+    vertex_attributes: MeshAttributes,
+    face_attributes: MeshAttributes,
+}
+
+impl<P, ID, IC> AttributedMesh3D<P, ID, IC>
+where
+    P: Is3D + Clone,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+{
+    /// Creates a new AttributedMesh3D from a mesh, without any channels
+    pub fn new(mesh: Mesh3D<P, ID, IC>) -> Self {
+        let vertex_attributes = MeshAttributes::new(mesh.num_vertices());
+        let face_attributes = MeshAttributes::new(mesh.num_faces());
+        Self {
+            mesh,
+            vertex_attributes,
+            face_attributes,
+        }
+    }
+    /// Returns the mesh
+    pub fn mesh(&self) -> &Mesh3D<P, ID, IC> {
+        &self.mesh
+    }
+    /// Adds a vertex channel, replacing any with the same name. Error if its number of values doesn't match the number of vertices
+    pub fn add_vertex_channel(&mut self, name: &str, channel: AttributeChannel) -> Result<()> {
+        self.vertex_attributes.add_channel(name, channel)
+    }
+    /// Adds a face channel, replacing any with the same name. Error if its number of values doesn't match the number of faces
+    pub fn add_face_channel(&mut self, name: &str, channel: AttributeChannel) -> Result<()> {
+        self.face_attributes.add_channel(name, channel)
+    }
+    /// Returns the vertex channel with the given name
+    pub fn vertex_channel(&self, name: &str) -> Option<&AttributeChannel> {
+        self.vertex_attributes.channel(name)
+    }
+    /// Returns the face channel with the given name
+    pub fn face_channel(&self, name: &str) -> Option<&AttributeChannel> {
+        self.face_attributes.channel(name)
+    }
+    /// Removes and returns the vertex channel with the given name
+    pub fn remove_vertex_channel(&mut self, name: &str) -> Option<AttributeChannel> {
+        self.vertex_attributes.remove_channel(name)
+    }
+    /// Removes and returns the face channel with the given name
+    pub fn remove_face_channel(&mut self, name: &str) -> Option<AttributeChannel> {
+        self.face_attributes.remove_channel(name)
+    }
+}
+
+impl<P, ID, IC> IsMesh<P, Face3> for AttributedMesh3D<P, ID, IC>
+where
+    P: Is3D + Clone,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+{
+    fn num_faces(&self) -> usize {
+        self.mesh.num_faces()
+    }
+
+    fn num_vertices(&self) -> usize {
+        self.mesh.num_vertices()
+    }
+
+    fn face_vertex_ids(&self, faceid: FId) -> Option<Face3> {
+        self.mesh.face_vertex_ids(faceid)
+    }
+
+    fn face_vertices(&self, faceid: FId) -> Option<[P; 3]> {
+        self.mesh.face_vertices(faceid)
+    }
+
+    fn vertex(&self, vertexid: VId) -> Option<P> {
+        self.mesh.vertex(vertexid)
+    }
+
+    fn vertex_attributes(&self) -> Option<&MeshAttributes> {
+        Some(&self.vertex_attributes)
+    }
+
+    fn face_attributes(&self) -> Option<&MeshAttributes> {
+        Some(&self.face_attributes)
+    }
+}
+
+impl<P, ID, IC> IsFaceEditableMesh<P, Face3> for AttributedMesh3D<P, ID, IC>
+where
+    P: IsEditable3D + IsBuildable3D + Clone,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+{
+    fn add_face(&mut self, v1: P, v2: P, v3: P) -> FId {
+        let vid1 = self.add_vertex(v1);
+        let vid2 = self.add_vertex(v2);
+        let vid3 = self.add_vertex(v3);
+        // safe since the vertices were just added
+        self.try_add_connection(vid1, vid2, vid3).unwrap()
+    }
+
+    fn try_add_connection(&mut self, vid1: VId, vid2: VId, vid3: VId) -> Result<FId> {
+        let fid = self.mesh.try_add_connection(vid1, vid2, vid3)?;
+        self.face_attributes.push_default();
+        Ok(fid)
+    }
+
+    fn reserve_faces(&mut self, n: usize) {
+        self.mesh.reserve_faces(n)
+    }
+
+    fn try_add_connection_derived(
+        &mut self,
+        vid1: VId,
+        vid2: VId,
+        vid3: VId,
+        source: Option<&MeshAttributes>,
+        faceid: FId,
+    ) -> Result<FId> {
+        if let Some(source) = source {
+            if faceid.0 >= source.len() {
+                return Err(ErrorKind::IncorrectFaceID);
+            }
+        }
+        let fid = self.mesh.try_add_connection(vid1, vid2, vid3)?;
+        self.face_attributes
+            .push_derived(source, &[(faceid.0, 1.0)])?;
+        Ok(fid)
+    }
+}
+
+impl<P, ID, IC> IsVertexEditableMesh<P, Face3> for AttributedMesh3D<P, ID, IC>
+where
+    P: IsEditable3D + IsBuildable3D + Clone,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+{
+    fn add_vertex(&mut self, vertex: P) -> VId {
+        self.vertex_attributes.push_default();
+        self.mesh.add_vertex(vertex)
+    }
+
+    fn change_vertex(&mut self, vid: VId, vertex: P) -> Result<()> {
+        self.mesh.change_vertex(vid, vertex)
+    }
+
+    fn reserve_vertices(&mut self, n: usize) {
+        self.mesh.reserve_vertices(n)
+    }
+
+    fn add_vertex_derived(
+        &mut self,
+        vertex: P,
+        source: Option<&MeshAttributes>,
+        weights: &[(VId, f64)],
+    ) -> Result<VId> {
+        let weights: Vec<(usize, f64)> = weights.iter().map(|(vid, w)| (vid.0, *w)).collect();
+        self.vertex_attributes
+            .push_derived(source, &weights)
+            .map_err(|_| ErrorKind::IncorrectVertexID)?;
+        Ok(self.mesh.add_vertex(vertex))
+    }
+}
+
+impl<P, ID, IC> HasBoundingBox3DMaybe for AttributedMesh3D<P, ID, IC>
+where
+    P: Is3D,
+    ID: IsDataContainer<P> + HasBoundingBox3DMaybe,
+    IC: IsIndexContainer,
+{
+    fn bounding_box_maybe(&self) -> Option<BoundingBox3D> {
+        self.mesh.bounding_box_maybe()
+    }
+}
+
+impl<P, ID, IC> From<Mesh3D<P, ID, IC>> for AttributedMesh3D<P, ID, IC>
+where
+    P: Is3D + Clone,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+{
+    fn from(mesh: Mesh3D<P, ID, IC>) -> Self {
+        Self::new(mesh)
+    }
+}
+
+impl<P, ID, IC> From<AttributedMesh3D<P, ID, IC>>
+    for (Mesh3D<P, ID, IC>, MeshAttributes, MeshAttributes)
+where
+    P: Is3D,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+{
+    fn from(am: AttributedMesh3D<P, ID, IC>) -> Self {
+        (am.mesh, am.vertex_attributes, am.face_attributes)
+    }
+}
@@ -0,0 +1,513 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Clipping and cutting of meshes by planes and boxes
+
+use crate::*;
+
+use fnv::FnvHashMap;
+
+//------------------------------------------------------------------------------
+
+/// Clips the mesh by the plane, keeping the part below it (opposite to u x v)
+/// Faces crossing the plane are split, if cap is set the cut is closed by faces within the plane
+/// Vertex attributes are interpolated along the cut edges, split faces keep their attributes and caps get default ones
+/// Error if capping is requested but the cut can't be closed (e.g. since the mesh isn't closed)
+pub fn clip_mesh<P, MI, MO, PL, PP, N>(mesh: &MI, plane: &PL, cap: bool) -> Result<MO>
+where
+    MI: IsMesh3D<P>,
+    MO: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+    P: IsBuildable3D,
+    PL: IsPlane3D<PP, N>,
+    PP: Is3D,
+    N: IsNormalized3D,
+{
+    let (origin, normal) = half_space_of_plane(plane);
+    let mut clipper = Clipper::new(mesh)?;
+    clipper.clip(&origin, &normal, cap)?;
+    clipper.build(mesh)
+}
+
+/// Cuts the mesh by the plane into the parts below and above it (in direction of u x v)
+/// Faces crossing the plane are split, if cap is set the cuts are closed by faces within the plane
+/// Error if capping is requested but the cut can't be closed (e.g. since the mesh isn't closed)
+/// Attributes are interpolated as by clip_mesh
+pub fn cut_mesh<P, MI, MO, PL, PP, N>(mesh: &MI, plane: &PL, cap: bool) -> Result<(MO, MO)>
+where
+    MI: IsMesh3D<P>,
+    MO: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+    P: IsBuildable3D,
+    PL: IsPlane3D<PP, N>,
+    PP: Is3D,
+    N: IsNormalized3D,
+{
+    let (origin, normal) = half_space_of_plane(plane);
+
+    let mut below = Clipper::new(mesh)?;
+    let mut above = below.clone();
+    below.clip(&origin, &normal, cap)?;
+    above.clip(&origin, &(normal * -1.0), cap)?;
+
+    Ok((below.build(mesh)?, above.build(mesh)?))
+}
+
+/// Clips the mesh by the box, keeping the part within it
+/// Faces crossing the box are split, if cap is set the cuts are closed by faces on the sides of the box
+/// Error if capping is requested but the cuts can't be closed (e.g. since the mesh isn't closed)
+/// Attributes are interpolated as by clip_mesh
+pub fn clip_mesh_box<P, MI, MO>(mesh: &MI, bb: &BoundingBox3D, cap: bool) -> Result<MO>
+where
+    MI: IsMesh3D<P>,
+    MO: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+    P: IsBuildable3D,
+{
+    let (min, max) = (bb.min_p(), bb.max_p());
+    let mut clipper = Clipper::new(mesh)?;
+    for (origin, normal) in [
+        (&min, Point3D::new(-1.0, 0.0, 0.0)),
+        (&min, Point3D::new(0.0, -1.0, 0.0)),
+        (&min, Point3D::new(0.0, 0.0, -1.0)),
+        (&max, Point3D::new(1.0, 0.0, 0.0)),
+        (&max, Point3D::new(0.0, 1.0, 0.0)),
+        (&max, Point3D::new(0.0, 0.0, 1.0)),
+    ]
+    .iter()
+    {
+        clipper.clip(origin, normal, cap)?;
+    }
+    clipper.build(mesh)
+}
+
+/// Clips the mesh by the box, keeping the part within it (the sizes of the box being its full extents)
+/// Faces crossing the box are split, if cap is set the cuts are closed by faces on the sides of the box
+/// Error if capping is requested but the cuts can't be closed (e.g. since the mesh isn't closed)
+/// Attributes are interpolated as by clip_mesh
+pub fn clip_mesh_box_unaligned<P, MI, MO>(
+    mesh: &MI,
+    box_3d: &BoxUnaligned3D,
+    cap: bool,
+) -> Result<MO>
+where
+    MI: IsMesh3D<P>,
+    MO: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+    P: IsBuildable3D,
+{
+    let c = &box_3d.center;
+    let mut clipper = Clipper::new(mesh)?;
+    for (dir, size) in [
+        (Point3D::new_from(&box_3d.x_dir()), box_3d.size[0]),
+        (Point3D::new_from(&box_3d.y_dir), box_3d.size[1]),
+        (Point3D::new_from(&box_3d.z_dir), box_3d.size[2]),
+    ]
+    .iter()
+    {
+        let half = 0.5 * **size;
+        for normal in [dir.clone(), dir.clone() * -1.0].iter() {
+            let origin = Point3D::new(
+                c.x + half * normal.x,
+                c.y + half * normal.y,
+                c.z + half * normal.z,
+            );
+            clipper.clip(&origin, normal, cap)?;
+        }
+    }
+    clipper.build(mesh)
+}
+
+//------------------------------------------------------------------------------
+
+/// Faces of a mesh which are clipped by half spaces one after another
+#[derive(Clone)]
+struct Clipper {
+    points: Vec<Point3D>,
+    /// The weighted input vertices each point is interpolated from
+    sources: Vec<Vec<(VId, f64)>>,
+    faces: Vec<[usize; 3]>,
+    /// The input face each face is a part of, None for caps
+    origins: Vec<Option<FId>>,
+}
+
+impl Clipper {
+    fn new<P, M>(mesh: &M) -> Result<Self>
+    where
+        M: IsMesh3D<P>,
+        P: IsBuildable3D,
+    {
+        let nv = mesh.num_vertices();
+        let mut points = Vec::with_capacity(nv);
+        for i in 0..nv {
+            let v = mesh.vertex(VId(i)).ok_or(ErrorKind::IncorrectVertexID)?;
+            points.push(Point3D::new_from(&v));
+        }
+
+        let nf = mesh.num_faces();
+        let mut faces = Vec::with_capacity(nf);
+        for i in 0..nf {
+            let f = mesh
+                .face_vertex_ids(FId(i))
+                .ok_or(ErrorKind::IncorrectFaceID)?;
+            let f = [f.a.0, f.b.0, f.c.0];
+            if f.iter().any(|v| *v >= nv) {
+                return Err(ErrorKind::IncorrectVertexID);
+            }
+            faces.push(f);
+        }
+
+        Ok(Self {
+            sources: (0..nv).map(|i| vec![(VId(i), 1.0)]).collect(),
+            points,
+            origins: (0..nf).map(|i| Some(FId(i))).collect(),
+            faces,
+        })
+    }
+
+    /// Keeps the parts of the faces with a non-positive distance to the plane through origin with the (unit) normal
+    fn clip(&mut self, origin: &Point3D, normal: &Point3D, cap: bool) -> Result<()> {
+        let mut distances = self
+            .points
+            .iter()
+            .map(|p| normal.dot(&conn(origin, p)))
+            .collect::<Vec<_>>();
+        let mut edge_points = FnvHashMap::<(usize, usize), usize>::default();
+
+        let faces = std::mem::take(&mut self.faces);
+        let origins = std::mem::take(&mut self.origins);
+        let mut polygon = Vec::with_capacity(4);
+        for (f, origin) in faces.iter().zip(origins) {
+            if f.iter().all(|v| distances[*v] <= 0.0) {
+                self.faces.push(*f);
+                self.origins.push(origin);
+                continue;
+            }
+
+            polygon.clear();
+            for k in 0..3 {
+                let (p, q) = (f[k], f[(k + 1) % 3]);
+                let (p_in, q_in) = (distances[p] <= 0.0, distances[q] <= 0.0);
+                if p_in {
+                    polygon.push(p);
+                }
+                if p_in != q_in {
+                    let x = self.edge_point(&mut edge_points, &mut distances, p, q);
+                    polygon.push(x);
+                }
+            }
+            // vertices on the plane are also used as edge points
+            polygon.dedup();
+            while polygon.len() > 1 && polygon.first() == polygon.last() {
+                polygon.pop();
+            }
+
+            for i in 2..polygon.len() {
+                self.faces.push([polygon[0], polygon[i - 1], polygon[i]]);
+                self.origins.push(origin);
+            }
+        }
+
+        if cap {
+            self.cap(&distances, normal)?;
+        }
+        Ok(())
+    }
+
+    /// The point where the plane intersects the edge, the vertex itself if it's on the plane
+    fn edge_point(
+        &mut self,
+        edge_points: &mut FnvHashMap<(usize, usize), usize>,
+        distances: &mut Vec<f64>,
+        p: usize,
+        q: usize,
+    ) -> usize {
+        let (dp, dq) = (distances[p], distances[q]);
+        if dp == 0.0 {
+            return p;
+        }
+        if dq == 0.0 {
+            return q;
+        }
+
+        let (points, sources) = (&mut self.points, &mut self.sources);
+        *edge_points.entry(edge_key(p, q)).or_insert_with(|| {
+            let (vp, vq) = (&points[p], &points[q]);
+            let t = dp / (dp - dq);
+            points.push(Point3D::new(
+                vp.x + t * (vq.x - vp.x),
+                vp.y + t * (vq.y - vp.y),
+                vp.z + t * (vq.z - vp.z),
+            ));
+            let mut weights = sources[p]
+                .iter()
+                .map(|(v, w)| (*v, (1.0 - t) * w))
+                .collect::<Vec<_>>();
+            for (v, w) in sources[q].iter() {
+                match weights.iter_mut().find(|(x, _)| x == v) {
+                    Some((_, x)) => *x += t * w,
+                    None => weights.push((*v, t * w)),
+                }
+            }
+            sources.push(weights);
+            distances.push(0.0);
+            points.len() - 1
+        })
+    }
+
+    /// Closes the boundary loops within the plane by faces
+    fn cap(&mut self, distances: &[f64], normal: &Point3D) -> Result<()> {
+        let mut n_uses = FnvHashMap::<(usize, usize), usize>::default();
+        for f in self.faces.iter() {
+            for k in 0..3 {
+                *n_uses.entry((f[k], f[(k + 1) % 3])).or_insert(0) += 1;
+            }
+        }
+
+        // the cap uses the boundary edges within the plane in reverse
+        let mut next = FnvHashMap::<usize, Vec<usize>>::default();
+        let mut n_edges = 0;
+        for (p, q) in n_uses.keys() {
+            if distances[*p] == 0.0 && distances[*q] == 0.0 && !n_uses.contains_key(&(*q, *p)) {
+                next.entry(*q).or_default().push(*p);
+                n_edges += 1;
+            }
+        }
+
+        let mut loops = Vec::new();
+        let mut starts = next.keys().cloned().collect::<Vec<_>>();
+        starts.sort();
+        for start in starts {
+            while let Some(mut current) = next.get_mut(&start).and_then(|x| x.pop()) {
+                n_edges -= 1;
+                let mut lp = vec![start];
+                while current != start {
+                    lp.push(current);
+                    current = next
+                        .get_mut(&current)
+                        .and_then(|x| x.pop())
+                        .ok_or(ErrorKind::MeshNotClosed)?;
+                    n_edges -= 1;
+                }
+                loops.push(lp);
+            }
+        }
+        if n_edges != 0 {
+            return Err(ErrorKind::MeshNotClosed);
+        }
+
+        let u = any_orthogonal(normal);
+        let v: Point3D = cross(normal, &u);
+        let positions = self
+            .points
+            .iter()
+            .map(|p| [u.dot(p), v.dot(p)])
+            .collect::<Vec<_>>();
+
+        let caps = triangulate_loops(&positions, loops);
+        self.origins.extend(caps.iter().map(|_| None));
+        self.faces.extend(caps);
+        Ok(())
+    }
+
+    /// Creates the mesh of the used points and faces, interpolating the attributes of the input mesh
+    fn build<P, MI, M>(&self, mesh: &MI) -> Result<M>
+    where
+        MI: IsMesh<P, Face3>,
+        M: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+        P: IsBuildable3D,
+    {
+        let mut ids = vec![None; self.points.len()];
+        let mut result = M::default();
+        result.reserve_faces(self.faces.len());
+        for (f, origin) in self.faces.iter().zip(self.origins.iter()) {
+            let mut face = [VId(0); 3];
+            for (k, v) in f.iter().enumerate() {
+                face[k] = match ids[*v] {
+                    Some(id) => id,
+                    None => {
+                        let id = result.add_vertex_derived(
+                            P::new_from(&self.points[*v]),
+                            mesh.vertex_attributes(),
+                            &self.sources[*v],
+                        )?;
+                        ids[*v] = Some(id);
+                        id
+                    }
+                };
+            }
+            match origin {
+                Some(x) => result.try_add_connection_derived(
+                    face[0],
+                    face[1],
+                    face[2],
+                    mesh.face_attributes(),
+                    *x,
+                )?,
+                None => result.try_add_connection(face[0], face[1], face[2])?,
+            };
+        }
+        Ok(result)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Origin and unit normal (u x v) of the plane
+fn half_space_of_plane<PL, P, N>(plane: &PL) -> (Point3D, Point3D)
+where
+    PL: IsPlane3D<P, N>,
+    P: Is3D,
+    N: IsNormalized3D,
+{
+    let normal: Point3D = cross(&plane.u(), &Point3D::new_from(&plane.v()));
+    let normal = match Norm3D::new(normal.clone()) {
+        Ok(n) => Point3D::new_from(&n),
+        Err(_) => normal,
+    };
+    (Point3D::new_from(&plane.origin()), normal)
+}
+
+fn signed_area(positions: &[[f64; 2]], lp: &[usize]) -> f64 {
+    let mut sum = 0.0;
+    for i in 0..lp.len() {
+        let (p, q) = (&positions[lp[i]], &positions[lp[(i + 1) % lp.len()]]);
+        sum += p[0] * q[1] - q[0] * p[1];
+    }
+    0.5 * sum
+}
+
+fn is_in_polygon(positions: &[[f64; 2]], lp: &[usize], x: &[f64; 2]) -> bool {
+    let mut inside = false;
+    for i in 0..lp.len() {
+        let (p, q) = (&positions[lp[i]], &positions[lp[(i + 1) % lp.len()]]);
+        if (p[1] > x[1]) != (q[1] > x[1])
+            && x[0] < p[0] + (x[1] - p[1]) / (q[1] - p[1]) * (q[0] - p[0])
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Triangulates the counter clockwise outer loops, connecting the clockwise holes within them
+fn triangulate_loops(positions: &[[f64; 2]], loops: Vec<Vec<usize>>) -> Vec<[usize; 3]> {
+    let (mut outers, mut holes) = (Vec::new(), Vec::new());
+    for lp in loops.into_iter() {
+        let area = signed_area(positions, &lp);
+        if lp.len() < 3 || area == 0.0 {
+            continue;
+        }
+        if area > 0.0 {
+            outers.push((area, lp, Vec::new()));
+        } else {
+            holes.push(lp);
+        }
+    }
+
+    // holes belong to the smallest outer loop containing them
+    for hole in holes.into_iter() {
+        let x = &positions[hole[0]];
+        let outer = outers
+            .iter_mut()
+            .filter(|(_, lp, _)| is_in_polygon(positions, lp, x))
+            .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        if let Some((_, _, holes)) = outer {
+            holes.push(hole);
+        }
+    }
+
+    let mut result = Vec::new();
+    for (_, mut polygon, mut holes) in outers.into_iter() {
+        let max_x = |lp: &Vec<usize>| {
+            lp.as_slice()
+                .iter()
+                .map(|v| positions[*v][0])
+                .fold(f64::MIN, f64::max)
+        };
+        holes.sort_by(|a, b| {
+            max_x(b)
+                .partial_cmp(&max_x(a))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        for hole in holes.iter() {
+            polygon = bridge_hole(positions, polygon, hole);
+        }
+        ear_clip(positions, polygon, &mut result);
+    }
+    result
+}
+
+/// Connects the hole to the polygon via an edge from the hole's rightmost vertex to a visible vertex of the polygon
+fn bridge_hole(positions: &[[f64; 2]], polygon: Vec<usize>, hole: &[usize]) -> Vec<usize> {
+    let m = (0..hole.len())
+        .max_by(|i, j| {
+            positions[hole[*i]][0]
+                .partial_cmp(&positions[hole[*j]][0])
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .unwrap_or(0);
+    let pm = positions[hole[m]];
+
+    // closest edge hit by a ray from m in x direction
+    let mut hit: Option<(f64, usize)> = None;
+    for i in 0..polygon.len() {
+        let j = (i + 1) % polygon.len();
+        let (p, q) = (&positions[polygon[i]], &positions[polygon[j]]);
+        if (p[1] > pm[1]) == (q[1] > pm[1]) {
+            continue;
+        }
+        let x = p[0] + (pm[1] - p[1]) / (q[1] - p[1]) * (q[0] - p[0]);
+        if x >= pm[0] && hit.iter().all(|(best, _)| x < *best) {
+            hit = Some((x, if p[0] > q[0] { i } else { j }));
+        }
+    }
+    let (hx, mut visible) = match hit {
+        None => return polygon,
+        Some(x) => x,
+    };
+
+    // vertices within the triangle of m, the hit and the candidate might block the view
+    let ph = [hx, pm[1]];
+    let candidate = positions[polygon[visible]];
+    let mut best_cos = f64::MIN;
+    for (i, v) in polygon.as_slice().iter().enumerate() {
+        let pv = &positions[*v];
+        let (a, b, c) = if orient(&pm, &ph, &candidate) >= 0.0 {
+            (&pm, &ph, &candidate)
+        } else {
+            (&pm, &candidate, &ph)
+        };
+        let within = orient(a, b, pv) > 0.0 && orient(b, c, pv) > 0.0 && orient(c, a, pv) > 0.0;
+        if within {
+            let (dx, dy) = (pv[0] - pm[0], pv[1] - pm[1]);
+            let cos = dx / dx.hypot(dy);
+            if cos > best_cos {
+                best_cos = cos;
+                visible = i;
+            }
+        }
+    }
+
+    let mut result = Vec::with_capacity(polygon.len() + hole.len() + 2);
+    result.extend_from_slice(&polygon[..=visible]);
+    result.extend_from_slice(&hole[m..]);
+    result.extend_from_slice(&hole[..=m]);
+    result.extend_from_slice(&polygon[visible..]);
+    result
+}
@@ -0,0 +1,239 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Discrete curvature estimation on meshes
+
+use crate::*;
+
+use std::f64::consts::PI;
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Default, Clone, PartialEq)]
+/// VertexCurvature, the discrete curvature at a vertex of a mesh
+/// Curvatures are positive for convex regions if the faces are oriented outwards
+pub struct VertexCurvature {
+    /// Mean curvature, based on the cotangent Laplace-Beltrami operator
+    pub mean: f64,
+    /// Gaussian curvature, based on the angle deficit
+    pub gaussian: f64,
+    /// Maximum principal curvature
+    pub k1: f64,
+    /// Minimum principal curvature
+    pub k2: f64,
+    /// Direction of the maximum principal curvature. None if it can't be determined
+    pub dir1: Option<Norm3D>,
+    /// Direction of the minimum principal curvature. None if it can't be determined
+    pub dir2: Option<Norm3D>,
+    /// Shape index in [-1.0, 1.0]. 1.0 for caps, 0.5 for ridges, 0.0 for saddles (and planes), -0.5 for ruts and -1.0 for cups
+    pub shape_index: f64,
+}
+
+//------------------------------------------------------------------------------
+
+/// Estimates the curvature at each vertex of a mesh
+/// Vertices without faces or with a vanishing area have zero curvature
+pub fn vertex_curvatures<P, M>(mesh: &M) -> Result<Vec<VertexCurvature>>
+where
+    M: IsSearchableMesh<P, Face3>,
+    P: IsBuildable3D,
+{
+    let nv = mesh.num_vertices();
+    let mut result = Vec::with_capacity(nv);
+    let mut cache = Vec::new();
+    let mut faces = Vec::new();
+    let mut neighbours = Vec::new();
+
+    for i in 0..nv {
+        let vid = VId(i);
+        faces.clear();
+        mesh.faces_of_vertex(vid, &mut cache, &mut faces)?;
+        let on_boundary = mesh.is_vertex_on_boundary(vid, &mut cache)?;
+        let center = Point3D::new_from(&mesh.vertex(vid).ok_or(ErrorKind::IncorrectVertexID)?);
+
+        let mut area = 0.0;
+        let mut angle_sum = 0.0;
+        let mut laplace = Point3D::default();
+        let mut normal = Point3D::default();
+
+        for f in faces.iter() {
+            let (a, b) = other_corners(mesh, *f, vid)?;
+            let to_a = conn(&center, &a);
+            let to_b = conn(&center, &b);
+
+            let face_area = 0.5 * *cross::<_, Point3D>(&to_a, &to_b).abs();
+            if face_area == 0.0 {
+                continue;
+            }
+            let angle = (2.0 * face_area).atan2(to_a.dot(&to_b));
+            let cot_a = cot(&conn(&a, &center), &conn(&a, &b));
+            let cot_b = cot(&conn(&b, &center), &conn(&b, &a));
+
+            laplace = laplace + to_a.clone() * cot_b + to_b.clone() * cot_a;
+            angle_sum += angle;
+            normal = normal + cross::<_, Point3D>(&to_a, &to_b) * (angle / (2.0 * face_area));
+
+            // mixed voronoi area
+            area += if angle > 0.5 * PI {
+                0.5 * face_area
+            } else if cot_a < 0.0 || cot_b < 0.0 {
+                0.25 * face_area
+            } else {
+                0.125 * (sqr_len(&to_a) * cot_b + sqr_len(&to_b) * cot_a)
+            };
+        }
+
+        let normal = match Norm3D::new(normal) {
+            Ok(n) if area > 0.0 => n,
+            _ => {
+                result.push(VertexCurvature::default());
+                continue;
+            }
+        };
+
+        let mean = -laplace.dot(&normal) / (4.0 * area);
+        let deficit = if on_boundary { PI } else { 2.0 * PI };
+        let gaussian = (deficit - angle_sum) / area;
+
+        let discriminant = (mean * mean - gaussian).max(0.0).sqrt();
+        let (k1, k2) = (mean + discriminant, mean - discriminant);
+
+        neighbours.clear();
+        mesh.vertex_neighbours(vid, &mut cache, &mut neighbours)?;
+        let (dir1, dir2) = principal_directions(mesh, &center, &normal, &neighbours)?;
+
+        result.push(VertexCurvature {
+            mean,
+            gaussian,
+            k1,
+            k2,
+            dir1,
+            dir2,
+            shape_index: shape_index(k1, k2),
+        });
+    }
+
+    Ok(result)
+}
+
+//------------------------------------------------------------------------------
+
+/// The corners of the face other than vid, in order of the face
+fn other_corners<P, M>(mesh: &M, fid: FId, vid: VId) -> Result<(Point3D, Point3D)>
+where
+    M: IsMesh<P, Face3>,
+    P: IsBuildable3D,
+{
+    let f = mesh
+        .face_vertex_ids(fid)
+        .ok_or(ErrorKind::IncorrectFaceID)?;
+    let (a, b) = if f.a == vid {
+        (f.b, f.c)
+    } else if f.b == vid {
+        (f.c, f.a)
+    } else {
+        (f.a, f.b)
+    };
+    let a = mesh.vertex(a).ok_or(ErrorKind::IncorrectVertexID)?;
+    let b = mesh.vertex(b).ok_or(ErrorKind::IncorrectVertexID)?;
+    Ok((Point3D::new_from(&a), Point3D::new_from(&b)))
+}
+
+/// Fits the second fundamental form to the normal curvatures towards the neighbours and returns its eigenvectors
+fn principal_directions<P, M>(
+    mesh: &M,
+    center: &Point3D,
+    normal: &Norm3D,
+    neighbours: &[VId],
+) -> Result<(Option<Norm3D>, Option<Norm3D>)>
+where
+    M: IsMesh<P, Face3>,
+    P: IsBuildable3D,
+{
+    if neighbours.len() < 3 {
+        return Ok((None, None));
+    }
+
+    // orthonormal basis of the tangent plane
+    let helper = if normal.x().abs() < 0.9 {
+        Norm3D::norm_x()
+    } else {
+        Norm3D::norm_y()
+    };
+    let e1: Point3D = cross(normal, &Point3D::new_from(&helper));
+    let e1 = Norm3D::new(e1)?;
+    let e2 = Norm3D::new(cross::<_, Point3D>(normal, &Point3D::new_from(&e1)))?;
+
+    // normal equations for u²a + 2uvb + v²c = kappa
+    let mut m = Matrix3::zeroes();
+    let mut rhs = [0.0; 3];
+    for vid in neighbours {
+        let p = Point3D::new_from(&mesh.vertex(*vid).ok_or(ErrorKind::IncorrectVertexID)?);
+        let d = conn(center, &p);
+        let len_sqr = sqr_len(&d);
+        if len_sqr == 0.0 {
+            continue;
+        }
+        let kappa = -2.0 * d.dot(normal) / len_sqr;
+        let (u, v) = (d.dot(&e1), d.dot(&e2));
+        let len_tangent = (u * u + v * v).sqrt();
+        if len_tangent == 0.0 {
+            continue;
+        }
+        let (u, v) = (u / len_tangent, v / len_tangent);
+        let row = [u * u, 2.0 * u * v, v * v];
+        for r in 0..3 {
+            for c in 0..3 {
+                m.data[r][c] += row[r] * row[c];
+            }
+            rhs[r] += row[r] * kappa;
+        }
+    }
+
+    // the neighbours must span enough directions
+    if m.determinant().abs() < 1e-9 {
+        return Ok((None, None));
+    }
+    let [a, b, c] = match m.solve(&rhs) {
+        None => return Ok((None, None)),
+        Some(x) => x,
+    };
+
+    // eigenvector of the larger eigenvalue of [[a, b], [b, c]]
+    let theta = 0.5 * (2.0 * b).atan2(a - c);
+    let (cos, sin) = (theta.cos(), theta.sin());
+    let dir1 = Point3D::new_from(&e1) * cos + Point3D::new_from(&e2) * sin;
+    let dir2 = Point3D::new_from(&e2) * cos - Point3D::new_from(&e1) * sin;
+
+    Ok((Norm3D::new(dir1).ok(), Norm3D::new(dir2).ok()))
+}
+
+fn shape_index(k1: f64, k2: f64) -> f64 {
+    if k1 == 0.0 && k2 == 0.0 {
+        return 0.0;
+    }
+    2.0 / PI * (k1 + k2).atan2(k1 - k2)
+}
+
+fn sqr_len(p: &Point3D) -> f64 {
+    p.x * p.x + p.y * p.y + p.z * p.z
+}
@@ -0,0 +1,578 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Algorithm to reduce the number of faces of a mesh by collapsing edges, using quadric error metrics
+
+use crate::*;
+
+use std::{cmp::Ordering, collections::BinaryHeap, marker::PhantomData};
+
+//------------------------------------------------------------------------------
+
+/// Algorithm to reduce the number of faces of a mesh by collapsing edges, using quadric error metrics (Garland and Heckbert)
+/// Collapses edges until the mesh has at most max_faces faces or the next collapse would introduce an error above max_error
+/// The error is the squared distance of a vertex to the planes of its original faces
+/// If preserve_boundary is set, vertices on the boundary are never moved, otherwise collapses moving the boundary are penalized
+/// Collapses which would flip face normals or result in non-manifold topology are skipped
+/// Vertex attributes are interpolated along the collapsed edges, the remaining faces keep theirs
+pub fn decimate_mesh<P, M>(
+    mesh: &M,
+    max_faces: usize,
+    max_error: Option<NonNegative>,
+    preserve_boundary: bool,
+) -> Result<M>
+where
+    M: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+    P: IsBuildable3D,
+{
+    let mut state = DecimationState::new(mesh)?;
+    state.init_quadrics(preserve_boundary);
+    state.init_candidates();
+
+    let max_error = max_error.map(|x| *x);
+
+    while state.n_faces > max_faces {
+        let candidate = match state.candidates.pop() {
+            None => break,
+            Some(x) => x,
+        };
+        if !state.is_up_to_date(&candidate) {
+            continue;
+        }
+        if let Some(max_error) = max_error {
+            if candidate.cost > max_error {
+                break;
+            }
+        }
+        if !state.can_collapse(candidate.keep, candidate.remove, &candidate.target) {
+            continue;
+        }
+        state.collapse(candidate.keep, candidate.remove, candidate.target);
+    }
+
+    state.into_mesh(mesh)
+}
+
+//------------------------------------------------------------------------------
+
+/// Symmetric 4x4 matrix stored as aa, ab, ac, ad, bb, bc, bd, cc, cd, dd
+#[derive(Clone, Copy, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    /// Quadric of the plane a*x + b*y + c*z + d = 0, scaled by weight
+    fn plane(a: f64, b: f64, c: f64, d: f64, weight: f64) -> Self {
+        Quadric([
+            weight * a * a,
+            weight * a * b,
+            weight * a * c,
+            weight * a * d,
+            weight * b * b,
+            weight * b * c,
+            weight * b * d,
+            weight * c * c,
+            weight * c * d,
+            weight * d * d,
+        ])
+    }
+
+    fn add(&mut self, other: &Self) {
+        for (x, y) in self.0.iter_mut().zip(other.0.iter()) {
+            *x += *y
+        }
+    }
+
+    fn sum(&self, other: &Self) -> Self {
+        let mut result = *self;
+        result.add(other);
+        result
+    }
+
+    fn error(&self, p: &Point3D) -> f64 {
+        let q = &self.0;
+        let (x, y, z) = (p.x, p.y, p.z);
+        q[0] * x * x
+            + 2.0 * q[1] * x * y
+            + 2.0 * q[2] * x * z
+            + 2.0 * q[3] * x
+            + q[4] * y * y
+            + 2.0 * q[5] * y * z
+            + 2.0 * q[6] * y
+            + q[7] * z * z
+            + 2.0 * q[8] * z
+            + q[9]
+    }
+
+    /// The position with minimal error, None if it can't be determined uniquely
+    fn optimum(&self) -> Option<Point3D> {
+        let q = &self.0;
+        let m = Matrix3 {
+            data: [[q[0], q[1], q[2]], [q[1], q[4], q[5]], [q[2], q[5], q[7]]],
+        };
+
+        let det = m.determinant();
+        let scale = q[0].abs() + q[4].abs() + q[7].abs();
+        if det.abs() <= 1e-12 * scale * scale * scale || det.abs() < f64::MIN_POSITIVE {
+            return None;
+        }
+
+        let result = m.solve(&[-q[3], -q[6], -q[8]])?;
+        Some(Point3D::new(result[0], result[1], result[2]))
+    }
+}
+
+//------------------------------------------------------------------------------
+
+struct Candidate {
+    cost: f64,
+    keep: usize,
+    remove: usize,
+    stamp_keep: usize,
+    stamp_remove: usize,
+    target: Point3D,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    // reversed, so the BinaryHeap returns the cheapest collapse first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .partial_cmp(&self.cost)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+struct DecimationState<P> {
+    positions: Vec<Point3D>,
+    phantom: PhantomData<P>,
+    faces: Vec<[usize; 3]>,
+    face_alive: Vec<bool>,
+    vertex_faces: Vec<Vec<usize>>,
+    vertex_alive: Vec<bool>,
+    locked: Vec<bool>,
+    on_boundary: Vec<bool>,
+    stamps: Vec<usize>,
+    quadrics: Vec<Quadric>,
+    candidates: BinaryHeap<Candidate>,
+    n_faces: usize,
+    /// The weighted input vertices each vertex was merged from, only tracked if there are vertex attributes
+    sources: Option<Vec<Vec<(VId, f64)>>>,
+}
+
+impl<P> DecimationState<P>
+where
+    P: IsBuildable3D,
+{
+    fn new<M>(mesh: &M) -> Result<Self>
+    where
+        M: IsMesh<P, Face3>,
+    {
+        let nv = mesh.num_vertices();
+        let nf = mesh.num_faces();
+
+        let mut positions = Vec::with_capacity(nv);
+        for i in 0..nv {
+            let v = mesh.vertex(VId(i)).ok_or(ErrorKind::IncorrectVertexID)?;
+            positions.push(Point3D::new_from(&v));
+        }
+
+        let mut faces = Vec::with_capacity(nf);
+        let mut vertex_faces = vec![Vec::new(); nv];
+        for i in 0..nf {
+            let f = mesh
+                .face_vertex_ids(FId(i))
+                .ok_or(ErrorKind::IncorrectFaceID)?;
+            let f = [f.a.0, f.b.0, f.c.0];
+            for v in f.iter() {
+                vertex_faces[*v].push(i);
+            }
+            faces.push(f);
+        }
+
+        Ok(Self {
+            positions,
+            phantom: PhantomData,
+            face_alive: vec![true; nf],
+            faces,
+            vertex_faces,
+            vertex_alive: vec![true; nv],
+            locked: vec![false; nv],
+            on_boundary: vec![false; nv],
+            stamps: vec![0; nv],
+            quadrics: vec![Quadric::default(); nv],
+            candidates: BinaryHeap::new(),
+            sources: mesh
+                .vertex_attributes()
+                .map(|_| (0..nv).map(|i| vec![(VId(i), 1.0)]).collect()),
+            n_faces: nf,
+        })
+    }
+
+    fn init_quadrics(&mut self, preserve_boundary: bool) {
+        for f in 0..self.faces.len() {
+            let [a, b, c] = self.faces[f];
+            let (pa, pb, pc) = (&self.positions[a], &self.positions[b], &self.positions[c]);
+            let n = cross(&conn(pa, pb), &conn(pa, pc));
+            let double_area = *n.abs();
+            if double_area == 0.0 {
+                continue;
+            }
+            let (nx, ny, nz) = (n.x / double_area, n.y / double_area, n.z / double_area);
+            let d = -(nx * pa.x + ny * pa.y + nz * pa.z);
+            let q = Quadric::plane(nx, ny, nz, d, 0.5 * double_area);
+            for v in self.faces[f].iter() {
+                self.quadrics[*v].add(&q);
+            }
+        }
+
+        // boundary edges are those used by a single face
+        let mut boundary_edges = Vec::new();
+        for f in 0..self.faces.len() {
+            for k in 0..3 {
+                let a = self.faces[f][k];
+                let b = self.faces[f][(k + 1) % 3];
+                if self.count_faces_with_edge(a, b) == 1 {
+                    boundary_edges.push((f, a, b));
+                }
+            }
+        }
+
+        for (f, a, b) in boundary_edges {
+            self.on_boundary[a] = true;
+            self.on_boundary[b] = true;
+            if preserve_boundary {
+                self.locked[a] = true;
+                self.locked[b] = true;
+                continue;
+            }
+
+            // plane perpendicular to the face containing the edge, heavily weighted
+            let [fa, fb, fc] = self.faces[f];
+            let face_normal = cross(
+                &conn(&self.positions[fa], &self.positions[fb]),
+                &conn(&self.positions[fa], &self.positions[fc]),
+            );
+            let edge = conn(&self.positions[a], &self.positions[b]);
+            let n = cross(&edge, &face_normal);
+            let len = *n.abs();
+            if len == 0.0 {
+                continue;
+            }
+            let (nx, ny, nz) = (n.x / len, n.y / len, n.z / len);
+            let pa = &self.positions[a];
+            let d = -(nx * pa.x + ny * pa.y + nz * pa.z);
+            let weight = BOUNDARY_WEIGHT * edge.abs().powi(2);
+            let q = Quadric::plane(nx, ny, nz, d, weight);
+            self.quadrics[a].add(&q);
+            self.quadrics[b].add(&q);
+        }
+    }
+
+    fn init_candidates(&mut self) {
+        for f in 0..self.faces.len() {
+            for k in 0..3 {
+                let a = self.faces[f][k];
+                let b = self.faces[f][(k + 1) % 3];
+                // each edge only once (if shared by two faces)
+                if a < b || self.count_faces_with_edge(a, b) == 1 {
+                    self.push_candidate(a, b);
+                }
+            }
+        }
+    }
+
+    fn count_faces_with_edge(&self, a: usize, b: usize) -> usize {
+        self.vertex_faces[a]
+            .as_slice()
+            .iter()
+            .filter(|f| self.face_alive[**f] && self.faces[**f].contains(&b))
+            .count()
+    }
+
+    fn push_candidate(&mut self, a: usize, b: usize) {
+        // locked vertices must stay, if both are locked the edge can't be collapsed
+        let (keep, remove) = match (self.locked[a], self.locked[b]) {
+            (true, true) => return,
+            (true, false) => (a, b),
+            (false, true) => (b, a),
+            (false, false) => (a, b),
+        };
+
+        let q = self.quadrics[keep].sum(&self.quadrics[remove]);
+        let (pk, pr) = (&self.positions[keep], &self.positions[remove]);
+
+        let target = if self.locked[keep] {
+            pk.clone()
+        } else {
+            let mid = Point3D::new(
+                0.5 * (pk.x + pr.x),
+                0.5 * (pk.y + pr.y),
+                0.5 * (pk.z + pr.z),
+            );
+            let mut options = vec![pk.clone(), pr.clone(), mid];
+            if let Some(optimum) = q.optimum() {
+                options.push(optimum);
+            }
+            // unwrap fine, since options not empty
+            options
+                .into_iter()
+                .min_by(|x, y| {
+                    q.error(x)
+                        .partial_cmp(&q.error(y))
+                        .unwrap_or(Ordering::Equal)
+                })
+                .unwrap()
+        };
+
+        self.candidates.push(Candidate {
+            cost: q.error(&target).max(0.0),
+            keep,
+            remove,
+            stamp_keep: self.stamps[keep],
+            stamp_remove: self.stamps[remove],
+            target,
+        });
+    }
+
+    fn is_up_to_date(&self, c: &Candidate) -> bool {
+        self.vertex_alive[c.keep]
+            && self.vertex_alive[c.remove]
+            && self.stamps[c.keep] == c.stamp_keep
+            && self.stamps[c.remove] == c.stamp_remove
+    }
+
+    fn neighbours(&self, v: usize) -> Vec<usize> {
+        let mut result = Vec::new();
+        for f in &self.vertex_faces[v] {
+            for x in self.faces[*f].iter() {
+                if *x != v {
+                    result.push(*x);
+                }
+            }
+        }
+        result.sort();
+        result.dedup();
+        result
+    }
+
+    fn can_collapse(&self, keep: usize, remove: usize, target: &Point3D) -> bool {
+        let shared_faces = self.count_faces_with_edge(keep, remove);
+        if shared_faces == 0 {
+            return false;
+        }
+
+        // collapsing an interior edge connecting two boundary vertices would pinch the mesh
+        if shared_faces == 2 && self.on_boundary[keep] && self.on_boundary[remove] {
+            return false;
+        }
+
+        // link condition, the only common neighbours may be the ones opposite to the edge
+        let nk = self.neighbours(keep);
+        let nr = self.neighbours(remove);
+        let common = nk
+            .into_iter()
+            .filter(|x| nr.binary_search(x).is_ok())
+            .collect::<Vec<_>>();
+        if common.len() != shared_faces {
+            return false;
+        }
+
+        // the opposite vertices lose an edge, interior vertices with less than three edges would fold the mesh
+        if common
+            .into_iter()
+            .any(|x| !self.on_boundary[x] && self.neighbours(x).len() <= 3)
+        {
+            return false;
+        }
+
+        // normals must not flip and faces must not degenerate
+        for (moved, other) in [(keep, remove), (remove, keep)].iter() {
+            for f in &self.vertex_faces[*moved] {
+                let face = self.faces[*f];
+                if face.contains(other) {
+                    continue;
+                }
+                let before = self.face_normal_with(&face, *moved, &self.positions[*moved]);
+                let after = self.face_normal_with(&face, *moved, target);
+
+                let len_before = *before.abs();
+                let len_after = *after.abs();
+                if len_after <= 1e-12 * len_before {
+                    return false;
+                }
+                let dot = before.x * after.x + before.y * after.y + before.z * after.z;
+                if dot < MIN_NORMAL_COS * len_before * len_after {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    fn face_normal_with(&self, face: &[usize; 3], v: usize, p: &Point3D) -> Point3D {
+        let pos = |i: usize| {
+            if face[i] == v {
+                p
+            } else {
+                &self.positions[face[i]]
+            }
+        };
+        cross(&conn(pos(0), pos(1)), &conn(pos(0), pos(2)))
+    }
+
+    fn collapse(&mut self, keep: usize, remove: usize, target: Point3D) {
+        let faces_remove = std::mem::take(&mut self.vertex_faces[remove]);
+
+        for f in faces_remove {
+            if self.faces[f].contains(&keep) {
+                // face degenerates, remove it from all its vertices
+                self.face_alive[f] = false;
+                self.n_faces -= 1;
+                for v in self.faces[f].iter() {
+                    if *v != remove {
+                        self.vertex_faces[*v].retain(|x| *x != f);
+                    }
+                }
+            } else {
+                for v in self.faces[f].iter_mut() {
+                    if *v == remove {
+                        *v = keep;
+                    }
+                }
+                self.vertex_faces[keep].push(f);
+            }
+        }
+
+        if let Some(sources) = self.sources.as_mut() {
+            // interpolate by the position of the target along the edge
+            let (pk, pr) = (&self.positions[keep], &self.positions[remove]);
+            let edge = conn(pk, pr);
+            let length = edge.dot(&edge);
+            let t = if length > 0.0 {
+                (conn(pk, &target).dot(&edge) / length).clamp(0.0, 1.0)
+            } else {
+                0.5
+            };
+            let removed = std::mem::take(&mut sources[remove]);
+            let kept = &mut sources[keep];
+            for (_, w) in kept.iter_mut() {
+                *w *= 1.0 - t;
+            }
+            for (v, w) in removed {
+                match kept.iter_mut().find(|(x, _)| *x == v) {
+                    Some((_, x)) => *x += t * w,
+                    None => kept.push((v, t * w)),
+                }
+            }
+        }
+
+        self.positions[keep] = target;
+        self.vertex_alive[remove] = false;
+        let q = self.quadrics[remove];
+        self.quadrics[keep].add(&q);
+        self.on_boundary[keep] = self.on_boundary[keep] || self.on_boundary[remove];
+        self.stamps[keep] += 1;
+
+        for n in self.neighbours(keep) {
+            self.stamps[n] += 1;
+        }
+        for n in self.neighbours(keep) {
+            self.push_candidate(keep, n);
+            // the neighbour's other edges changed their stamp as well
+            for nn in self.neighbours(n) {
+                if nn != keep {
+                    self.push_candidate(n, nn);
+                }
+            }
+        }
+    }
+
+    fn into_mesh<M>(self, source: &M) -> Result<M>
+    where
+        M: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+    {
+        let mut result = M::default();
+        result.reserve_faces(self.n_faces);
+
+        let mut new_ids = vec![None; self.positions.len()];
+        let positions = self.positions;
+
+        for (f, face) in self.faces.iter().enumerate() {
+            if !self.face_alive[f] {
+                continue;
+            }
+            let mut ids = [VId(0); 3];
+            for (id, v) in ids.iter_mut().zip(face.iter()) {
+                *id = match new_ids[*v] {
+                    Some(x) => x,
+                    None => {
+                        let weights = match self.sources.as_ref() {
+                            Some(sources) => sources[*v].as_slice(),
+                            None => &[],
+                        };
+                        let x = result.add_vertex_derived(
+                            P::new_from(&positions[*v]),
+                            source.vertex_attributes(),
+                            weights,
+                        )?;
+                        new_ids[*v] = Some(x);
+                        x
+                    }
+                };
+            }
+            result.try_add_connection_derived(
+                ids[0],
+                ids[1],
+                ids[2],
+                source.face_attributes(),
+                FId(f),
+            )?;
+        }
+
+        Ok(result)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Weight of the planes used to penalize moving the boundary
+const BOUNDARY_WEIGHT: f64 = 1000.0;
+
+/// Minimum cosine between a face's normal before and after a collapse
+const MIN_NORMAL_COS: f64 = 0.2;
@@ -43,3 +43,107 @@ where
 {
     (p.x() - q.x()).powi(2) + (p.y() - q.y()).powi(2) + (p.z() - q.z()).powi(2)
 }
+
+/// Returns the squared distance between the point and the triangle
+pub fn sqr_dist_point_triangle_3d<P, Q>(p: &P, a: &Q, b: &Q, c: &Q) -> f64
+where
+    P: Is3D,
+    Q: Is3D,
+{
+    sqr_dist_3d(p, &closest_point_on_triangle_3d(p, a, b, c))
+}
+
+/// Returns the distance between the point and the triangle
+pub fn dist_point_triangle_3d<P, Q>(p: &P, a: &Q, b: &Q, c: &Q) -> f64
+where
+    P: Is3D,
+    Q: Is3D,
+{
+    sqr_dist_point_triangle_3d(p, a, b, c).sqrt()
+}
+
+/// Returns the point of the triangle closest to p
+pub fn closest_point_on_triangle_3d<P, Q>(p: &P, a: &Q, b: &Q, c: &Q) -> Point3D
+where
+    P: Is3D,
+    Q: Is3D,
+{
+    let (p, a, b, c) = (
+        Point3D::new_from(p),
+        Point3D::new_from(a),
+        Point3D::new_from(b),
+        Point3D::new_from(c),
+    );
+    let ab = conn(&a, &b);
+    let ac = conn(&a, &c);
+    let at = |s: f64, t: f64| {
+        Point3D::new(
+            a.x + s * ab.x + t * ac.x,
+            a.y + s * ab.y + t * ac.y,
+            a.z + s * ab.z + t * ac.z,
+        )
+    };
+
+    // the Voronoi regions of the corners, edges and face, see Ericson, Real-Time Collision Detection
+    let ap = conn(&a, &p);
+    let (d1, d2) = (ab.dot(&ap), ac.dot(&ap));
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+
+    let bp = conn(&b, &p);
+    let (d3, d4) = (ab.dot(&bp), ac.dot(&bp));
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+
+    let cp = conn(&c, &p);
+    let (d5, d6) = (ab.dot(&cp), ac.dot(&cp));
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return at(d1 / (d1 - d3), 0.0);
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return at(0.0, d2 / (d2 - d6));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        let t = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return at(1.0 - t, t);
+    }
+
+    let sum = va + vb + vc;
+    if sum <= 0.0 {
+        // degenerate triangle, the closest point is on one of its edges
+        return [(&a, &b), (&b, &c), (&c, &a)]
+            .iter()
+            .map(|(u, v)| closest_point_on_segment_3d(&p, u, v))
+            .min_by(|x, y| {
+                sqr_dist_3d(&p, x)
+                    .partial_cmp(&sqr_dist_3d(&p, y))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap_or(a);
+    }
+    at(vb / sum, vc / sum)
+}
+
+//------------------------------------------------------------------------------
+
+fn closest_point_on_segment_3d(p: &Point3D, a: &Point3D, b: &Point3D) -> Point3D {
+    let ab = conn(a, b);
+    let length = ab.dot(&ab);
+    let t = if length == 0.0 {
+        0.0
+    } else {
+        (ab.dot(&conn(a, p)) / length).clamp(0.0, 1.0)
+    };
+    Point3D::new(a.x + t * ab.x, a.y + t * ab.y, a.z + t * ab.z)
+}
@@ -0,0 +1,533 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! DynamicAABBTree3D, an incrementally updatable axis aligned bounding box tree in 3D for moving objects
+
+use crate::*;
+
+//------------------------------------------------------------------------------
+
+#[derive(Clone)]
+/// DynamicAABBTree3D, an incrementally updatable axis aligned bounding box tree in 3D for moving objects
+/// Leaves store bounding boxes enlarged by a margin, so small movements don't require any changes of the tree
+/// The tree is kept balanced via rotations
+pub struct DynamicAABBTree3D<HB>
+where
+    HB: HasBoundingBox3D,
+{
+    objects: Vec<Option<DynObject<HB>>>,
+    free_objects: Vec<usize>,
+    nodes: Vec<DynNode>,
+    free_nodes: Vec<usize>,
+    root: Option<usize>,
+    margin: NonNegative,
+    n_objects: usize,
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Clone)]
+struct DynObject<HB> {
+    data: HB,
+    leaf: usize,
+}
+
+#[derive(Clone)]
+struct DynNode {
+    bb: BoundingBox3D,
+    parent: Option<usize>,
+    height: usize,
+    kind: DynNodeKind,
+}
+
+#[derive(Clone, Copy)]
+enum DynNodeKind {
+    Leaf(OId),
+    Branch { left: usize, right: usize },
+}
+
+//------------------------------------------------------------------------------
+
+impl<HB> DynamicAABBTree3D<HB>
+where
+    HB: HasBoundingBox3D,
+{
+    /// Creates a new, empty tree. The leaves' bounding boxes will be enlarged by margin
+    pub fn new(margin: NonNegative) -> Self {
+        Self {
+            objects: Vec::new(),
+            free_objects: Vec::new(),
+            nodes: Vec::new(),
+            free_nodes: Vec::new(),
+            root: None,
+            margin,
+            n_objects: 0,
+        }
+    }
+    /// Number of stored objects
+    pub fn len(&self) -> usize {
+        self.n_objects
+    }
+    /// Whether no objects are stored
+    pub fn is_empty(&self) -> bool {
+        self.n_objects == 0
+    }
+    /// Height of the tree (0 if empty or a single object)
+    pub fn height(&self) -> usize {
+        self.root.map_or(0, |r| self.nodes[r].height)
+    }
+    /// Returns the object of the given id
+    pub fn get(&self, id: OId) -> Option<&HB> {
+        self.object(id).map(|x| &x.data)
+    }
+    /// Applies a function to each stored object
+    pub fn for_each(&self, f: &mut dyn FnMut(OId, &HB)) {
+        for (i, x) in self.objects.iter().enumerate() {
+            if let Some(x) = x {
+                f(OId(i), &x.data)
+            }
+        }
+    }
+    /// Inserts a new object, returning its id
+    pub fn insert(&mut self, data: HB) -> OId {
+        let id = match self.free_objects.pop() {
+            Some(i) => OId(i),
+            None => {
+                self.objects.push(None);
+                OId(self.objects.len() - 1)
+            }
+        };
+
+        let bb = self.fat(data.bounding_box());
+        let leaf = self.alloc_node(DynNode {
+            bb,
+            parent: None,
+            height: 0,
+            kind: DynNodeKind::Leaf(id),
+        });
+        self.insert_leaf(leaf);
+
+        self.objects[id.0] = Some(DynObject { data, leaf });
+        self.n_objects += 1;
+
+        id
+    }
+    /// Removes the object of the given id, returning it
+    pub fn remove(&mut self, id: OId) -> Result<HB> {
+        let object = self
+            .objects
+            .get_mut(id.0)
+            .and_then(|x| x.take())
+            .ok_or(ErrorKind::IncorrectObjectID)?;
+
+        self.remove_leaf(object.leaf);
+        self.free_node(object.leaf);
+        self.free_objects.push(id.0);
+        self.n_objects -= 1;
+
+        Ok(object.data)
+    }
+    /// Replaces the object of the given id, updating the tree if required
+    pub fn update(&mut self, id: OId, data: HB) -> Result<()> {
+        let object = self
+            .objects
+            .get_mut(id.0)
+            .and_then(|x| x.as_mut())
+            .ok_or(ErrorKind::IncorrectObjectID)?;
+
+        object.data = data;
+        let leaf = object.leaf;
+        self.refresh(leaf, id);
+
+        Ok(())
+    }
+    /// Moves the object of the given id, updating the tree if required
+    pub fn move_by(&mut self, id: OId, x: f64, y: f64, z: f64) -> Result<()>
+    where
+        HB: IsMovable3D,
+    {
+        let object = self
+            .objects
+            .get_mut(id.0)
+            .and_then(|x| x.as_mut())
+            .ok_or(ErrorKind::IncorrectObjectID)?;
+
+        object.data.move_by(x, y, z);
+        let leaf = object.leaf;
+        self.refresh(leaf, id);
+
+        Ok(())
+    }
+
+    pub fn for_each_intersection_candidate(&self, line: &Line3D, f: &mut dyn FnMut(OId, &HB)) {
+        self.for_each_hit(&|bb| intersection(line, bb).is_some(), f)
+    }
+
+    pub fn for_each_collision_candidate(&self, bb: &BoundingBox3D, f: &mut dyn FnMut(OId, &HB)) {
+        self.for_each_hit(&|x| x.collides_with(bb), f)
+    }
+
+    pub fn bb_colliding<'a>(&'a self, bb: &BoundingBox3D, result: &mut Vec<&'a HB>) {
+        self.for_each_leaf_hit(&|x| x.collides_with(bb), &mut |id| {
+            let x = self.get(id).unwrap(); // unwrap fine, since leaves always reference existing objects
+            if x.bounding_box().collides_with(bb) {
+                result.push(x)
+            }
+        })
+    }
+    /// Applies a function to each pair of objects with overlapping bounding boxes (each pair only once)
+    pub fn for_each_overlapping_pair(&self, f: &mut dyn FnMut(OId, OId)) {
+        for (i, x) in self.objects.iter().enumerate() {
+            let x = match x {
+                None => continue,
+                Some(x) => x,
+            };
+            let bb = x.data.bounding_box();
+            let fat = self.nodes[x.leaf].bb.clone();
+
+            self.for_each_leaf_hit(&|node_bb| node_bb.collides_with(&fat), &mut |other| {
+                if other.0 <= i {
+                    return;
+                }
+                // unwrap fine, since leaves always reference existing objects
+                if self.get(other).unwrap().bounding_box().collides_with(&bb) {
+                    f(OId(i), other)
+                }
+            });
+        }
+    }
+    /// Returns all pairs of objects whose colliders collide (each pair only once)
+    pub fn colliding_pairs(&self) -> Vec<(OId, OId)>
+    where
+        HB: HasColliders3D,
+    {
+        let mut result = Vec::new();
+        self.for_each_overlapping_pair(&mut |a, b| {
+            // unwraps fine, since ids of pairs are always valid
+            if self.get(a).unwrap().collides_with(self.get(b).unwrap()) {
+                result.push((a, b))
+            }
+        });
+        result
+    }
+
+    fn object(&self, id: OId) -> Option<&DynObject<HB>> {
+        self.objects.get(id.0).and_then(|x| x.as_ref())
+    }
+
+    fn for_each_hit(&self, is_hit: &dyn Fn(&BoundingBox3D) -> bool, f: &mut dyn FnMut(OId, &HB)) {
+        self.for_each_leaf_hit(is_hit, &mut |id| {
+            let x = self.get(id).unwrap(); // unwrap fine, since leaves always reference existing objects
+            if is_hit(&x.bounding_box()) {
+                f(id, x)
+            }
+        })
+    }
+
+    fn for_each_leaf_hit(&self, is_hit: &dyn Fn(&BoundingBox3D) -> bool, f: &mut dyn FnMut(OId)) {
+        let mut stack = match self.root {
+            None => return,
+            Some(root) => vec![root],
+        };
+
+        while let Some(i) = stack.pop() {
+            let node = &self.nodes[i];
+            if !is_hit(&node.bb) {
+                continue;
+            }
+            match node.kind {
+                DynNodeKind::Leaf(id) => f(id),
+                DynNodeKind::Branch { left, right } => {
+                    stack.push(right);
+                    stack.push(left);
+                }
+            }
+        }
+    }
+
+    // reinserts the leaf if the object left its fat bounding box
+    fn refresh(&mut self, leaf: usize, id: OId) {
+        // unwrap fine, since only called for existing objects
+        let bb = self.object(id).unwrap().data.bounding_box();
+        if encloses(&self.nodes[leaf].bb, &bb) {
+            return;
+        }
+
+        self.remove_leaf(leaf);
+        self.nodes[leaf].bb = self.fat(bb);
+        self.insert_leaf(leaf);
+    }
+
+    fn fat(&self, bb: BoundingBox3D) -> BoundingBox3D {
+        let m = *self.margin;
+        if m == 0.0 {
+            return bb;
+        }
+        let (min, max) = (bb.min_p(), bb.max_p());
+        BoundingBox3D::new(
+            &Point3D::new(min.x() - m, min.y() - m, min.z() - m),
+            &Point3D::new(max.x() + m, max.y() + m, max.z() + m),
+        )
+        .unwrap_or(bb)
+    }
+
+    fn alloc_node(&mut self, node: DynNode) -> usize {
+        match self.free_nodes.pop() {
+            Some(i) => {
+                self.nodes[i] = node;
+                i
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn free_node(&mut self, i: usize) {
+        self.free_nodes.push(i)
+    }
+
+    fn children(&self, i: usize) -> Option<(usize, usize)> {
+        match self.nodes[i].kind {
+            DynNodeKind::Leaf(_) => None,
+            DynNodeKind::Branch { left, right } => Some((left, right)),
+        }
+    }
+
+    fn replace_child(&mut self, parent: Option<usize>, old: usize, new: usize) {
+        match parent {
+            None => self.root = Some(new),
+            Some(p) => {
+                if let DynNodeKind::Branch { left, right } = self.nodes[p].kind {
+                    self.nodes[p].kind = if left == old {
+                        DynNodeKind::Branch { left: new, right }
+                    } else {
+                        DynNodeKind::Branch { left, right: new }
+                    };
+                }
+            }
+        }
+        self.nodes[new].parent = parent;
+    }
+
+    fn insert_leaf(&mut self, leaf: usize) {
+        let mut index = match self.root {
+            None => {
+                self.nodes[leaf].parent = None;
+                self.root = Some(leaf);
+                return;
+            }
+            Some(root) => root,
+        };
+
+        // find the best sibling
+        let leaf_bb = self.nodes[leaf].bb.clone();
+        while let Some((left, right)) = self.children(index) {
+            let area = area_3d(&self.nodes[index].bb);
+            let combined_area = area_3d(&self.nodes[index].bb.combine(&leaf_bb));
+
+            let cost = 2.0 * combined_area;
+            let inheritance_cost = 2.0 * (combined_area - area);
+
+            let cost_left = self.descend_cost(left, &leaf_bb) + inheritance_cost;
+            let cost_right = self.descend_cost(right, &leaf_bb) + inheritance_cost;
+
+            if cost < cost_left && cost < cost_right {
+                break;
+            }
+
+            index = if cost_left < cost_right { left } else { right };
+        }
+
+        let sibling = index;
+        let old_parent = self.nodes[sibling].parent;
+        let new_parent = self.alloc_node(DynNode {
+            bb: self.nodes[sibling].bb.combine(&leaf_bb),
+            parent: old_parent,
+            height: self.nodes[sibling].height + 1,
+            kind: DynNodeKind::Branch {
+                left: sibling,
+                right: leaf,
+            },
+        });
+
+        self.replace_child(old_parent, sibling, new_parent);
+        self.nodes[sibling].parent = Some(new_parent);
+        self.nodes[leaf].parent = Some(new_parent);
+
+        self.fix_upwards(Some(new_parent));
+    }
+
+    fn descend_cost(&self, i: usize, bb: &BoundingBox3D) -> f64 {
+        let combined = area_3d(&self.nodes[i].bb.combine(bb));
+        match self.nodes[i].kind {
+            DynNodeKind::Leaf(_) => combined,
+            DynNodeKind::Branch { .. } => combined - area_3d(&self.nodes[i].bb),
+        }
+    }
+
+    fn remove_leaf(&mut self, leaf: usize) {
+        let parent = match self.nodes[leaf].parent {
+            None => {
+                self.root = None;
+                return;
+            }
+            Some(parent) => parent,
+        };
+
+        // unwrap fine, since parents are always branches
+        let (left, right) = self.children(parent).unwrap();
+        let sibling = if left == leaf { right } else { left };
+        let grand_parent = self.nodes[parent].parent;
+
+        self.replace_child(grand_parent, parent, sibling);
+        self.free_node(parent);
+        self.nodes[leaf].parent = None;
+
+        self.fix_upwards(grand_parent);
+    }
+
+    fn fix_upwards(&mut self, mut current: Option<usize>) {
+        while let Some(i) = current {
+            let i = self.balance(i);
+            // unwrap fine, since only branches are passed
+            let (left, right) = self.children(i).unwrap();
+            self.nodes[i].height = 1 + self.nodes[left].height.max(self.nodes[right].height);
+            self.nodes[i].bb = self.nodes[left].bb.combine(&self.nodes[right].bb);
+            current = self.nodes[i].parent;
+        }
+    }
+
+    // performs a rotation if the subtree of a is imbalanced, returns the new root of the subtree
+    fn balance(&mut self, a: usize) -> usize {
+        if self.nodes[a].height < 2 {
+            return a;
+        }
+        let (b, c) = match self.children(a) {
+            None => return a,
+            Some(x) => x,
+        };
+
+        let hb = self.nodes[b].height;
+        let hc = self.nodes[c].height;
+
+        if hc > hb + 1 {
+            self.rotate_up(a, c, b, false)
+        } else if hb > hc + 1 {
+            self.rotate_up(a, b, c, true)
+        } else {
+            a
+        }
+    }
+
+    // moves the child up to replace a, other is the remaining child of a
+    fn rotate_up(&mut self, a: usize, child: usize, other: usize, child_is_left: bool) -> usize {
+        // unwrap fine, since child is higher than other and therefore a branch
+        let (f, g) = self.children(child).unwrap();
+
+        let a_parent = self.nodes[a].parent;
+        self.replace_child(a_parent, a, child);
+        self.nodes[a].parent = Some(child);
+
+        // the higher grand child stays with child, the lower one moves to a
+        let (keep, moved) = if self.nodes[f].height > self.nodes[g].height {
+            (f, g)
+        } else {
+            (g, f)
+        };
+
+        self.nodes[child].kind = DynNodeKind::Branch {
+            left: a,
+            right: keep,
+        };
+        self.nodes[a].kind = if child_is_left {
+            DynNodeKind::Branch {
+                left: moved,
+                right: other,
+            }
+        } else {
+            DynNodeKind::Branch {
+                left: other,
+                right: moved,
+            }
+        };
+        self.nodes[moved].parent = Some(a);
+
+        self.nodes[a].bb = self.nodes[other].bb.combine(&self.nodes[moved].bb);
+        self.nodes[a].height = 1 + self.nodes[other].height.max(self.nodes[moved].height);
+        self.nodes[child].bb = self.nodes[a].bb.combine(&self.nodes[keep].bb);
+        self.nodes[child].height = 1 + self.nodes[a].height.max(self.nodes[keep].height);
+
+        child
+    }
+}
+
+//------------------------------------------------------------------------------
+
+impl<HB> HasBoundingBox3DMaybe for DynamicAABBTree3D<HB>
+where
+    HB: HasBoundingBox3D,
+{
+    fn bounding_box_maybe(&self) -> Option<BoundingBox3D> {
+        self.root.map(|r| self.nodes[r].bb.clone())
+    }
+}
+
+impl<HB> IsColliderContainer3D for DynamicAABBTree3D<HB>
+where
+    HB: HasColliders3D,
+{
+    fn any_element_collides_with_collider(&self, other: &dyn HasColliders3D) -> bool {
+        let mut any_collides = false;
+        self.for_each_collision_candidate(&other.bounding_box(), &mut |_, candidate| {
+            if !any_collides {
+                any_collides = candidate.collides_with(other);
+            }
+        });
+
+        any_collides
+    }
+
+    fn any_element_collides_with_bounding(&self, other: &dyn HasBoundingBox3D) -> bool {
+        let mut any_collides = false;
+        self.for_each_collision_candidate(&other.bounding_box(), &mut |_, _candidate| {
+            any_collides = true;
+        });
+
+        any_collides
+    }
+}
+
+//------------------------------------------------------------------------------
+
+fn encloses(outer: &BoundingBox3D, inner: &BoundingBox3D) -> bool {
+    let (omin, omax) = (outer.min_p(), outer.max_p());
+    let (imin, imax) = (inner.min_p(), inner.max_p());
+
+    omin.x() <= imin.x()
+        && omin.y() <= imin.y()
+        && omin.z() <= imin.z()
+        && omax.x() >= imax.x()
+        && omax.y() >= imax.y()
+        && omax.z() >= imax.z()
+}
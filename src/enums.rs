@@ -27,3 +27,34 @@ pub enum Precision {
     P32,
     P64,
 }
+
+/// Used to flag how the normals of faces are weighted when calculating the normals of vertices
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalWeighting {
+    /// Each face has the same weight
+    Uniform,
+    /// Faces are weighted by their area
+    Area,
+    /// Faces are weighted by their angle at the vertex
+    Angle,
+}
+
+/// Used to flag how the neighbours of a vertex are weighted by Laplacian operators
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaplacianWeighting {
+    /// Each neighbour has the same weight
+    Uniform,
+    /// Neighbours are weighted by the cotangents of the angles opposite to their edge
+    Cotangent,
+}
+
+/// Used to flag the boolean operation of meshes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BooleanOperation {
+    /// Everything within either of the meshes
+    Union,
+    /// Everything within both meshes
+    Intersection,
+    /// Everything within the first but not the second mesh
+    Difference,
+}
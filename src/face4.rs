@@ -0,0 +1,71 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Face4, a face defined by 4 indices (e.g. the quads of PolyMesh3D)
+
+use std::fmt;
+
+use crate::*;
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, PartialEq, PartialOrd, Clone, Eq, Ord, Hash)]
+/// Face4, a face defined by 4 indices (e.g. the quads of PolyMesh3D)
+pub struct Face4 {
+    pub a: VId,
+    pub b: VId,
+    pub c: VId,
+    pub d: VId,
+}
+
+impl Face4 {
+    /// Creates a new Face4 from 4 indices
+    pub fn new(a: VId, b: VId, c: VId, d: VId) -> Self {
+        Face4 { a, b, c, d }
+    }
+}
+
+impl IsTopologyUnit for Face4 {
+    fn n_vids() -> usize {
+        4
+    }
+
+    fn vid(&self, index: usize) -> Option<VId> {
+        match index {
+            0 => Some(self.a),
+            1 => Some(self.b),
+            2 => Some(self.c),
+            3 => Some(self.d),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Face4 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "({}, {}, {}, {})",
+            self.a.0, self.b.0, self.c.0, self.d.0
+        )
+    }
+}
@@ -0,0 +1,74 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! FaceN, a face defined by a variable number of indices (e.g. used for PolyMesh3D)
+
+use std::fmt;
+
+use crate::*;
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Eq, Ord, Hash)]
+/// FaceN, a face defined by a variable number of indices (e.g. used for PolyMesh3D)
+/// Since its size isn't known at compile time, it can't implement IsTopologyUnit
+pub struct FaceN {
+    pub vids: Vec<VId>,
+}
+
+impl FaceN {
+    /// Creates a new FaceN from its indices
+    pub fn new(vids: Vec<VId>) -> Self {
+        FaceN { vids }
+    }
+    /// Returns the number of indices
+    pub fn len(&self) -> usize {
+        self.vids.len()
+    }
+    /// Returns whether there are no indices
+    pub fn is_empty(&self) -> bool {
+        self.vids.is_empty()
+    }
+    /// Returns the vertex id of the nth element. None if index out of bounds
+    pub fn vid(&self, index: usize) -> Option<VId> {
+        self.vids.get(index).cloned()
+    }
+}
+
+impl From<Face3> for FaceN {
+    fn from(f: Face3) -> Self {
+        FaceN::new(vec![f.a, f.b, f.c])
+    }
+}
+
+impl From<Face4> for FaceN {
+    fn from(f: Face4) -> Self {
+        FaceN::new(vec![f.a, f.b, f.c, f.d])
+    }
+}
+
+impl fmt::Display for FaceN {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ids: Vec<String> = self.vids.iter().map(|v| v.0.to_string()).collect();
+        write!(f, "({})", ids.join(", "))
+    }
+}
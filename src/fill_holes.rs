@@ -0,0 +1,258 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Algorithm to close the holes of a mesh
+
+use crate::*;
+
+use std::cmp::Ordering;
+
+//------------------------------------------------------------------------------
+
+/// Algorithm to close the holes of a mesh
+/// Every boundary loop with at most max_hole_size edges is triangulated, minimizing the area of the patch
+/// If fair is set, the patches are refined to match the edge lengths of the loop and their inner vertices are smoothed
+/// Attributes of the mesh are kept, inner vertices of patches interpolate those of the loop and patch faces get default values
+pub fn fill_holes<P, MI, MO>(mesh: &MI, max_hole_size: usize, fair: bool) -> Result<MO>
+where
+    MI: IsSearchableMesh<P, Face3>,
+    MO: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+    P: IsBuildable3D,
+{
+    let nv = mesh.num_vertices();
+    let nf = mesh.num_faces();
+
+    let mut result = MO::default();
+    result.reserve_vertices(nv);
+    result.reserve_faces(nf);
+
+    for i in 0..nv {
+        result.add_vertex_derived(
+            mesh.vertex(VId(i)).ok_or(ErrorKind::IncorrectVertexID)?,
+            mesh.vertex_attributes(),
+            &[(VId(i), 1.0)],
+        )?;
+    }
+
+    for i in 0..nf {
+        let f = mesh
+            .face_vertex_ids(FId(i))
+            .ok_or(ErrorKind::IncorrectFaceID)?;
+        result.try_add_connection_derived(f.a, f.b, f.c, mesh.face_attributes(), FId(i))?;
+    }
+
+    let mut loops = Vec::new();
+    mesh.boundary_loops(&mut loops)?;
+
+    for boundary in loops {
+        if boundary.len() < 3 || boundary.len() > max_hole_size {
+            continue;
+        }
+
+        let mut positions = Vec::with_capacity(boundary.len());
+        for vid in boundary.iter() {
+            let v = mesh.vertex(*vid).ok_or(ErrorKind::IncorrectVertexID)?;
+            positions.push(Point3D::new_from(&v));
+        }
+
+        let mut patch = HolePatch::new(positions);
+        if fair {
+            patch.fair();
+        }
+
+        let mut ids = boundary.clone();
+        for (p, sources) in patch
+            .positions
+            .iter()
+            .zip(patch.sources.iter())
+            .skip(patch.n_fixed)
+        {
+            let weights = sources
+                .iter()
+                .map(|(i, w)| (boundary[*i], *w))
+                .collect::<Vec<_>>();
+            ids.push(result.add_vertex_derived(
+                P::new_from(p),
+                mesh.vertex_attributes(),
+                &weights,
+            )?);
+        }
+
+        for [a, b, c] in patch.triangles {
+            result.try_add_connection(ids[a], ids[b], ids[c])?;
+        }
+    }
+
+    Ok(result)
+}
+
+//------------------------------------------------------------------------------
+
+/// Triangulation of a single hole, the first n_fixed positions are the boundary loop
+struct HolePatch {
+    positions: Vec<Point3D>,
+    /// The weighted loop vertices each position was created from
+    sources: Vec<Vec<(usize, f64)>>,
+    n_fixed: usize,
+    triangles: Vec<[usize; 3]>,
+}
+
+impl HolePatch {
+    /// Minimum area triangulation of the loop, oriented opposite to it to match the neighbouring faces
+    fn new(positions: Vec<Point3D>) -> Self {
+        let n = positions.len();
+        let area = |a: usize, b: usize, c: usize| {
+            triangle_area(&positions[a], &positions[b], &positions[c])
+        };
+
+        // weights[i][j] is the minimal area to triangulate the polygon i, i+1, ..., j
+        let mut weights = vec![vec![0.0; n]; n];
+        let mut splits = vec![vec![0; n]; n];
+        for len in 2..n {
+            for i in 0..n - len {
+                let j = i + len;
+                // unwrap fine, since j > i + 1
+                let (m, w) = (i + 1..j)
+                    .map(|m| (m, weights[i][m] + weights[m][j] + area(i, m, j)))
+                    .min_by(|x, y| x.1.partial_cmp(&y.1).unwrap_or(Ordering::Equal))
+                    .unwrap();
+                weights[i][j] = w;
+                splits[i][j] = m;
+            }
+        }
+
+        let mut triangles = Vec::with_capacity(n - 2);
+        let mut stack = vec![(0, n - 1)];
+        while let Some((i, j)) = stack.pop() {
+            if j < i + 2 {
+                continue;
+            }
+            let m = splits[i][j];
+            triangles.push([j, m, i]);
+            stack.push((i, m));
+            stack.push((m, j));
+        }
+
+        Self {
+            positions,
+            sources: (0..n).map(|i| vec![(i, 1.0)]).collect(),
+            n_fixed: n,
+            triangles,
+        }
+    }
+
+    /// Splits large triangles until they match the edge lengths of the loop and smoothes the inner vertices
+    fn fair(&mut self) {
+        let n = self.n_fixed;
+        let mean_length = (0..n)
+            .map(|i| dist_3d(&self.positions[i], &self.positions[(i + 1) % n]))
+            .sum::<f64>()
+            / n as f64;
+        let target_area = 0.25 * 3.0f64.sqrt() * mean_length * mean_length;
+
+        for _ in 0..MAX_REFINEMENT_ROUNDS {
+            let mut triangles = Vec::with_capacity(self.triangles.len());
+            for [a, b, c] in self.triangles.iter().cloned() {
+                let (pa, pb, pc) = (&self.positions[a], &self.positions[b], &self.positions[c]);
+                if triangle_area(pa, pb, pc) <= target_area {
+                    triangles.push([a, b, c]);
+                    continue;
+                }
+                let center = Point3D::new(
+                    (pa.x + pb.x + pc.x) / 3.0,
+                    (pa.y + pb.y + pc.y) / 3.0,
+                    (pa.z + pb.z + pc.z) / 3.0,
+                );
+                let mut sources: Vec<(usize, f64)> = Vec::new();
+                for v in [a, b, c].iter() {
+                    for (i, w) in self.sources[*v].iter() {
+                        match sources.iter_mut().find(|(j, _)| j == i) {
+                            Some((_, x)) => *x += w / 3.0,
+                            None => sources.push((*i, w / 3.0)),
+                        }
+                    }
+                }
+                let m = self.positions.len();
+                self.positions.push(center);
+                self.sources.push(sources);
+                triangles.push([a, b, m]);
+                triangles.push([b, c, m]);
+                triangles.push([c, a, m]);
+            }
+            let done = triangles.len() == self.triangles.len();
+            self.triangles = triangles;
+            if done {
+                break;
+            }
+        }
+
+        let n_free = self.positions.len() - n;
+        if n_free == 0 {
+            return;
+        }
+
+        let mut neighbours = vec![Vec::new(); n_free];
+        for t in self.triangles.iter() {
+            for k in 0..3 {
+                let (v, other) = (t[k], t[(k + 1) % 3]);
+                if v >= n {
+                    neighbours[v - n].push(other);
+                }
+                if other >= n {
+                    neighbours[other - n].push(v);
+                }
+            }
+        }
+        for x in neighbours.iter_mut() {
+            x.sort();
+            x.dedup();
+        }
+
+        for _ in 0..FAIRING_ITERATIONS {
+            let smoothed = neighbours
+                .iter()
+                .map(|ns| {
+                    let mut sum = Point3D::default();
+                    for v in ns.as_slice() {
+                        sum = sum + self.positions[*v].clone();
+                    }
+                    sum / ns.len() as f64
+                })
+                .collect::<Vec<_>>();
+            for (i, p) in smoothed.into_iter().enumerate() {
+                self.positions[n + i] = p;
+            }
+        }
+    }
+}
+
+fn triangle_area(a: &Point3D, b: &Point3D, c: &Point3D) -> f64 {
+    0.5 * *cross(&conn(a, b), &conn(a, c)).abs()
+}
+
+//------------------------------------------------------------------------------
+
+/// Maximum number of times the patch triangles are split during fairing
+const MAX_REFINEMENT_ROUNDS: usize = 10;
+
+/// Number of umbrella smoothing iterations during fairing
+const FAIRING_ITERATIONS: usize = 50;
@@ -71,6 +71,34 @@ where
     }
 }
 
+#[cfg(feature = "rayon")]
+impl<S, P> FilterOutlier3D<S, P>
+where
+    P: Is3D + Send,
+    S: IsSphereSearchable<P> + Sync,
+{
+    /// Filters the positions in parallel (the cache used by is_allowed can't be shared between threads)
+    pub fn filter_par<PSearch, RA>(&self, ra: &RA, view: &mut View)
+    where
+        PSearch: Is3D + Sync,
+        RA: IsRandomAccessible<PSearch> + Sync,
+    {
+        let searchable = &self.searchable;
+        let search_distance = self.search_distance;
+        let min_neighbours = self.min_neighbours;
+
+        filter_view_par(ra.len(), view, &|i| {
+            has_min_neighbours(
+                searchable,
+                search_distance,
+                min_neighbours,
+                &ra[i],
+                &mut Vec::new(),
+            )
+        })
+    }
+}
+
 impl<S, P, PSearch> IsFilter<PSearch> for FilterOutlier3D<S, P>
 where
     P: Is3D,
@@ -78,20 +106,13 @@ where
     S: IsSphereSearchable<P>,
 {
     fn is_allowed(&self, p: &PSearch) -> bool {
-        let mut pts = self.cache.borrow_mut();
-        pts.clear();
-        self.searchable.in_sphere(
-            &Sphere {
-                center: Point3D {
-                    x: p.x(),
-                    y: p.y(),
-                    z: p.z(),
-                },
-                radius: self.search_distance,
-            },
-            &mut pts,
-        );
-        pts.len() >= self.min_neighbours
+        has_min_neighbours(
+            &self.searchable,
+            self.search_distance,
+            self.min_neighbours,
+            p,
+            &mut self.cache.borrow_mut(),
+        )
     }
 }
 
@@ -104,3 +125,33 @@ where
         self.search_distance *= factor;
     }
 }
+
+//------------------------------------------------------------------------------
+
+// takes the search buffer instead of self, since the RefCell cache isn't Sync
+fn has_min_neighbours<S, P, PSearch>(
+    searchable: &S,
+    search_distance: Positive,
+    min_neighbours: usize,
+    p: &PSearch,
+    pts: &mut Vec<P>,
+) -> bool
+where
+    P: Is3D,
+    PSearch: Is3D,
+    S: IsSphereSearchable<P>,
+{
+    pts.clear();
+    searchable.in_sphere(
+        &Sphere {
+            center: Point3D {
+                x: p.x(),
+                y: p.y(),
+                z: p.z(),
+            },
+            radius: search_distance,
+        },
+        pts,
+    );
+    pts.len() >= min_neighbours
+}
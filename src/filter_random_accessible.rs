@@ -49,6 +49,21 @@ where
     }
 }
 
+#[cfg(feature = "rayon")]
+impl<F, T> FilterRandomAccessible<F, T>
+where
+    F: IsFilter<T> + Sync,
+    T: Sync,
+{
+    /// Same as filter, but evaluates the elements in parallel
+    pub fn filter_par<RA>(&self, ra: &RA, view: &mut View)
+    where
+        RA: IsRandomAccessible<T> + Sync,
+    {
+        filter_view_par(ra.len(), view, &|i| self.filter.is_allowed(&ra[i]))
+    }
+}
+
 impl<F, T, RA> IsFilterRandomAccessible<RA, T> for FilterRandomAccessible<F, T>
 where
     F: IsFilter<T>,
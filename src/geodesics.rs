@@ -0,0 +1,344 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Geodesic distances and shortest paths on meshes
+
+use crate::*;
+
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+//------------------------------------------------------------------------------
+
+/// Calculates the length of the shortest path along the edges of the mesh from each vertex to the closest source
+/// Vertices which can't be reached have infinite distance
+pub fn dijkstra_distances<P, M>(mesh: &M, sources: &[VId]) -> Result<Vec<f64>>
+where
+    M: IsSearchableMesh<P, Face3>,
+    P: Is3D,
+{
+    let graph = EdgeGraph::new(mesh)?;
+    let (distances, _) = graph.dijkstra(sources, None)?;
+    Ok(distances)
+}
+
+/// Calculates the shortest path along the edges of the mesh, returning the positions of its vertices from start to end
+/// Error if there is no path
+pub fn dijkstra_path<P, M>(mesh: &M, start: VId, end: VId) -> Result<PointCloud3D<P>>
+where
+    M: IsSearchableMesh<P, Face3>,
+    P: Is3D,
+{
+    let graph = EdgeGraph::new(mesh)?;
+    let (distances, predecessors) = graph.dijkstra(&[start], Some(end))?;
+    if end.0 >= distances.len() || distances[end.0] == f64::INFINITY {
+        return Err(ErrorKind::NoPath);
+    }
+
+    let mut ids = vec![end.0];
+    while let Some(previous) = predecessors[ids[ids.len() - 1]] {
+        ids.push(previous);
+    }
+    ids.reverse();
+    polyline(mesh, &ids)
+}
+
+/// Calculates approximate geodesic distances from each vertex to the closest source via the heat method (Crane et al.)
+/// Heat diffused from the sources for a short time defines the directions of the distance gradient, the distances are then recovered by a Poisson equation
+/// The mesh should be manifold. Vertices which can't be reached have infinite distance
+pub fn heat_geodesic_distances<P, M>(mesh: &M, sources: &[VId]) -> Result<Vec<f64>>
+where
+    M: IsSearchableMesh<P, Face3>,
+    P: Is3D,
+{
+    let graph = EdgeGraph::new(mesh)?;
+    let (reachable, _) = graph.dijkstra(sources, None)?;
+    let positions = &graph.positions;
+    let n = positions.len();
+
+    let topology = MeshTopology::new(mesh)?;
+    let faces = &topology.faces;
+
+    // lumped masses and cotangent Laplacian, the time step is the squared mean edge length
+    let mut masses = vec![0.0; n];
+    let mut laplacian = SparseMatrix::new(n);
+    let mut sum_lengths = 0.0;
+    for [a, b, c] in faces.iter() {
+        let (pa, pb, pc) = (&positions[*a], &positions[*b], &positions[*c]);
+        let area = 0.5 * *cross::<_, Point3D>(&conn(pa, pb), &conn(pa, pc)).abs();
+        for v in [a, b, c].iter() {
+            masses[**v] += area / 3.0;
+        }
+        for (i, j, k) in [(a, b, c), (b, c, a), (c, a, b)].iter() {
+            let (pi, pj, pk) = (&positions[**i], &positions[**j], &positions[**k]);
+            laplacian.add_edge(**i, **j, 0.5 * cot(&conn(pk, pi), &conn(pk, pj)));
+            sum_lengths += dist_3d(pi, pj);
+        }
+    }
+    let time = if faces.is_empty() {
+        0.0
+    } else {
+        (sum_lengths / (3 * faces.len()) as f64).powi(2)
+    };
+
+    let mut heat_system = laplacian.clone();
+    heat_system.scale(time);
+    heat_system.add_diagonal(&masses);
+    let mut impulse = vec![0.0; n];
+    for s in sources.iter() {
+        impulse[s.0] = 1.0;
+    }
+    let heat = heat_system.solve(&impulse);
+
+    // divergence of the normalized negative heat gradient
+    let mut divergence = vec![0.0; n];
+    for [a, b, c] in faces.iter() {
+        let ids = [*a, *b, *c];
+        let ps = [&positions[*a], &positions[*b], &positions[*c]];
+        let normal: Point3D = cross(&conn(ps[0], ps[1]), &conn(ps[0], ps[2]));
+        let double_area = *normal.abs();
+        if double_area == 0.0 {
+            continue;
+        }
+
+        let mut gradient = Point3D::default();
+        for k in 0..3 {
+            let opposite = conn(ps[(k + 1) % 3], ps[(k + 2) % 3]);
+            let rotated: Point3D = cross(&normal, &opposite);
+            gradient = gradient + rotated * (heat[ids[k]] / (double_area * double_area));
+        }
+        let length = *gradient.abs();
+        if length == 0.0 {
+            continue;
+        }
+        let direction = gradient * (-1.0 / length);
+
+        for k in 0..3 {
+            let (pi, pj, pk) = (ps[k], ps[(k + 1) % 3], ps[(k + 2) % 3]);
+            let (e1, e2) = (conn(pi, pj), conn(pi, pk));
+            divergence[ids[k]] += 0.5
+                * (cot(&conn(pk, pi), &conn(pk, pj)) * e1.dot(&direction)
+                    + cot(&conn(pj, pi), &conn(pj, pk)) * e2.dot(&direction));
+        }
+    }
+
+    for x in divergence.iter_mut() {
+        *x = -*x;
+    }
+    let mut distances = laplacian.solve(&divergence);
+
+    // the distances of the sources of each connected component are zero
+    let (labels, n_components) = topology.face_components();
+    let component_of = |v: usize| {
+        topology
+            .vertex_faces
+            .get(v)
+            .and_then(|fs| fs.first())
+            .map(|f| labels[*f])
+    };
+    let mut shifts = vec![f64::INFINITY; n_components];
+    for s in sources.iter() {
+        if let Some(c) = component_of(s.0) {
+            shifts[c] = shifts[c].min(distances[s.0]);
+        }
+    }
+    for (v, d) in distances.iter_mut().enumerate() {
+        *d = match component_of(v) {
+            _ if reachable[v] == f64::INFINITY => f64::INFINITY,
+            _ if reachable[v] == 0.0 => 0.0,
+            Some(c) => (*d - shifts[c]).max(0.0),
+            None => f64::INFINITY,
+        };
+    }
+    Ok(distances)
+}
+
+/// Traces a path from start towards the closest source of the distances (as calculated by dijkstra_distances or heat_geodesic_distances)
+/// Each step moves to the neighbour minimizing its distance plus the length of the edge, stopping at a source or a local minimum
+/// Returns the positions of the path's vertices, error if start can't reach any source
+pub fn geodesic_path<P, M>(mesh: &M, distances: &[f64], start: VId) -> Result<PointCloud3D<P>>
+where
+    M: IsSearchableMesh<P, Face3>,
+    P: Is3D,
+{
+    if distances.len() != mesh.num_vertices() {
+        return Err(ErrorKind::DimensionsDontMatch);
+    }
+    if distances
+        .get(start.0)
+        .ok_or(ErrorKind::IncorrectVertexID)?
+        .is_infinite()
+    {
+        return Err(ErrorKind::NoPath);
+    }
+
+    let graph = EdgeGraph::new(mesh)?;
+    let mut ids = vec![start.0];
+    let mut current = start.0;
+    while distances[current] > 0.0 {
+        let next = graph.neighbours[current]
+            .iter()
+            .filter(|(u, _)| distances[*u] < distances[current])
+            .min_by(|(u, lu), (w, lw)| {
+                (distances[*u] + lu)
+                    .partial_cmp(&(distances[*w] + lw))
+                    .unwrap_or(Ordering::Equal)
+            });
+        match next {
+            None => break,
+            Some((u, _)) => {
+                current = *u;
+                ids.push(current);
+            }
+        }
+    }
+    polyline(mesh, &ids)
+}
+
+//------------------------------------------------------------------------------
+
+/// The vertices of a mesh and the lengths of the edges between them
+struct EdgeGraph {
+    positions: Vec<Point3D>,
+    neighbours: Vec<Vec<(usize, f64)>>,
+}
+
+impl EdgeGraph {
+    fn new<P, M>(mesh: &M) -> Result<Self>
+    where
+        M: IsSearchableMesh<P, Face3>,
+        P: Is3D,
+    {
+        let n = mesh.num_vertices();
+        let mut positions = Vec::with_capacity(n);
+        for i in 0..n {
+            let v = mesh.vertex(VId(i)).ok_or(ErrorKind::IncorrectVertexID)?;
+            positions.push(Point3D::new_from(&v));
+        }
+
+        let mut cache = Vec::new();
+        let mut vids = Vec::new();
+        let mut neighbours = Vec::with_capacity(n);
+        for i in 0..n {
+            vids.clear();
+            mesh.vertex_neighbours(VId(i), &mut cache, &mut vids)?;
+            neighbours.push(
+                vids.iter()
+                    .map(|u| (u.0, dist_3d(&positions[i], &positions[u.0])))
+                    .collect(),
+            );
+        }
+
+        Ok(Self {
+            positions,
+            neighbours,
+        })
+    }
+
+    /// The distances of the vertices to the closest source and their predecessors on the shortest paths, stopping early once end is reached
+    fn dijkstra(
+        &self,
+        sources: &[VId],
+        end: Option<VId>,
+    ) -> Result<(Vec<f64>, Vec<Option<usize>>)> {
+        let n = self.positions.len();
+        let mut distances = vec![f64::INFINITY; n];
+        let mut predecessors = vec![None; n];
+        let mut queue = BinaryHeap::new();
+
+        for s in sources.iter() {
+            if s.0 >= n {
+                return Err(ErrorKind::IncorrectVertexID);
+            }
+            distances[s.0] = 0.0;
+            queue.push(Candidate {
+                distance: 0.0,
+                vertex: s.0,
+            });
+        }
+
+        while let Some(Candidate { distance, vertex }) = queue.pop() {
+            if distance > distances[vertex] {
+                continue;
+            }
+            if end == Some(VId(vertex)) {
+                break;
+            }
+            for (u, length) in self.neighbours[vertex].iter() {
+                let d = distance + length;
+                if d < distances[*u] {
+                    distances[*u] = d;
+                    predecessors[*u] = Some(vertex);
+                    queue.push(Candidate {
+                        distance: d,
+                        vertex: *u,
+                    });
+                }
+            }
+        }
+        Ok((distances, predecessors))
+    }
+}
+
+//------------------------------------------------------------------------------
+
+struct Candidate {
+    distance: f64,
+    vertex: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    // reversed, so the BinaryHeap returns the closest vertex first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .distance
+            .partial_cmp(&self.distance)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+fn polyline<P, M>(mesh: &M, ids: &[usize]) -> Result<PointCloud3D<P>>
+where
+    M: IsMesh<P, Face3>,
+    P: Is3D,
+{
+    let mut result = PointCloud3D::new();
+    for id in ids.iter() {
+        result.push(mesh.vertex(VId(*id)).ok_or(ErrorKind::IncorrectVertexID)?);
+    }
+    Ok(result)
+}
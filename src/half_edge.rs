@@ -43,7 +43,8 @@ where
 {
     /// Creates a new HalfEdge3D for the given IsMesh3D
     /// This only stays valid if IMesh3D is not changed after creation
-    /// The mesh must be manifold
+    /// The mesh must be manifold and consistently oriented
+    /// Use diagnose_mesh to check this and split_non_manifold_vertices / orient_faces_consistently to repair arbitrary meshes
     pub fn new<T, M>(mesh: &M) -> Self
     where
         M: IsMesh<T, Face3>,
@@ -91,6 +92,11 @@ where
             }
         }
 
+        // vertices which aren't used by any face have no edges, but must still be valid ids
+        if vertices_start_edges.len() < n_vertices {
+            vertices_start_edges.resize(n_vertices, IC::with_capacity_and_support_for(0, n_edges));
+        }
+
         let mut result = HalfEdge {
             tails,
             twins,
@@ -112,7 +118,7 @@ where
             for originating_id in cache.iter() {
                 let _ = result.next(*originating_id).map(|candidate_id| {
                     if result.tails.get(candidate_id.0) == result.tails.get(i) {
-                        result.twins[i] = Some(candidate_id)
+                        result.twins[i] = Some(*originating_id)
                     }
                 });
             }
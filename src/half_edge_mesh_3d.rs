@@ -0,0 +1,580 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! HalfEdgeMesh3D, an editable mesh with half edge connectivity
+
+use crate::*;
+
+use fnv::FnvHashMap;
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+/// HalfEdgeMesh3D, an editable mesh with half edge connectivity
+/// As within HalfEdge, the half edge 3 * f + k starts at the k-th vertex of face f, next and prev are implied by this
+/// Twins are looked up via the vertices of the edges, so they stay consistent during all operations
+/// The mesh must stay manifold and consistently oriented, operations which would break this return an error
+/// Removing faces can leave a vertex with several separate fans of faces
+/// Removing a face or vertex moves the last face or vertex into its place (as Vec::swap_remove)
+pub struct HalfEdgeMesh3D<P> {
+    vertices: Vec<P>,
+    faces: Vec<[usize; 3]>,
+    vertex_faces: Vec<Vec<usize>>,
+    /// The half edges keyed by their tail and head
+    edges: FnvHashMap<(usize, usize), usize>,
+}
+
+impl<P> Default for HalfEdgeMesh3D<P> {
+    fn default() -> Self {
+        Self {
+            vertices: Vec::new(),
+            faces: Vec::new(),
+            vertex_faces: Vec::new(),
+            edges: FnvHashMap::default(),
+        }
+    }
+}
+
+impl<P> HalfEdgeMesh3D<P>
+where
+    P: Is3D + Clone,
+{
+    /// Creates a new HalfEdgeMesh3D from an IsMesh. Error if the mesh isn't manifold or consistently oriented
+    /// Use diagnose_mesh to check this and split_non_manifold_vertices / orient_faces_consistently to repair arbitrary meshes
+    pub fn new<M>(mesh: &M) -> Result<Self>
+    where
+        M: IsMesh<P, Face3>,
+    {
+        if !diagnose_mesh(mesh)?.is_valid_for_half_edge() {
+            return Err(ErrorKind::MeshNotManifold);
+        }
+
+        let mut result = Self::default();
+        result.vertices.reserve(mesh.num_vertices());
+        result.vertex_faces.reserve(mesh.num_vertices());
+        result.faces.reserve(mesh.num_faces());
+
+        for i in 0..mesh.num_vertices() {
+            result.push_vertex(mesh.vertex(VId(i)).ok_or(ErrorKind::IncorrectVertexID)?);
+        }
+        for i in 0..mesh.num_faces() {
+            let f = mesh
+                .face_vertex_ids(FId(i))
+                .ok_or(ErrorKind::IncorrectFaceID)?;
+            // the faces of a vertex might only be connected once all are added, so fans aren't checked
+            let f = result.checked_face(f.a, f.b, f.c)?;
+            result.faces.push(f);
+            result.link_face(i);
+        }
+        Ok(result)
+    }
+    /// Returns the edge from tail to head. None if there is none
+    pub fn edge_between(&self, tail: VId, head: VId) -> Option<EId> {
+        self.edges.get(&(tail.0, head.0)).map(|x| EId(*x))
+    }
+    /// Appends the faces of the vertex. Error if id invalid
+    pub fn faces_of_vertex(&self, vid: VId, result: &mut Vec<FId>) -> Result<()> {
+        let faces = self
+            .vertex_faces
+            .get(vid.0)
+            .ok_or(ErrorKind::IncorrectVertexID)?;
+        result.extend(faces.as_slice().iter().map(|f| FId(*f)));
+        Ok(())
+    }
+    /// Appends the vertices connected to the vertex via an edge. Sorts and dedups the result. Error if id invalid
+    pub fn neighbours(&self, vid: VId, result: &mut Vec<VId>) -> Result<()> {
+        let faces = self
+            .vertex_faces
+            .get(vid.0)
+            .ok_or(ErrorKind::IncorrectVertexID)?;
+        for f in faces {
+            let k = self.corner(*f, vid.0);
+            result.push(VId(self.faces[*f][(k + 1) % 3]));
+            result.push(VId(self.faces[*f][(k + 2) % 3]));
+        }
+        result.sort();
+        result.dedup();
+        Ok(())
+    }
+    /// Removes a face, the last face takes its id. Error if id invalid
+    pub fn remove_face(&mut self, fid: FId) -> Result<()> {
+        if fid.0 >= self.faces.len() {
+            return Err(ErrorKind::IncorrectFaceID);
+        }
+        let last = self.faces.len() - 1;
+        self.unlink_face(fid.0);
+        if fid.0 != last {
+            self.unlink_face(last);
+            self.faces.swap_remove(fid.0);
+            self.link_face(fid.0);
+        } else {
+            self.faces.pop();
+        }
+        Ok(())
+    }
+    /// Removes a vertex and all its faces, leaving a hole. The last vertex takes its id. Error if id invalid
+    pub fn remove_vertex(&mut self, vid: VId) -> Result<()> {
+        let mut faces = self
+            .vertex_faces
+            .get(vid.0)
+            .ok_or(ErrorKind::IncorrectVertexID)?
+            .clone();
+        // descending, so the removals don't move the faces still to be removed
+        faces.sort();
+        for f in faces.into_iter().rev() {
+            self.remove_face(FId(f))?;
+        }
+        self.swap_remove_vertex(vid.0);
+        Ok(())
+    }
+    /// Flips the edge, connecting the vertices opposite to it instead. Error if id invalid or the flip isn't possible
+    pub fn flip_edge(&mut self, eid: EId) -> Result<()> {
+        let (f0, a, b, c) = self.edge_face_corners(eid)?;
+        let twin = self.edge_twin(eid).ok_or(ErrorKind::InvalidMeshOperation)?;
+        let (f1, _, _, d) = self.edge_face_corners(twin)?;
+
+        if c == d || self.edges.contains_key(&(c, d)) || self.edges.contains_key(&(d, c)) {
+            return Err(ErrorKind::InvalidMeshOperation);
+        }
+
+        self.unlink_face(f0);
+        self.unlink_face(f1);
+        self.faces[f0] = [a, d, c];
+        self.faces[f1] = [d, b, c];
+        self.link_face(f0);
+        self.link_face(f1);
+        Ok(())
+    }
+    /// Splits the edge (and its twin) by inserting the vertex. Returns the id of the new vertex. Error if id invalid
+    pub fn split_edge(&mut self, eid: EId, vertex: P) -> Result<VId> {
+        let (f0, a, b, c) = self.edge_face_corners(eid)?;
+        let twin = match self.edge_twin(eid) {
+            None => None,
+            Some(t) => Some(self.edge_face_corners(t)?),
+        };
+
+        let m = self.push_vertex(vertex).0;
+
+        self.unlink_face(f0);
+        self.faces[f0] = [a, m, c];
+        self.link_face(f0);
+        self.faces.push([m, b, c]);
+        self.link_face(self.faces.len() - 1);
+
+        if let Some((f1, _, _, d)) = twin {
+            self.unlink_face(f1);
+            self.faces[f1] = [b, m, d];
+            self.link_face(f1);
+            self.faces.push([m, a, d]);
+            self.link_face(self.faces.len() - 1);
+        }
+
+        Ok(VId(m))
+    }
+    /// Splits the face into three by inserting the vertex. Returns the id of the new vertex. Error if id invalid
+    pub fn split_face(&mut self, fid: FId, vertex: P) -> Result<VId> {
+        let [a, b, c] = *self.faces.get(fid.0).ok_or(ErrorKind::IncorrectFaceID)?;
+        let m = self.push_vertex(vertex).0;
+
+        self.unlink_face(fid.0);
+        self.faces[fid.0] = [a, b, m];
+        self.link_face(fid.0);
+        self.faces.push([b, c, m]);
+        self.link_face(self.faces.len() - 1);
+        self.faces.push([c, a, m]);
+        self.link_face(self.faces.len() - 1);
+
+        Ok(VId(m))
+    }
+    /// Returns whether the edge can be collapsed without changing the topology of the mesh (link condition)
+    pub fn is_collapse_ok(&self, eid: EId) -> bool {
+        let (a, b) = match (self.edge_tail(eid), self.edge_head(eid)) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return false,
+        };
+        let n_faces = if self.edge_twin(eid).is_some() { 2 } else { 1 };
+
+        // an interior edge connecting two boundary vertices would pinch the mesh
+        if n_faces == 2 && self.is_boundary(a.0) && self.is_boundary(b.0) {
+            return false;
+        }
+
+        let mut na = Vec::new();
+        let mut nb = Vec::new();
+        // ids valid, since tail and head found
+        let _ = self.neighbours(a, &mut na);
+        let _ = self.neighbours(b, &mut nb);
+        let common = na
+            .into_iter()
+            .filter(|x| nb.binary_search(x).is_ok())
+            .collect::<Vec<_>>();
+        if common.len() != n_faces {
+            return false;
+        }
+
+        // the opposite vertices lose an edge, interior vertices need at least three
+        common.into_iter().all(|x| {
+            let mut n = Vec::new();
+            let _ = self.neighbours(x, &mut n);
+            self.is_boundary(x.0) || n.len() > 3
+        })
+    }
+    /// Collapses the edge, removing its head and moving its tail to the vertex. The last vertex takes the id of the removed head
+    /// Returns the (possibly changed) id of the remaining vertex. Error if id invalid or the collapse isn't possible (see is_collapse_ok)
+    pub fn collapse_edge(&mut self, eid: EId, vertex: P) -> Result<VId> {
+        if !self.is_collapse_ok(eid) {
+            return Err(ErrorKind::InvalidMeshOperation);
+        }
+        // unwraps fine, since is_collapse_ok
+        let a = self.edge_tail(eid).unwrap().0;
+        let b = self.edge_head(eid).unwrap().0;
+
+        let mut removed = self.vertex_faces[b]
+            .as_slice()
+            .iter()
+            .cloned()
+            .filter(|f| self.faces[*f].contains(&a))
+            .collect::<Vec<_>>();
+        removed.sort();
+        for f in removed.into_iter().rev() {
+            self.remove_face(FId(f))?;
+        }
+
+        for f in self.vertex_faces[b].clone() {
+            self.unlink_face(f);
+            for x in self.faces[f].iter_mut() {
+                if *x == b {
+                    *x = a;
+                }
+            }
+            self.link_face(f);
+        }
+
+        self.vertices[a] = vertex;
+        self.swap_remove_vertex(b);
+        Ok(VId(if a == self.vertices.len() { b } else { a }))
+    }
+    /// Returns the vertices and the vertex ids of the faces
+    pub fn into_parts(self) -> (Vec<P>, Vec<[usize; 3]>) {
+        (self.vertices, self.faces)
+    }
+
+    fn push_vertex(&mut self, vertex: P) -> VId {
+        self.vertices.push(vertex);
+        self.vertex_faces.push(Vec::new());
+        VId(self.vertices.len() - 1)
+    }
+
+    /// Error if a vertex id is invalid, duplicated or one of the face's edges already exists
+    fn checked_face(&self, a: VId, b: VId, c: VId) -> Result<[usize; 3]> {
+        let f = [a.0, b.0, c.0];
+        if f.iter().any(|x| *x >= self.vertices.len()) {
+            return Err(ErrorKind::IncorrectVertexID);
+        }
+        if a == b || a == c || b == c {
+            return Err(ErrorKind::FaceIDsNotUnique);
+        }
+        if (0..3).any(|k| self.edges.contains_key(&(f[k], f[(k + 1) % 3]))) {
+            return Err(ErrorKind::MeshNotManifold);
+        }
+        Ok(f)
+    }
+
+    fn push_face(&mut self, a: VId, b: VId, c: VId) -> Result<FId> {
+        let f = self.checked_face(a, b, c)?;
+        // a used vertex must be on the boundary and the face has to attach to one of its boundary edges
+        // otherwise it would get a second fan of faces (bowtie)
+        for k in 0..3 {
+            let (prev, v, next) = (f[(k + 2) % 3], f[k], f[(k + 1) % 3]);
+            if !self.vertex_faces[v].is_empty()
+                && !self.edges.contains_key(&(next, v))
+                && !self.edges.contains_key(&(v, prev))
+            {
+                return Err(ErrorKind::MeshNotManifold);
+            }
+        }
+
+        self.faces.push(f);
+        let fid = self.faces.len() - 1;
+        self.link_face(fid);
+        Ok(FId(fid))
+    }
+
+    fn ensure_edge(&self, eid: EId) -> Option<()> {
+        if eid.0 < 3 * self.faces.len() {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    /// Whether any edge originating at the vertex has no twin
+    fn is_boundary(&self, v: usize) -> bool {
+        self.vertex_faces[v].as_slice().iter().any(|f| {
+            let k = self.corner(*f, v);
+            let head = self.faces[*f][(k + 1) % 3];
+            !self.edges.contains_key(&(head, v))
+        })
+    }
+
+    /// The index of the vertex within the face
+    fn corner(&self, f: usize, v: usize) -> usize {
+        let face = &self.faces[f];
+        if face[0] == v {
+            0
+        } else if face[1] == v {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// The face of the edge, its tail, head and the vertex opposite to it
+    fn edge_face_corners(&self, eid: EId) -> Result<(usize, usize, usize, usize)> {
+        let f = eid.0 / 3;
+        let k = eid.0 % 3;
+        let face = self.faces.get(f).ok_or(ErrorKind::IncorrectEdgeID)?;
+        Ok((f, face[k], face[(k + 1) % 3], face[(k + 2) % 3]))
+    }
+
+    fn link_face(&mut self, f: usize) {
+        let face = self.faces[f];
+        for k in 0..3 {
+            self.vertex_faces[face[k]].push(f);
+            self.edges.insert((face[k], face[(k + 1) % 3]), 3 * f + k);
+        }
+    }
+
+    fn unlink_face(&mut self, f: usize) {
+        let face = self.faces[f];
+        for k in 0..3 {
+            self.vertex_faces[face[k]].retain(|x| *x != f);
+            self.edges.remove(&(face[k], face[(k + 1) % 3]));
+        }
+    }
+
+    /// Removes a vertex without faces, moving the last vertex into its place
+    fn swap_remove_vertex(&mut self, v: usize) {
+        let last = self.vertices.len() - 1;
+        let faces = self.vertex_faces[last].clone();
+        if v != last {
+            for f in &faces {
+                self.unlink_face(*f);
+                for x in self.faces[*f].iter_mut() {
+                    if *x == last {
+                        *x = v;
+                    }
+                }
+            }
+        }
+        self.vertices.swap_remove(v);
+        self.vertex_faces.swap_remove(v);
+        if v != last {
+            for f in faces {
+                self.link_face(f);
+            }
+        }
+    }
+}
+
+impl<P> IsMesh<P, Face3> for HalfEdgeMesh3D<P>
+where
+    P: Is3D + Clone,
+{
+    fn num_faces(&self) -> usize {
+        self.faces.len()
+    }
+
+    fn num_vertices(&self) -> usize {
+        self.vertices.len()
+    }
+
+    fn face_vertex_ids(&self, faceid: FId) -> Option<Face3> {
+        self.faces
+            .get(faceid.0)
+            .map(|[a, b, c]| Face3::new(VId(*a), VId(*b), VId(*c)))
+    }
+
+    fn face_vertices(&self, faceid: FId) -> Option<[P; 3]> {
+        self.faces.get(faceid.0).map(|[a, b, c]| {
+            [
+                self.vertices[*a].clone(),
+                self.vertices[*b].clone(),
+                self.vertices[*c].clone(),
+            ]
+        })
+    }
+
+    fn vertex(&self, vertexid: VId) -> Option<P> {
+        self.vertices.get(vertexid.0).cloned()
+    }
+}
+
+impl<P> IsSearchableMesh<P, Face3> for HalfEdgeMesh3D<P>
+where
+    P: Is3D + Clone,
+{
+    fn edges_of_face(&self, faceid: FId) -> Option<(EId, EId, EId)> {
+        if faceid.0 >= self.faces.len() {
+            return None;
+        }
+        Some((
+            EId(faceid.0 * 3),
+            EId(faceid.0 * 3 + 1),
+            EId(faceid.0 * 3 + 2),
+        ))
+    }
+
+    fn edges_originating_from_vertex(&self, vertexid: VId, result: &mut Vec<EId>) -> Result<()> {
+        let faces = self
+            .vertex_faces
+            .get(vertexid.0)
+            .ok_or(ErrorKind::IncorrectVertexID)?;
+        result.extend(
+            faces
+                .as_slice()
+                .iter()
+                .map(|f| EId(3 * f + self.corner(*f, vertexid.0))),
+        );
+        Ok(())
+    }
+
+    fn edges_ending_at_vertex(
+        &self,
+        vertexid: VId,
+        _cache: &mut Vec<EId>,
+        result: &mut Vec<EId>,
+    ) -> Result<()> {
+        let faces = self
+            .vertex_faces
+            .get(vertexid.0)
+            .ok_or(ErrorKind::IncorrectVertexID)?;
+        result.extend(
+            faces
+                .as_slice()
+                .iter()
+                .map(|f| EId(3 * f + (self.corner(*f, vertexid.0) + 2) % 3)),
+        );
+        Ok(())
+    }
+
+    fn edges_of_vertex(
+        &self,
+        vertexid: VId,
+        cache: &mut Vec<EId>,
+        result: &mut Vec<EId>,
+    ) -> Result<()> {
+        self.edges_originating_from_vertex(vertexid, result)?;
+        self.edges_ending_at_vertex(vertexid, cache, result)
+    }
+
+    fn edge_tail(&self, edgeid: EId) -> Option<VId> {
+        self.faces.get(edgeid.0 / 3).map(|f| VId(f[edgeid.0 % 3]))
+    }
+
+    fn edge_head(&self, edgeid: EId) -> Option<VId> {
+        self.faces
+            .get(edgeid.0 / 3)
+            .map(|f| VId(f[(edgeid.0 + 1) % 3]))
+    }
+
+    fn edge_next(&self, edgeid: EId) -> Option<EId> {
+        self.ensure_edge(edgeid)
+            .map(|_| EId(3 * (edgeid.0 / 3) + (edgeid.0 + 1) % 3))
+    }
+
+    fn edge_prev(&self, edgeid: EId) -> Option<EId> {
+        self.ensure_edge(edgeid)
+            .map(|_| EId(3 * (edgeid.0 / 3) + (edgeid.0 + 2) % 3))
+    }
+
+    fn edge_twin(&self, edgeid: EId) -> Option<EId> {
+        let tail = self.edge_tail(edgeid)?;
+        let head = self.edge_head(edgeid)?;
+        self.edge_between(head, tail)
+    }
+
+    fn edge_face(&self, edgeid: EId) -> Option<FId> {
+        self.ensure_edge(edgeid).map(|_| FId(edgeid.0 / 3))
+    }
+
+    fn is_vertex_on_boundary(&self, vertexid: VId, _cache: &mut Vec<EId>) -> Result<bool> {
+        if vertexid.0 >= self.vertices.len() {
+            return Err(ErrorKind::IncorrectVertexID);
+        }
+        Ok(self.is_boundary(vertexid.0))
+    }
+}
+
+impl<P> IsFaceEditableMesh<P, Face3> for HalfEdgeMesh3D<P>
+where
+    P: Is3D + Clone,
+{
+    fn add_face(&mut self, v1: P, v2: P, v3: P) -> FId {
+        let a = self.push_vertex(v1);
+        let b = self.push_vertex(v2);
+        let c = self.push_vertex(v3);
+        // unwrap fine, since the vertices are new
+        self.push_face(a, b, c).unwrap()
+    }
+
+    /// Error if the face would make the mesh non-manifold or inconsistently oriented
+    fn try_add_connection(&mut self, vid1: VId, vid2: VId, vid3: VId) -> Result<FId> {
+        self.push_face(vid1, vid2, vid3)
+    }
+
+    fn reserve_faces(&mut self, n: usize) {
+        self.faces.reserve(n)
+    }
+}
+
+impl<P> IsVertexEditableMesh<P, Face3> for HalfEdgeMesh3D<P>
+where
+    P: Is3D + Clone,
+{
+    fn add_vertex(&mut self, vertex: P) -> VId {
+        self.push_vertex(vertex)
+    }
+
+    fn change_vertex(&mut self, vid: VId, vertex: P) -> Result<()> {
+        let v = self
+            .vertices
+            .get_mut(vid.0)
+            .ok_or(ErrorKind::IncorrectVertexID)?;
+        *v = vertex;
+        Ok(())
+    }
+
+    fn reserve_vertices(&mut self, n: usize) {
+        self.vertices.reserve(n);
+        self.vertex_faces.reserve(n)
+    }
+}
+
+impl<P> HasBoundingBox3DMaybe for HalfEdgeMesh3D<P>
+where
+    P: Is3D,
+{
+    fn bounding_box_maybe(&self) -> Option<BoundingBox3D> {
+        BoundingBox3D::from_iterator(self.vertices.iter()).ok()
+    }
+}
@@ -48,12 +48,25 @@ where
     let (unduped, indices) = pack_dupes_indexed(duped_vertices.iter());
     let nf_new = indices.len() / 3;
 
+    // the attributes of merged vertices are averaged
+    let mut merged = vec![Vec::new(); unduped.len()];
+    for (corner, index) in indices.as_slice().iter().enumerate() {
+        let f = mesh.face_vertex_ids(FId(corner / 3)).unwrap(); // safe
+        let vid = f.vid(corner % 3).unwrap(); // safe
+        if !merged[*index].contains(&vid) {
+            merged[*index].push(vid);
+        }
+    }
+
     let mut result = M::default();
     result.reserve_vertices(unduped.len());
     result.reserve_faces(nf_new);
 
-    for x in unduped {
-        result.add_vertex(x);
+    let mut weights = Vec::new();
+    for (x, vids) in unduped.into_iter().zip(merged.iter()) {
+        weights.clear();
+        weights.extend(vids.iter().map(|vid| (*vid, 1.0 / vids.len() as f64)));
+        result.add_vertex_derived(x, mesh.vertex_attributes(), &weights)?;
     }
 
     for f in 0..nf_new {
@@ -61,7 +74,13 @@ where
         if a == b || a == c || b == c {
             continue;
         }
-        result.try_add_connection(VId(a), VId(b), VId(c))?;
+        result.try_add_connection_derived(
+            VId(a),
+            VId(b),
+            VId(c),
+            mesh.face_attributes(),
+            FId(f),
+        )?;
     }
 
     Ok(result)
@@ -26,7 +26,7 @@ use crate::*;
 
 use std::{
     fmt,
-    io::{BufRead, Error as ioError},
+    io::{BufRead, Error as ioError, Write},
     iter::FusedIterator,
     marker::PhantomData,
 };
@@ -182,6 +182,88 @@ where
 
 //------------------------------------------------------------------------------
 
+/// Saves an IsMesh3D in the .obj file format
+pub fn save_obj<M, P, W>(write: &mut W, mesh: &M) -> ObjResult<()>
+where
+    M: IsMesh<P, Face3>,
+    P: IsBuildable3D,
+    W: Write,
+{
+    for i in 0..mesh.num_vertices() {
+        let vertex = mesh.vertex(VId(i)).unwrap(); // safe since iterating num_vertices
+        write.write_all(("v ".to_string() + &vertex.to_str() + "\n").as_bytes())?;
+    }
+
+    //obj indexing starts at 1
+    for i in 0..mesh.num_faces() {
+        let face = mesh.face_vertex_ids(FId(i)).unwrap(); // safe since iterating num_faces
+        write.write_all(
+            format!("f {} {} {}\n", face.a.0 + 1, face.b.0 + 1, face.c.0 + 1).as_bytes(),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Saves an IsMesh3D in the .obj file format with additional vertex normals
+pub fn save_obj_with_normals<M, P, W>(write: &mut W, mesh: &M, normals: &[Norm3D]) -> ObjResult<()>
+where
+    M: IsMesh<P, Face3>,
+    P: IsBuildable3D,
+    W: Write,
+{
+    if mesh.num_vertices() != normals.len() {
+        return Err(ObjError::NormalArrayIncorrectLength);
+    }
+
+    for (i, normal) in normals.iter().enumerate() {
+        let vertex = mesh.vertex(VId(i)).unwrap(); // safe since iterating num_vertices
+        write.write_all(
+            ("v ".to_string() + &vertex.to_str() + "\nvn " + &normal.to_str() + "\n").as_bytes(),
+        )?;
+    }
+
+    //obj indexing starts at 1
+    for i in 0..mesh.num_faces() {
+        let face = mesh.face_vertex_ids(FId(i)).unwrap(); // safe since iterating num_faces
+        let (a, b, c) = (face.a.0 + 1, face.b.0 + 1, face.c.0 + 1);
+        write.write_all(format!("f {}//{} {}//{} {}//{}\n", a, a, b, b, c, c).as_bytes())?;
+    }
+
+    Ok(())
+}
+
+/// Saves a PolyMesh3D in the .obj file format, keeping its quads and n-gons
+pub fn save_obj_poly_mesh<P, ID, IC, W>(
+    write: &mut W,
+    mesh: &PolyMesh3D<P, ID, IC>,
+) -> ObjResult<()>
+where
+    P: IsBuildable3D + Clone,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+    W: Write,
+{
+    for i in 0..mesh.num_vertices() {
+        let vertex = mesh.vertex(VId(i)).unwrap(); // safe since iterating num_vertices
+        write.write_all(("v ".to_string() + &vertex.to_str() + "\n").as_bytes())?;
+    }
+
+    //obj indexing starts at 1
+    for i in 0..mesh.num_faces() {
+        let face = mesh.face_vertex_ids(FId(i)).unwrap(); // safe since iterating num_faces
+        let mut line = "f".to_string();
+        for vid in face.vids.iter() {
+            line += &format!(" {}", vid.0 + 1);
+        }
+        write.write_all((line + "\n").as_bytes())?;
+    }
+
+    Ok(())
+}
+
+//------------------------------------------------------------------------------
+
 /// Loads an IsMesh3D from the .obj file format
 pub fn load_obj_mesh<EM, P, R>(read: R, mesh: &mut EM) -> ObjIOResult<()>
 where
@@ -207,6 +289,37 @@ where
     Ok(())
 }
 
+/// Loads a PolyMesh3D from the .obj file format, keeping its quads and n-gons
+pub fn load_obj_poly_mesh<P, ID, IC, R>(
+    mut read: R,
+    mesh: &mut PolyMesh3D<P, ID, IC>,
+) -> ObjIOResult<()>
+where
+    P: IsBuildable3D + IsEditable3D + Clone,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+    R: BufRead,
+{
+    let mut line_buffer = Vec::new();
+    let mut face = Vec::new();
+    let mut i_line = 0;
+
+    while let Ok(line) = fetch_line(&mut read, &mut line_buffer) {
+        i_line += 1;
+
+        if line.starts_with(b"v ") {
+            mesh.add_vertex(fetch_vertex(line).line(i_line, line)?);
+        } else if line.starts_with(b"f ") {
+            fetch_poly_face(line, &mut face).line(i_line, line)?;
+            mesh.try_add_face(&face)
+                .map_err(|_| ObjError::InvalidMeshIndices)
+                .line(i_line, line)?;
+        }
+    }
+
+    Ok(())
+}
+
 /// Loads IsPushable<Is3D> from the .obj file format
 pub fn load_obj_points<IP, P, R>(read: R, ip: &mut IP) -> ObjIOResult<()>
 where
@@ -229,13 +342,15 @@ where
 pub enum ObjError {
     AccessFile,
     InvalidMeshIndices,
+    NormalArrayIncorrectLength,
     Face,
     Vertex,
 }
 
 /// Result type for .obj file operations
 pub type ObjIOResult<T> = IOResult<T, ObjError>;
-type ObjResult<T> = std::result::Result<T, ObjError>;
+/// Result type for .obj file operations
+pub type ObjResult<T> = std::result::Result<T, ObjError>;
 
 impl fmt::Debug for ObjError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -244,6 +359,9 @@ impl fmt::Debug for ObjError {
             Self::Face => write!(f, "Unable to parse face"),
             Self::Vertex => write!(f, "Unable to parse vertex"),
             Self::InvalidMeshIndices => write!(f, "File contains invalid mesh indices"),
+            Self::NormalArrayIncorrectLength => {
+                write!(f, "The provided normal array has an incorrect length")
+            }
         }
     }
 }
@@ -310,3 +428,20 @@ fn fetch_face(line: &[u8]) -> ObjResult<[usize; 3]> {
     //obj indexing starts at 1
     Ok([a - 1, b - 1, c - 1])
 }
+
+#[inline(always)]
+fn fetch_poly_face(line: &[u8], result: &mut Vec<VId>) -> ObjResult<()> {
+    result.clear();
+    let mut words = to_words_skip_empty(line);
+
+    // skip "f"
+    words.next().ok_or(ObjError::Face)?;
+
+    //obj indexing starts at 1
+    for word in words {
+        let i: usize = from_ascii(until_bytes(word, b'/')).ok_or(ObjError::Face)?;
+        result.push(VId(i.checked_sub(1).ok_or(ObjError::Face)?));
+    }
+
+    Ok(())
+}
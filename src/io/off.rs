@@ -26,7 +26,7 @@ use crate::*;
 
 use std::{
     fmt,
-    io::{BufRead, Error as ioError},
+    io::{BufRead, Error as ioError, Write},
     iter::FusedIterator,
     marker::PhantomData,
 };
@@ -328,6 +328,90 @@ where
     Ok(())
 }
 
+/// Loads a PolyMesh3D from the .off file format, keeping its quads and n-gons
+pub fn load_off_poly_mesh<P, ID, IC, R>(
+    mut read: R,
+    mesh: &mut PolyMesh3D<P, ID, IC>,
+) -> OffIOResult<()>
+where
+    P: IsBuildable3D + IsEditable3D + Clone,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+    R: BufRead,
+{
+    let mut line_buffer = Vec::new();
+    let mut face = Vec::new();
+    let mut i_line = 0;
+    let mut off_seen = false;
+    let mut counts = None;
+    let mut n_vertices_added = 0;
+
+    while let Ok(line) = fetch_line(&mut read, &mut line_buffer) {
+        i_line += 1;
+
+        if !off_seen && line.starts_with(b"OFF") {
+            off_seen = true;
+            continue;
+        }
+
+        if line.is_empty() || line.starts_with(b"#") {
+            continue;
+        }
+
+        match counts {
+            None => {
+                let [n_vertices, n_faces] =
+                    OffMeshIterator::<P, R>::fetch_counts(line).line(i_line, line)?;
+                mesh.reserve_vertices(n_vertices);
+                mesh.reserve_faces(n_faces);
+                counts = Some(n_vertices);
+            }
+            Some(n_vertices) if n_vertices_added < n_vertices => {
+                mesh.add_vertex(fetch_vertex(line).line(i_line, line)?);
+                n_vertices_added += 1;
+            }
+            Some(_) => {
+                fetch_poly_face(line, &mut face).line(i_line, line)?;
+                mesh.try_add_face(&face)
+                    .map_err(|_| OffError::InvalidMeshIndices)
+                    .line(i_line, line)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Saves a PolyMesh3D in the .off file format, keeping its quads and n-gons
+pub fn save_off_poly_mesh<P, ID, IC, W>(
+    write: &mut W,
+    mesh: &PolyMesh3D<P, ID, IC>,
+) -> OffResult<()>
+where
+    P: IsBuildable3D + Clone,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+    W: Write,
+{
+    write.write_all(format!("OFF\n{} {} 0\n", mesh.num_vertices(), mesh.num_faces()).as_bytes())?;
+
+    for i in 0..mesh.num_vertices() {
+        let vertex = mesh.vertex(VId(i)).unwrap(); // safe since iterating num_vertices
+        write.write_all((vertex.to_str() + "\n").as_bytes())?;
+    }
+
+    for i in 0..mesh.num_faces() {
+        let face = mesh.face_vertex_ids(FId(i)).unwrap(); // safe since iterating num_faces
+        let mut line = face.len().to_string();
+        for vid in face.vids.iter() {
+            line += &format!(" {}", vid.0);
+        }
+        write.write_all((line + "\n").as_bytes())?;
+    }
+
+    Ok(())
+}
+
 /// Loads IsPushable<Is3D> from the .off file format
 pub fn load_off_points<IP, P, R>(read: R, ip: &mut IP) -> OffIOResult<()>
 where
@@ -362,7 +446,8 @@ pub enum OffError {
 
 /// Result type for .off file operations
 pub type OffIOResult<T> = IOResult<T, OffError>;
-type OffResult<T> = std::result::Result<T, OffError>;
+/// Result type for .off file operations
+pub type OffResult<T> = std::result::Result<T, OffError>;
 
 impl fmt::Debug for OffError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -416,3 +501,22 @@ where
 
     Ok(P::new(x, y, z))
 }
+
+#[inline(always)]
+fn fetch_poly_face(line: &[u8], result: &mut Vec<VId>) -> OffResult<()> {
+    result.clear();
+    let mut words = to_words_skip_empty(line);
+
+    let count: usize = words
+        .next()
+        .and_then(from_ascii)
+        .ok_or(OffError::FaceVertexCount)?;
+
+    // additional words (e.g. colors) are ignored
+    for _ in 0..count {
+        let i: usize = words.next().and_then(from_ascii).ok_or(OffError::Face)?;
+        result.push(VId(i));
+    }
+
+    Ok(())
+}
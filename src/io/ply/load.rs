@@ -26,9 +26,9 @@ use crate::*;
 
 use std::io::{BufRead, Read};
 
-use super::super::{byte_reader::*, types::*};
+use super::super::{byte_reader::*, types::*, utils::*};
 
-use super::{header::*, iterators::*, iterators_internal::*, types::*};
+use super::{header::*, iterators::*, iterators_internal::*, types::*, utils::*};
 
 //------------------------------------------------------------------------------
 
@@ -111,6 +111,38 @@ where
     Ok(())
 }
 
+/// Loads a PolyMesh3D from the .ply file format, keeping its quads and n-gons
+pub fn load_ply_poly_mesh<P, ID, IC, R>(
+    mut read: R,
+    mesh: &mut PolyMesh3D<P, ID, IC>,
+) -> PlyIOResult<()>
+where
+    P: IsBuildable3D + IsEditable3D + Clone,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+    R: BufRead,
+{
+    let mut line_buffer = Vec::new();
+    let mut i_line = 0;
+
+    let header = match load_header(&mut read, &mut line_buffer, &mut i_line)? {
+        Header::Full(header) => header,
+        Header::Partial(_) => return Err(PlyError::LoadHeaderInvalid).simple(),
+    };
+    mesh.reserve_vertices(header.vertex.count);
+    mesh.reserve_faces(header.face.count);
+
+    match header.format {
+        Format::Ascii => load_poly_mesh_ascii(&mut read, mesh, header, i_line),
+        Format::LittleEndian => {
+            load_poly_mesh_binary::<LittleReader, _, _, _, _>(&mut read, mesh, header).simple()
+        }
+        Format::BigEndian => {
+            load_poly_mesh_binary::<BigReader, _, _, _, _>(&mut read, mesh, header).simple()
+        }
+    }
+}
+
 //------------------------------------------------------------------------------
 
 /// Loads the points from the .ply file into IsPushable<Is3D>
@@ -236,3 +268,83 @@ where
 
     Ok(())
 }
+
+//------------------------------------------------------------------------------
+
+fn load_poly_mesh_binary<BR, P, ID, IC, R>(
+    read: &mut R,
+    mesh: &mut PolyMesh3D<P, ID, IC>,
+    header: FullHeader,
+) -> PlyResult<()>
+where
+    P: IsBuildable3D + IsEditable3D + Clone,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+    R: Read,
+    BR: IsByteReader,
+{
+    let mut points = PlyBinaryPointsIterator::<BR, P, _>::new(read, header.clone().into());
+    for p in &mut points {
+        mesh.add_vertex(p?);
+    }
+    let read = points.destruct();
+
+    let format = &header.face.format;
+    let mut face = Vec::new();
+    for _ in 0..header.face.count {
+        skip_bytes(read, format.before.bytes)?;
+
+        let count = read_face_type::<BR, _>(read, format.count)?;
+        face.clear();
+        for _ in 0..count {
+            face.push(VId(read_face_type::<BR, _>(read, format.index)?));
+        }
+
+        skip_bytes(read, format.after.bytes)?;
+
+        mesh.try_add_face(&face)
+            .or(Err(PlyError::InvalidMeshIndices))?;
+    }
+
+    Ok(())
+}
+
+//------------------------------------------------------------------------------
+
+fn load_poly_mesh_ascii<P, ID, IC, R>(
+    read: &mut R,
+    mesh: &mut PolyMesh3D<P, ID, IC>,
+    header: FullHeader,
+    i_line: usize,
+) -> PlyIOResult<()>
+where
+    P: IsBuildable3D + IsEditable3D + Clone,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+    R: BufRead,
+{
+    let mut points = PlyAsciiPointsIterator::new(read, header.clone().into(), i_line);
+    for p in &mut points {
+        mesh.add_vertex(p?);
+    }
+    let (read, mut i_line) = points.destruct();
+
+    let mut line_buffer = Vec::new();
+    let mut face = Vec::new();
+    for _ in 0..header.face.count {
+        let line = match fetch_line(read, &mut line_buffer) {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        i_line += 1;
+
+        collect_poly_index_line(line, header.face.format.before.words, &mut face)
+            .ok_or(PlyError::FaceStructure)
+            .line(i_line, line)?;
+        mesh.try_add_face(&face)
+            .or(Err(PlyError::InvalidMeshIndices))
+            .line(i_line, line)?;
+    }
+
+    Ok(())
+}
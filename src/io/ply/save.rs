@@ -76,6 +76,64 @@ where
 
 //------------------------------------------------------------------------------
 
+/// Saves a PolyMesh3D in the ASCII .ply file format, keeping its quads and n-gons
+pub fn save_ply_ascii_poly_mesh<P, ID, IC, W>(
+    write: &mut W,
+    mesh: &PolyMesh3D<P, ID, IC>,
+) -> PlyResult<()>
+where
+    P: IsBuildable3D + Clone,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+    W: Write,
+{
+    // uchar can't hold the vertex count of large n-gons
+    let max_face_size = (0..mesh.num_faces())
+        .filter_map(|i| mesh.face_size(FId(i)))
+        .max()
+        .unwrap_or(0);
+    let count_type = if max_face_size <= u8::MAX as usize {
+        "uchar"
+    } else {
+        "uint"
+    };
+
+    let header = "ply\n".to_string()
+        + "format ascii 1.0\n"
+        + "comment Created by rust-3d\n"
+        + "element vertex "
+        + &mesh.num_vertices().to_string()
+        + "\n"
+        + "property float x\n"
+        + "property float y\n"
+        + "property float z\n"
+        + "element face "
+        + &mesh.num_faces().to_string()
+        + "\n"
+        + "property list "
+        + count_type
+        + " uint vertex_indices\n"
+        + "end_header\n";
+    write.write_all(header.as_bytes())?;
+
+    for i in 0..mesh.num_vertices() {
+        let vertex = mesh.vertex(VId(i)).unwrap(); // safe since iterating num_vertices
+        write.write_all((vertex.to_str() + "\n").as_bytes())?;
+    }
+
+    for i in 0..mesh.num_faces() {
+        let face = mesh.face_vertex_ids(FId(i)).unwrap(); // safe since iterating num_faces
+        let mut line = face.len().to_string();
+        for vid in face.vids.iter() {
+            line += &format!(" {}", vid.0);
+        }
+        write.write_all((line + "\n").as_bytes())?;
+    }
+    Ok(())
+}
+
+//------------------------------------------------------------------------------
+
 /// Saves an IsMesh3D in the ASCII .ply file format with additional colors
 pub fn save_ply_ascii_colored<M, P, W>(write: &mut W, mesh: &M, colors: &Vec<Rgb>) -> PlyResult<()>
 where
@@ -321,3 +379,137 @@ where
 
     Ok(())
 }
+
+//------------------------------------------------------------------------------
+
+/// Saves an IsMesh3D in the ASCII .ply file format with additional vertex normals
+pub fn save_ply_ascii_with_normals<M, P, W>(
+    write: &mut W,
+    mesh: &M,
+    normals: &[Norm3D],
+) -> PlyResult<()>
+where
+    M: IsMesh<P, Face3>,
+    P: IsBuildable3D,
+    W: Write,
+{
+    let n_vertices = mesh.num_vertices();
+    let n_faces = mesh.num_faces();
+
+    if n_vertices != normals.len() {
+        return Err(PlyError::NormalArrayIncorrectLength);
+    }
+
+    let header = "ply\n".to_string()
+        + "format ascii 1.0\n"
+        + "comment Created by rust-3d\n"
+        + "element vertex "
+        + &n_vertices.to_string()
+        + "\n"
+        + "property float x\n"
+        + "property float y\n"
+        + "property float z\n"
+        + "property float nx\n"
+        + "property float ny\n"
+        + "property float nz\n"
+        + "element face "
+        + &n_faces.to_string()
+        + "\n"
+        + "property list uchar uint vertex_indices\n"
+        + "end_header\n";
+    write.write_all(header.as_bytes())?;
+
+    for (i, normal) in normals.iter().enumerate() {
+        let vertex = mesh.vertex(VId(i)).unwrap(); // safe since iterating n_vertices
+        write.write_all((vertex.to_str() + " " + &normal.to_str() + "\n").as_bytes())?;
+    }
+
+    for i in 0..n_faces {
+        let face = mesh.face_vertex_ids(FId(i)).unwrap(); // safe since iterating n_faces
+        write.write_all(
+            ("3 ".to_string()
+                + &face.a.to_string()
+                + " "
+                + &face.b.to_string()
+                + " "
+                + &face.c.to_string()
+                + "\n")
+                .as_bytes(),
+        )?;
+    }
+    Ok(())
+}
+
+//------------------------------------------------------------------------------
+
+/// Saves an IsMesh3D in the binary .ply file format with additional vertex normals
+pub fn save_ply_binary_with_normals<M, P, W>(
+    write: &mut W,
+    mesh: &M,
+    precision: &Precision,
+    normals: &[Norm3D],
+) -> PlyResult<()>
+where
+    M: IsMesh<P, Face3>,
+    P: IsBuildable3D,
+    W: Write,
+{
+    let n_vertices = mesh.num_vertices();
+    let n_faces = mesh.num_faces();
+
+    if n_vertices != normals.len() {
+        return Err(PlyError::NormalArrayIncorrectLength);
+    }
+
+    let t = match precision {
+        Precision::P32 => "float",
+        Precision::P64 => "double",
+    };
+
+    let mut header = "ply\n".to_string()
+        + "format binary_big_endian 1.0\n"
+        + "comment Created by rust-3d\n"
+        + "element vertex "
+        + &n_vertices.to_string()
+        + "\n";
+    for property in ["x", "y", "z", "nx", "ny", "nz"].iter() {
+        header = header + "property " + t + " " + property + "\n";
+    }
+    header = header
+        + "element face "
+        + &n_faces.to_string()
+        + "\n"
+        + "property list uint8 uint32 vertex_indices\n"
+        + "end_header\n";
+
+    write.write_all(header.as_bytes())?;
+
+    for (i, normal) in normals.iter().enumerate() {
+        let vertex = mesh.vertex(VId(i)).unwrap(); // safe since iterating n_vertices
+        for x in [
+            vertex.x(),
+            vertex.y(),
+            vertex.z(),
+            normal.x(),
+            normal.y(),
+            normal.z(),
+        ]
+        .iter()
+        {
+            match precision {
+                Precision::P32 => write.write_all(&(*x as f32).to_be_bytes())?,
+                Precision::P64 => write.write_all(&x.to_be_bytes())?,
+            }
+        }
+    }
+
+    for i in 0..n_faces {
+        let face = mesh.face_vertex_ids(FId(i)).unwrap(); // safe since iterating n_faces
+        write.write_all(&3u8.to_be_bytes())?;
+        write.write_all(&(face.a.0 as u32).to_be_bytes())?;
+        write.write_all(&(face.b.0 as u32).to_be_bytes())?;
+        write.write_all(&(face.c.0 as u32).to_be_bytes())?;
+    }
+
+    Ok(())
+}
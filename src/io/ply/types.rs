@@ -290,6 +290,7 @@ pub enum PlyError {
     LoadVertexCountIncorrect,
     AccessFile,
     ColorArrayIncorrectLength,
+    NormalArrayIncorrectLength,
     VertexElement,
     FaceElement,
     InvalidType(String),
@@ -322,6 +323,9 @@ impl fmt::Debug for PlyError {
             Self::ColorArrayIncorrectLength => {
                 write!(f, "The provided color array has an incorrect length")
             }
+            Self::NormalArrayIncorrectLength => {
+                write!(f, "The provided normal array has an incorrect length")
+            }
             Self::VertexElement => write!(f, "Invalid vertex element"),
             Self::FaceElement => write!(f, "Invalid face element"),
             Self::InvalidType(x) => write!(f, "Invalid type in header '{}'", x),
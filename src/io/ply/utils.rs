@@ -47,6 +47,20 @@ pub fn collect_index_line(line: &[u8]) -> Option<[usize; 3]> {
     Some([a, b, c])
 }
 
+#[inline(always)]
+pub fn collect_poly_index_line(line: &[u8], skip: usize, result: &mut Vec<VId>) -> Option<()> {
+    result.clear();
+    let mut words = to_words_skip_empty(line);
+    skip_n(&mut words, skip);
+
+    let count: usize = from_ascii(words.next()?)?;
+    for _ in 0..count {
+        result.push(VId(from_ascii(words.next()?)?));
+    }
+
+    Some(())
+}
+
 //------------------------------------------------------------------------------
 
 #[inline(always)]
@@ -37,4 +37,16 @@ where
     fn try_add_connection(&mut self, vid1: VId, vid2: VId, vid3: VId) -> Result<FId>;
     /// Should reserve space for n additional faces
     fn reserve_faces(&mut self, n: usize);
+    /// Should add a face derived from a face of a mesh with the given face attributes and return its id
+    /// Meshes storing attributes should copy them, others can ignore source and faceid
+    fn try_add_connection_derived(
+        &mut self,
+        vid1: VId,
+        vid2: VId,
+        vid3: VId,
+        _source: Option<&MeshAttributes>,
+        _faceid: FId,
+    ) -> Result<FId> {
+        self.try_add_connection(vid1, vid2, vid3)
+    }
 }
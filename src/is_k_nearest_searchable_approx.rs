@@ -0,0 +1,54 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! IsKNearestSearchableApprox trait used for search structures which can be queried for approximate nearest neighbours.
+//! You should only implement this, if your solution is rather efficient
+
+use crate::*;
+
+//------------------------------------------------------------------------------
+
+/// IsKNearestSearchableApprox trait used for search structures which can be queried for approximate nearest neighbours.
+/// You should only implement this, if your solution is rather efficient
+pub trait IsKNearestSearchableApprox<Search, Find> {
+    /// Should return k neighbours of search, each at most (1 + epsilon) times as distant as the exact k nearest neighbours
+    /// If max_checks is reached, the search should stop early and return the best neighbours found so far
+    fn knearest_approx(
+        &self,
+        search: &Search,
+        n: usize,
+        epsilon: NonNegative,
+        max_checks: Option<usize>,
+        result: &mut Vec<Find>,
+    );
+    /// Should return an approximate nearest neighbour to search, if there is any
+    fn nearest_approx(
+        &self,
+        search: &Search,
+        epsilon: NonNegative,
+        max_checks: Option<usize>,
+    ) -> Option<Find> {
+        let mut result = Vec::new();
+        self.knearest_approx(search, 1, epsilon, max_checks, &mut result);
+        result.pop()
+    }
+}
@@ -41,4 +41,12 @@ where
     fn face_vertices(&self, faceid: FId) -> Option<[V; 3]>;
     /// Should return the vertex with the given id
     fn vertex(&self, vertexid: VId) -> Option<V>;
+    /// Should return the attributes of the vertices, None if the mesh doesn't store any
+    fn vertex_attributes(&self) -> Option<&MeshAttributes> {
+        None
+    }
+    /// Should return the attributes of the faces, None if the mesh doesn't store any
+    fn face_attributes(&self) -> Option<&MeshAttributes> {
+        None
+    }
 }
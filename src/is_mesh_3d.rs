@@ -44,6 +44,128 @@ where
 
         Norm3D::new(n).and_then(|x| Ok(x))
     }
+
+    /// Returns the summed area of all faces
+    fn area(&self) -> Result<f64> {
+        let mut result = 0.0;
+        for i in 0..self.num_faces() {
+            result += face_area(
+                &self
+                    .face_vertices(FId(i))
+                    .ok_or(ErrorKind::IncorrectFaceID)?,
+            );
+        }
+        Ok(result)
+    }
+
+    /// Returns the enclosed volume, positive if the faces are oriented outwards
+    /// Only meaningful for closed meshes
+    fn signed_volume(&self) -> Result<f64> {
+        let mut result = 0.0;
+        for i in 0..self.num_faces() {
+            result += tetrahedron_det(
+                &self
+                    .face_vertices(FId(i))
+                    .ok_or(ErrorKind::IncorrectFaceID)?,
+            ) / 6.0;
+        }
+        Ok(result)
+    }
+
+    /// Returns the centroid of the surface, weighting the faces by their area
+    /// Error if the mesh has no area
+    fn area_centroid(&self) -> Result<Point3D> {
+        let mut sum = Point3D::default();
+        let mut area = 0.0;
+        for i in 0..self.num_faces() {
+            let vs = self
+                .face_vertices(FId(i))
+                .ok_or(ErrorKind::IncorrectFaceID)?;
+            let a = face_area(&vs);
+            for v in vs.iter() {
+                sum.move_by(a * v.x() / 3.0, a * v.y() / 3.0, a * v.z() / 3.0);
+            }
+            area += a;
+        }
+        if area == 0.0 {
+            return Err(ErrorKind::MeshDegenerate);
+        }
+        Ok(Point3D::new(sum.x / area, sum.y / area, sum.z / area))
+    }
+
+    /// Returns the centroid of the enclosed solid
+    /// Only meaningful for closed meshes, error if the mesh encloses no volume
+    fn volume_centroid(&self) -> Result<Point3D> {
+        let mut sum = Point3D::default();
+        let mut volume = 0.0;
+        for i in 0..self.num_faces() {
+            let vs = self
+                .face_vertices(FId(i))
+                .ok_or(ErrorKind::IncorrectFaceID)?;
+            let v = tetrahedron_det(&vs) / 6.0;
+            for p in vs.iter() {
+                // the fourth corner of the tetrahedron is the origin
+                sum.move_by(v * p.x() / 4.0, v * p.y() / 4.0, v * p.z() / 4.0);
+            }
+            volume += v;
+        }
+        if volume == 0.0 {
+            return Err(ErrorKind::MeshDegenerate);
+        }
+        Ok(Point3D::new(sum.x / volume, sum.y / volume, sum.z / volume))
+    }
+
+    /// Returns the inertia tensor of the enclosed solid with uniform density 1, relative to its volume centroid
+    /// Only meaningful for closed meshes, error if the mesh encloses no volume
+    fn inertia_tensor(&self) -> Result<Matrix3> {
+        let centroid = self.volume_centroid()?;
+
+        // second moments of the solid, relative to the centroid
+        let mut moments = [[0.0; 3]; 3];
+        let mut volume = 0.0;
+        for i in 0..self.num_faces() {
+            let vs = self
+                .face_vertices(FId(i))
+                .ok_or(ErrorKind::IncorrectFaceID)?;
+            let vs = [
+                conn(&centroid, &Point3D::new_from(&vs[0])),
+                conn(&centroid, &Point3D::new_from(&vs[1])),
+                conn(&centroid, &Point3D::new_from(&vs[2])),
+            ];
+            let det = tetrahedron_det(&vs);
+            let coords = vs.iter().map(|v| [v.x, v.y, v.z]).collect::<Vec<_>>();
+            for (r, row) in moments.iter_mut().enumerate() {
+                for (c, moment) in row.iter_mut().enumerate() {
+                    let products = coords.iter().map(|v| v[r] * v[c]).sum::<f64>();
+                    let sums = coords.iter().map(|v| v[r]).sum::<f64>()
+                        * coords.iter().map(|v| v[c]).sum::<f64>();
+                    *moment += det * (products + sums) / 120.0;
+                }
+            }
+            volume += det / 6.0;
+        }
+        if volume == 0.0 {
+            return Err(ErrorKind::MeshDegenerate);
+        }
+
+        let trace = moments[0][0] + moments[1][1] + moments[2][2];
+        let mut result = Matrix3::zeroes();
+        for (r, row) in result.data.iter_mut().enumerate() {
+            for (c, x) in row.iter_mut().enumerate() {
+                *x = if r == c { trace } else { 0.0 } - moments[r][c];
+            }
+        }
+        Ok(result)
+    }
+
+    /// Returns whether the mesh is closed, manifold and consistently oriented, so it encloses a volume
+    fn is_watertight(&self) -> Result<bool>
+    where
+        Self: Sized,
+    {
+        let diagnostics = diagnose_mesh(self)?;
+        Ok(diagnostics.is_closed() && diagnostics.is_valid_for_half_edge())
+    }
 }
 
 impl<M, P> IsMesh3D<P> for M
@@ -52,3 +174,20 @@ where
     P: IsBuildable3D,
 {
 }
+
+//------------------------------------------------------------------------------
+
+fn face_area<P>([a, b, c]: &[P; 3]) -> f64
+where
+    P: IsBuildable3D,
+{
+    0.5 * *cross(&conn(a, b), &conn(a, c)).abs()
+}
+
+/// Six times the signed volume of the tetrahedron of the face and the origin
+fn tetrahedron_det<P>([a, b, c]: &[P; 3]) -> f64
+where
+    P: IsBuildable3D,
+{
+    a.dot(&cross(b, c))
+}
@@ -65,6 +65,70 @@ pub trait IsSearchableMesh<V, TU>: IsMesh<V, Face3> {
     fn num_edges(&self) -> usize {
         self.num_faces() * 3
     }
+    /// Returns whether the edge is on the boundary of the mesh (has no twin)
+    fn is_edge_on_boundary(&self, edgeid: EId) -> bool {
+        self.edge_twin(edgeid).is_none()
+    }
+    /// Returns whether the vertex is on the boundary of the mesh. Error if id invalid
+    /// cache can be any Vec
+    fn is_vertex_on_boundary(&self, vertexid: VId, cache: &mut Vec<EId>) -> Result<bool> {
+        let mut edges = Vec::new();
+        self.edges_of_vertex(vertexid, cache, &mut edges)?;
+
+        Ok(edges.into_iter().any(|e| self.is_edge_on_boundary(e)))
+    }
+    /// Appends the boundary loops of the mesh as ordered cycles of vertex ids, following the direction of the boundary edges
+    fn boundary_loops(&self, result: &mut Vec<Vec<VId>>) -> Result<()> {
+        let n_edges = self.num_edges();
+        let mut visited = vec![false; n_edges];
+        let mut originating = Vec::new();
+
+        for start in 0..n_edges {
+            if visited[start] || !self.is_edge_on_boundary(EId(start)) {
+                continue;
+            }
+
+            let mut cycle = Vec::new();
+            let mut current = EId(start);
+            loop {
+                visited[current.0] = true;
+                cycle.push(self.edge_tail(current).ok_or(ErrorKind::IncorrectEdgeID)?);
+                let head = self.edge_head(current).ok_or(ErrorKind::IncorrectEdgeID)?;
+
+                originating.clear();
+                self.edges_originating_from_vertex(head, &mut originating)?;
+                match originating
+                    .iter()
+                    .find(|e| !visited[e.0] && self.is_edge_on_boundary(**e))
+                {
+                    None => break,
+                    Some(e) => current = *e,
+                }
+            }
+            result.push(cycle);
+        }
+        Ok(())
+    }
+    /// Appends the vertices connected to the given vertex via an edge. Sorts and dedups the result. Error if id invalid
+    /// cache can be any Vec
+    fn vertex_neighbours(
+        &self,
+        vertexid: VId,
+        cache: &mut Vec<EId>,
+        result: &mut Vec<VId>,
+    ) -> Result<()> {
+        let mut edges = Vec::new();
+        self.edges_of_vertex(vertexid, cache, &mut edges)?;
+
+        for e in edges {
+            let tail = self.edge_tail(e).ok_or(ErrorKind::IncorrectEdgeID)?;
+            let head = self.edge_head(e).ok_or(ErrorKind::IncorrectEdgeID)?;
+            result.push(if tail == vertexid { head } else { tail });
+        }
+        result.sort();
+        result.dedup();
+        Ok(())
+    }
     /// Appends faces a vertex is part of. Error if id invalid
     /// cache can be any Vec
     fn faces_of_vertex(
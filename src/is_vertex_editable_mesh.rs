@@ -37,4 +37,14 @@ where
     fn change_vertex(&mut self, vid: VId, vertex: V) -> Result<()>;
     /// Should reserve space for n additional vertices
     fn reserve_vertices(&mut self, n: usize);
+    /// Should add a vertex derived from the weighted vertices of a mesh with the given vertex attributes and return its id
+    /// Meshes storing attributes should interpolate them, others can ignore source and weights
+    fn add_vertex_derived(
+        &mut self,
+        vertex: V,
+        _source: Option<&MeshAttributes>,
+        _weights: &[(VId, f64)],
+    ) -> Result<VId> {
+        Ok(self.add_vertex(vertex))
+    }
 }
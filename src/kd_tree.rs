@@ -82,6 +82,23 @@ where
     }
 }
 
+#[cfg(feature = "rayon")]
+impl<P> KdTree<P>
+where
+    P: Is3D + Clone + Send,
+{
+    /// Creates the tree from a given point cloud, building the subtrees in parallel
+    pub fn build_par(&mut self, pc: PointCloud3D<P>) -> Result<()> {
+        match pc.len() {
+            0 => Err(ErrorKind::TooFewPoints),
+            _ => {
+                self.root = Some(KdNode::new_par(0, pc.data));
+                Ok(())
+            }
+        }
+    }
+}
+
 //------------------------------------------------------------------------------
 
 impl<PSearch, PFind> IsKNearestSearchable<PSearch, PFind> for KdTree<PFind>
@@ -106,6 +123,32 @@ where
     }
 }
 
+impl<PSearch, PFind> IsKNearestSearchableApprox<PSearch, PFind> for KdTree<PFind>
+where
+    PSearch: Is3D,
+    PFind: Is3D + Clone,
+{
+    fn knearest_approx(
+        &self,
+        search: &PSearch,
+        n: usize,
+        epsilon: NonNegative,
+        max_checks: Option<usize>,
+        result: &mut Vec<PFind>,
+    ) {
+        if n < 1 {
+            return;
+        }
+        if let Some(ref node) = self.root {
+            let factor = (1.0 + *epsilon) * (1.0 + *epsilon);
+            let mut checks_left = max_checks.unwrap_or(usize::MAX);
+            let mut candidates = Vec::with_capacity(n + 1);
+            node.knearest_approx(search, n, factor, &mut checks_left, &mut candidates);
+            result.extend(candidates.into_iter().map(|(_, p)| p));
+        }
+    }
+}
+
 impl<P> IsSphereSearchable<P> for KdTree<P>
 where
     P: Is3D + Clone,
@@ -163,15 +206,62 @@ where
         }
     }
 
-    pub fn new(dim: i8, mut pc: Vec<P>) -> KdNode<P> {
+    pub fn new(dim: i8, pc: Vec<P>) -> KdNode<P> {
+        let (dimension, val, pc_left, pc_right) = Self::split(dim, pc);
+
+        let left = match pc_left.len() {
+            0 => None,
+            _ => Some(Box::new(KdNode::new(dimension + 1, pc_left))),
+        };
+
+        let right = match pc_right.len() {
+            0 => None,
+            _ => Some(Box::new(KdNode::new(dimension + 1, pc_right))),
+        };
+
+        KdNode {
+            left,
+            right,
+            val,
+            dimension,
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    pub fn new_par(dim: i8, pc: Vec<P>) -> KdNode<P>
+    where
+        P: Send,
+    {
+        if pc.len() < PARALLEL_MIN_SIZE {
+            return Self::new(dim, pc);
+        }
+
+        let (dimension, val, pc_left, pc_right) = Self::split(dim, pc);
+
+        let (left, right) = rayon::join(
+            || match pc_left.len() {
+                0 => None,
+                _ => Some(Box::new(KdNode::new_par(dimension + 1, pc_left))),
+            },
+            || match pc_right.len() {
+                0 => None,
+                _ => Some(Box::new(KdNode::new_par(dimension + 1, pc_right))),
+            },
+        );
+
+        KdNode {
+            left,
+            right,
+            val,
+            dimension,
+        }
+    }
+
+    // splits the data at the median, returning (dimension, median, left, right)
+    fn split(dim: i8, mut pc: Vec<P>) -> (i8, P, Vec<P>, Vec<P>) {
         let dimension = dim % 2;
         if pc.len() == 1 {
-            return KdNode {
-                left: None,
-                right: None,
-                val: pc[0].clone(),
-                dimension,
-            };
+            return (dimension, pc[0].clone(), Vec::new(), Vec::new());
         }
 
         pc.sort_by(|a, b| match dimension {
@@ -194,22 +284,7 @@ where
             }
         }
 
-        let left = match pc_left.len() {
-            0 => None,
-            _ => Some(Box::new(KdNode::new(dimension + 1, pc_left))),
-        };
-
-        let right = match pc_right.len() {
-            0 => None,
-            _ => Some(Box::new(KdNode::new(dimension + 1, pc_right))),
-        };
-
-        KdNode {
-            left,
-            right,
-            val,
-            dimension,
-        }
+        (dimension, val, pc_left, pc_right)
     }
 }
 
@@ -279,6 +354,56 @@ where
         Self::sort_and_limit(pc, search, n);
     }
 
+    // candidates are kept sorted by their squared distance
+    // subtrees are skipped if even a (1 + epsilon) closer position couldn't improve the candidates (factor = (1 + epsilon)^2)
+    pub fn knearest_approx<PSearch>(
+        &self,
+        search: &PSearch,
+        n: usize,
+        factor: f64,
+        checks_left: &mut usize,
+        candidates: &mut Vec<(f64, P)>,
+    ) where
+        PSearch: Is3D,
+    {
+        if *checks_left == 0 {
+            return;
+        }
+        *checks_left -= 1;
+
+        let sqr_dist = sqr_dist_3d(search, &self.val);
+        if candidates.len() < n || sqr_dist < candidates[candidates.len() - 1].0 {
+            let pos = candidates
+                .iter()
+                .position(|(d, _)| *d > sqr_dist)
+                .unwrap_or(candidates.len());
+            candidates.insert(pos, (sqr_dist, self.val.clone()));
+            candidates.truncate(n);
+        }
+
+        let diff = match self.dimension {
+            0 => search.x() - self.val.x(),
+            1 => search.y() - self.val.y(),
+            _ => search.z() - self.val.z(),
+        };
+
+        let (near, far) = if diff < 0.0 {
+            (&self.left, &self.right)
+        } else {
+            (&self.right, &self.left)
+        };
+
+        if let Some(ref node) = near {
+            node.knearest_approx(search, n, factor, checks_left, candidates);
+        }
+
+        if let Some(ref node) = far {
+            if candidates.len() < n || diff * diff * factor < candidates[candidates.len() - 1].0 {
+                node.knearest_approx(search, n, factor, checks_left, candidates);
+            }
+        }
+    }
+
     pub fn in_sphere(&self, sphere: &Sphere, pc: &mut Vec<P>) {
         if dist_3d(&sphere.center, &self.val) <= *sphere.radius {
             pc.push(self.val.clone());
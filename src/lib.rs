@@ -138,6 +138,11 @@ pub mod test_helper;
 mod utils;
 pub use self::utils::*;
 
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "rayon")]
+pub use self::parallel::*;
+
 mod aa_bb_tree_2d;
 pub use self::aa_bb_tree_2d::AABBTree2D;
 
@@ -147,6 +152,24 @@ pub use self::strong_types::*;
 mod aa_bb_tree_3d;
 pub use self::aa_bb_tree_3d::AABBTree3D;
 
+mod aa_bb_tree_flat_2d;
+pub use self::aa_bb_tree_flat_2d::AABBTreeFlat2D;
+
+mod aa_bb_tree_flat_3d;
+pub use self::aa_bb_tree_flat_3d::AABBTreeFlat3D;
+
+mod dynamic_aa_bb_tree_3d;
+pub use self::dynamic_aa_bb_tree_3d::DynamicAABBTree3D;
+
+mod sah;
+use self::sah::*;
+
+mod mesh_topology;
+use self::mesh_topology::*;
+
+mod sparse_matrix;
+use self::sparse_matrix::*;
+
 mod point_2d;
 pub use self::point_2d::Point2D;
 
@@ -225,12 +248,30 @@ pub use self::kd_tree::KdTree;
 mod mesh_3d;
 pub use self::mesh_3d::Mesh3D;
 
+mod normal_mesh_3d;
+pub use self::normal_mesh_3d::NormalMesh3D;
+
+mod attributed_mesh_3d;
+pub use self::attributed_mesh_3d::AttributedMesh3D;
+
+mod poly_mesh_3d;
+pub use self::poly_mesh_3d::{triangulate_polygon_3d, PolyMesh3D};
+
+mod mesh_attributes;
+pub use self::mesh_attributes::{AttributeChannel, MeshAttributes};
+
+mod half_edge_mesh_3d;
+pub use self::half_edge_mesh_3d::HalfEdgeMesh3D;
+
 mod searchable_mesh;
 pub use self::searchable_mesh::SearchableMesh;
 
 mod oc_tree;
 pub use self::oc_tree::OcTree;
 
+mod uniform_grid_3d;
+pub use self::uniform_grid_3d::UniformGrid3D;
+
 mod view;
 pub use self::view::View;
 
@@ -249,6 +290,12 @@ pub use self::rgb::Rgb;
 mod face3;
 pub use self::face3::Face3;
 
+mod face4;
+pub use self::face4::Face4;
+
+mod face_n;
+pub use self::face_n::FaceN;
+
 mod half_edge;
 pub use self::half_edge::HalfEdge;
 
@@ -287,6 +334,65 @@ pub use self::heal_mesh::heal_mesh;
 mod cluster_vertices;
 pub use self::cluster_vertices::cluster_vertices;
 
+mod decimate_mesh;
+pub use self::decimate_mesh::decimate_mesh;
+
+mod fill_holes;
+pub use self::fill_holes::fill_holes;
+
+mod mesh_diagnostics;
+pub use self::mesh_diagnostics::{diagnose_mesh, MeshDiagnostics};
+
+mod repair_mesh;
+use self::repair_mesh::split_vertex_fans;
+pub use self::repair_mesh::{orient_faces_consistently, split_non_manifold_vertices};
+
+mod mesh_components;
+pub use self::mesh_components::{
+    face_components, keep_largest_component, remove_small_components, split_components,
+    vertex_components,
+};
+
+mod vertex_normals;
+pub use self::vertex_normals::{split_creases, vertex_normals};
+
+mod curvature;
+pub use self::curvature::{vertex_curvatures, VertexCurvature};
+
+mod smoothing;
+pub use self::smoothing::{laplacian_smoothing, taubin_smoothing};
+
+mod remesh_isotropic;
+pub use self::remesh_isotropic::remesh_isotropic;
+
+mod mesh_boolean;
+pub use self::mesh_boolean::mesh_boolean;
+
+mod slice_mesh;
+pub use self::slice_mesh::{slice_mesh, slice_mesh_stack, SliceContour};
+
+mod clip_mesh;
+pub use self::clip_mesh::{clip_mesh, clip_mesh_box, clip_mesh_box_unaligned, cut_mesh};
+
+mod mesh_distance;
+pub use self::mesh_distance::{
+    cloud_to_mesh_distance, mesh_to_mesh_distance, DistanceReport, MeshDistanceQuery,
+    MeshDistanceReport,
+};
+
+mod offset_mesh;
+pub use self::offset_mesh::{offset_mesh, shell_mesh};
+
+mod geodesics;
+pub use self::geodesics::{
+    dijkstra_distances, dijkstra_path, geodesic_path, heat_geodesic_distances,
+};
+
+mod parameterization;
+pub use self::parameterization::{
+    harmonic_parameterization, lscm_parameterization, parameterization_distortion, FaceDistortion,
+};
+
 mod circle;
 pub use self::circle::Circle;
 
@@ -363,6 +469,9 @@ pub use self::is_vertex_editable_mesh::IsVertexEditableMesh;
 mod is_k_nearest_searchable;
 pub use self::is_k_nearest_searchable::IsKNearestSearchable;
 
+mod is_k_nearest_searchable_approx;
+pub use self::is_k_nearest_searchable_approx::IsKNearestSearchableApprox;
+
 mod is_matrix3_transformable;
 pub use self::is_matrix3_transformable::IsMatrix3Transformable;
 
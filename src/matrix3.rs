@@ -69,6 +69,30 @@ impl Matrix3 {
             ],
         }
     }
+    /// Calculates the determinant of the matrix
+    pub fn determinant(&self) -> f64 {
+        let m = &self.data;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+    /// Solves self * x = rhs for x. None if the matrix is singular
+    pub fn solve(&self, rhs: &[f64; 3]) -> Option<[f64; 3]> {
+        let det = self.determinant();
+        if det == 0.0 {
+            return None;
+        }
+
+        let mut result = [0.0; 3];
+        for (i, r) in result.iter_mut().enumerate() {
+            let mut mi = self.clone();
+            for (row, value) in mi.data.iter_mut().zip(rhs.iter()) {
+                row[i] = *value;
+            }
+            *r = mi.determinant() / det;
+        }
+        Some(result)
+    }
 }
 
 impl Default for Matrix3 {
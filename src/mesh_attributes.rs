@@ -0,0 +1,239 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! MeshAttributes, named and typed attribute channels of the vertices or faces of a mesh
+
+use crate::*;
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, PartialEq, Clone)]
+/// AttributeChannel, the typed values of an attribute, one per vertex or face
+pub enum AttributeChannel {
+    /// E.g. curvatures or other scalar fields
+    Scalar(Vec<f64>),
+    /// E.g. texture coordinates
+    Vector2(Vec<[f64; 2]>),
+    /// E.g. normals
+    Vector3(Vec<[f64; 3]>),
+    /// Colors
+    Color(Vec<Rgb>),
+    /// E.g. ids of segments or materials, these are never interpolated
+    Label(Vec<usize>),
+}
+
+impl AttributeChannel {
+    /// Returns the number of values
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Scalar(x) => x.len(),
+            Self::Vector2(x) => x.len(),
+            Self::Vector3(x) => x.len(),
+            Self::Color(x) => x.len(),
+            Self::Label(x) => x.len(),
+        }
+    }
+    /// Returns whether there are no values
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Creates a channel of the same type, holding n default values
+    fn defaults_like(&self, n: usize) -> Self {
+        match self {
+            Self::Scalar(_) => Self::Scalar(vec![0.0; n]),
+            Self::Vector2(_) => Self::Vector2(vec![[0.0; 2]; n]),
+            Self::Vector3(_) => Self::Vector3(vec![[0.0; 3]; n]),
+            Self::Color(_) => Self::Color(vec![Rgb::default(); n]),
+            Self::Label(_) => Self::Label(vec![0; n]),
+        }
+    }
+    fn push_default(&mut self) {
+        match self {
+            Self::Scalar(x) => x.push(0.0),
+            Self::Vector2(x) => x.push([0.0; 2]),
+            Self::Vector3(x) => x.push([0.0; 3]),
+            Self::Color(x) => x.push(Rgb::default()),
+            Self::Label(x) => x.push(0),
+        }
+    }
+    /// Pushes the weighted sum of values of source, labels use the one with the largest total weight instead
+    /// Pushes a default value if source is of another type
+    fn push_interpolated(&mut self, source: &Self, weights: &[(usize, f64)]) -> Result<()> {
+        if weights.iter().any(|(i, _)| *i >= source.len()) {
+            return Err(ErrorKind::IndexOutOfBounds);
+        }
+
+        match (self, source) {
+            (Self::Scalar(x), Self::Scalar(s)) => {
+                x.push(weights.iter().map(|(i, w)| w * s[*i]).sum())
+            }
+            (Self::Vector2(x), Self::Vector2(s)) => {
+                let mut sum = [0.0; 2];
+                for (i, w) in weights.iter() {
+                    sum[0] += w * s[*i][0];
+                    sum[1] += w * s[*i][1];
+                }
+                x.push(sum)
+            }
+            (Self::Vector3(x), Self::Vector3(s)) => {
+                let mut sum = [0.0; 3];
+                for (i, w) in weights.iter() {
+                    sum[0] += w * s[*i][0];
+                    sum[1] += w * s[*i][1];
+                    sum[2] += w * s[*i][2];
+                }
+                x.push(sum)
+            }
+            (Self::Color(x), Self::Color(s)) => {
+                let mut sum = [0.0; 3];
+                for (i, w) in weights.iter() {
+                    sum[0] += w * s[*i].r as f64;
+                    sum[1] += w * s[*i].g as f64;
+                    sum[2] += w * s[*i].b as f64;
+                }
+                let channel = |c: f64| c.round().clamp(0.0, 255.0) as u8;
+                x.push(Rgb::new(channel(sum[0]), channel(sum[1]), channel(sum[2])))
+            }
+            (Self::Label(x), Self::Label(s)) => {
+                let mut totals: Vec<(usize, f64)> = Vec::new();
+                for (i, w) in weights.iter() {
+                    match totals.iter_mut().find(|(label, _)| *label == s[*i]) {
+                        Some((_, total)) => *total += w,
+                        None => totals.push((s[*i], *w)),
+                    }
+                }
+                let mut best = (0, f64::MIN);
+                for (label, total) in totals {
+                    if total > best.1 {
+                        best = (label, total);
+                    }
+                }
+                x.push(best.0)
+            }
+            (x, _) => x.push_default(),
+        }
+        Ok(())
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Default, Debug, PartialEq, Clone)]
+/// MeshAttributes, named attribute channels which all hold one value per vertex or face of a mesh
+pub struct MeshAttributes {
+    n_elements: usize,
+    channels: Vec<(String, AttributeChannel)>,
+}
+
+impl MeshAttributes {
+    /// Creates new MeshAttributes without channels for n elements
+    pub fn new(n_elements: usize) -> Self {
+        Self {
+            n_elements,
+            channels: Vec::new(),
+        }
+    }
+    /// Returns the number of elements each channel holds a value for
+    pub fn len(&self) -> usize {
+        self.n_elements
+    }
+    /// Returns whether there are no elements
+    pub fn is_empty(&self) -> bool {
+        self.n_elements == 0
+    }
+    /// Returns the number of channels
+    pub fn num_channels(&self) -> usize {
+        self.channels.len()
+    }
+    /// Returns the names of the channels in the order they were added
+    pub fn names(&self) -> Vec<&str> {
+        self.channels
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+    /// Returns the channel with the given name
+    pub fn channel(&self, name: &str) -> Option<&AttributeChannel> {
+        self.channels
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, channel)| channel)
+    }
+    /// Adds a channel, replacing any with the same name. Error if its number of values doesn't match the number of elements
+    pub fn add_channel(&mut self, name: &str, channel: AttributeChannel) -> Result<()> {
+        if channel.len() != self.n_elements {
+            return Err(ErrorKind::DimensionsDontMatch);
+        }
+        match self.channels.iter_mut().find(|(n, _)| n == name) {
+            Some((_, existing)) => *existing = channel,
+            None => self.channels.push((name.to_string(), channel)),
+        }
+        Ok(())
+    }
+    /// Removes and returns the channel with the given name
+    pub fn remove_channel(&mut self, name: &str) -> Option<AttributeChannel> {
+        let index = self.channels.iter().position(|(n, _)| n == name)?;
+        Some(self.channels.remove(index).1)
+    }
+    /// Adds an element with default values in all channels
+    pub fn push_default(&mut self) {
+        for (_, channel) in self.channels.iter_mut() {
+            channel.push_default();
+        }
+        self.n_elements += 1;
+    }
+    /// Adds an element derived from the weighted elements of source, see AttributeChannel for how the values are interpolated
+    /// Channels of source which are missing are added, with default values for the existing elements
+    /// Channels missing within source or of another type within source get a default value
+    /// Error if an element id is out of bounds for source
+    pub fn push_derived(
+        &mut self,
+        source: Option<&MeshAttributes>,
+        weights: &[(usize, f64)],
+    ) -> Result<()> {
+        let source = match source {
+            None => {
+                self.push_default();
+                return Ok(());
+            }
+            Some(x) => x,
+        };
+        if weights.iter().any(|(i, _)| *i >= source.n_elements) {
+            return Err(ErrorKind::IndexOutOfBounds);
+        }
+
+        for (name, channel) in source.channels.iter() {
+            if self.channel(name).is_none() {
+                self.channels
+                    .push((name.clone(), channel.defaults_like(self.n_elements)));
+            }
+        }
+        for (name, channel) in self.channels.iter_mut() {
+            match source.channel(name) {
+                Some(s) => channel.push_interpolated(s, weights)?,
+                None => channel.push_default(),
+            }
+        }
+        self.n_elements += 1;
+        Ok(())
+    }
+}
@@ -0,0 +1,973 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Boolean operations (union, intersection, difference) of closed meshes
+
+use crate::*;
+
+use fnv::{FnvHashMap, FnvHashSet};
+
+use std::{cmp::Ordering, f64::consts::PI};
+
+//------------------------------------------------------------------------------
+
+/// Algorithm to combine two closed meshes via a boolean operation
+/// The faces of both meshes are split along their intersection curves, the pieces are kept depending on whether they are inside the other mesh
+/// The meshes must be closed, manifold and oriented outwards, the result then is as well (faces of b are flipped for the difference)
+/// Degenerate configurations (e.g. coplanar faces or touching vertices) are resolved by moving b by a tiny offset
+/// Vertex attributes are interpolated along the intersected edges, faces keep the attributes of the face they are a part of
+/// Error if the meshes aren't closed or manifold or the configuration can't be resolved
+pub fn mesh_boolean<P, MA, MB, MO>(a: &MA, b: &MB, operation: BooleanOperation) -> Result<MO>
+where
+    MA: IsMesh3D<P>,
+    MB: IsMesh3D<P>,
+    MO: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+    P: IsBuildable3D,
+{
+    let solid_a = Solid::new(a)?;
+    let solid_b = Solid::new(b)?;
+
+    let bb = match BoundingBox3D::from_iterator(
+        solid_a.positions.iter().chain(solid_b.positions.iter()),
+    ) {
+        Ok(bb) => bb,
+        Err(_) => return Err(ErrorKind::MeshNotClosed),
+    };
+    let diagonal = dist_3d(&bb.min_p(), &bb.max_p());
+
+    for attempt in 0..MAX_ATTEMPTS {
+        let mut moved_b = solid_b.clone();
+        if attempt > 0 {
+            let offset = PERTURBATION * diagonal * 2.0f64.powi(attempt - 1);
+            for p in moved_b.positions.iter_mut() {
+                p.x += offset * PERTURBATION_DIR[0];
+                p.y += offset * PERTURBATION_DIR[1];
+                p.z += offset * PERTURBATION_DIR[2];
+            }
+        }
+
+        if let Some(combined) =
+            BooleanState::new(&solid_a, &moved_b, EPS * diagonal).combine(operation)
+        {
+            return build_mesh(
+                a,
+                b,
+                &combined,
+                solid_a.positions.len(),
+                solid_a.faces.len(),
+            );
+        }
+    }
+
+    Err(ErrorKind::InvalidMeshOperation)
+}
+
+//------------------------------------------------------------------------------
+
+/// Number of tries to resolve degenerate configurations
+const MAX_ATTEMPTS: i32 = 6;
+/// Initial offset to resolve degenerate configurations, relative to the size of the meshes
+const PERTURBATION: f64 = 1e-7;
+/// Direction of the offset, chosen to not be aligned with common geometry
+const PERTURBATION_DIR: [f64; 3] = [0.719_185_1, 0.520_104_3, 0.460_771_8];
+/// Distances below this are considered zero, relative to the size of the meshes
+const EPS: f64 = 1e-10;
+/// Barycentric coordinates below this are considered to be on the border of a triangle
+const EPS_BARYCENTRIC: f64 = 1e-9;
+
+//------------------------------------------------------------------------------
+
+#[derive(Clone)]
+struct Solid {
+    positions: Vec<Point3D>,
+    faces: Vec<[usize; 3]>,
+}
+
+impl Solid {
+    fn new<P, M>(mesh: &M) -> Result<Self>
+    where
+        M: IsMesh3D<P>,
+        P: IsBuildable3D,
+    {
+        let diagnostics = diagnose_mesh(mesh)?;
+        if !diagnostics.is_valid_for_half_edge() {
+            return Err(ErrorKind::MeshNotManifold);
+        }
+        if !diagnostics.is_closed() || mesh.num_faces() == 0 {
+            return Err(ErrorKind::MeshNotClosed);
+        }
+
+        let mut positions = Vec::with_capacity(mesh.num_vertices());
+        for i in 0..mesh.num_vertices() {
+            let v = mesh.vertex(VId(i)).ok_or(ErrorKind::IncorrectVertexID)?;
+            positions.push(Point3D::new_from(&v));
+        }
+
+        let mut faces = Vec::with_capacity(mesh.num_faces());
+        for i in 0..mesh.num_faces() {
+            let f = mesh
+                .face_vertex_ids(FId(i))
+                .ok_or(ErrorKind::IncorrectFaceID)?;
+            faces.push([f.a.0, f.b.0, f.c.0]);
+        }
+
+        Ok(Self { positions, faces })
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Bounding box of a face, used within the AABBTree3D
+#[derive(Clone)]
+struct FaceBox {
+    face: usize,
+    bb: BoundingBox3D,
+}
+
+impl HasBoundingBox3DMaybe for FaceBox {
+    fn bounding_box_maybe(&self) -> Option<BoundingBox3D> {
+        Some(self.bb.clone())
+    }
+}
+
+impl HasBoundingBox3D for FaceBox {
+    fn bounding_box(&self) -> BoundingBox3D {
+        self.bb.clone()
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Triangle used for SAT checks (in contrast to TriFace3D allowing axis aligned triangles)
+struct SatTriangle([Point3D; 3]);
+
+impl IsSATObject for SatTriangle {
+    fn for_each_point<F>(&self, f: &mut F)
+    where
+        F: FnMut(&Point3D),
+    {
+        for p in self.0.iter() {
+            f(p)
+        }
+    }
+
+    fn for_each_axis<F>(&self, f: &mut F)
+    where
+        F: FnMut(&Norm3D),
+    {
+        let [a, b, c] = &self.0;
+        let n = cross(&conn(a, b), &conn(a, c));
+        if let Ok(normal) = Norm3D::new(n.clone()) {
+            f(&normal);
+        }
+        for (p, q) in [(a, b), (b, c), (c, a)].iter() {
+            if let Ok(axis) = Norm3D::new(cross(&n, &conn(*p, *q))) {
+                f(&axis);
+            }
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Points and faces resulting from a boolean operation
+struct Combined {
+    points: Vec<Point3D>,
+    faces: Vec<[usize; 3]>,
+    /// The face of either solid each face is a part of
+    face_origins: Vec<usize>,
+    /// The edge and parameter along it of each intersection point, these follow the vertices of both solids
+    hit_edges: Vec<(usize, usize, f64)>,
+}
+
+/// Intersection of the faces of two solids
+/// Points and faces of both solids are stored together, the ones of b following the ones of a
+/// Methods return None if a degenerate configuration is encountered
+struct BooleanState {
+    points: Vec<Point3D>,
+    faces: Vec<[usize; 3]>,
+    n_faces_a: usize,
+    /// The edge and parameter along it of each intersection point
+    hit_edges: Vec<(usize, usize, f64)>,
+    eps: f64,
+    /// Intersection points of edges (keyed by their sorted vertex ids) and faces of the other solid
+    edge_hits: FnvHashMap<(usize, usize, usize), Option<usize>>,
+    /// Intersection points on the edges
+    edge_points: FnvHashMap<(usize, usize), Vec<usize>>,
+    /// Intersection points within the faces
+    face_points: Vec<Vec<usize>>,
+    /// Intersection segments within the faces
+    face_segments: Vec<Vec<(usize, usize)>>,
+}
+
+impl BooleanState {
+    fn new(a: &Solid, b: &Solid, eps: f64) -> Self {
+        let na = a.positions.len();
+        let mut points = a.positions.clone();
+        points.extend(b.positions.iter().cloned());
+        let mut faces = a.faces.clone();
+        faces.extend(b.faces.iter().map(|[x, y, z]| [x + na, y + na, z + na]));
+        let n_faces = faces.len();
+
+        Self {
+            points,
+            faces,
+            n_faces_a: a.faces.len(),
+            hit_edges: Vec::new(),
+            eps,
+            edge_hits: FnvHashMap::default(),
+            edge_points: FnvHashMap::default(),
+            face_points: vec![Vec::new(); n_faces],
+            face_segments: vec![Vec::new(); n_faces],
+        }
+    }
+
+    /// Returns the points and faces of the result
+    fn combine(mut self, operation: BooleanOperation) -> Option<Combined> {
+        self.intersect()?;
+
+        let constraints = self
+            .face_segments
+            .iter()
+            .flatten()
+            .map(|(p, q)| edge_key(*p, *q))
+            .collect::<FnvHashSet<_>>();
+
+        let mut triangles_a = Vec::new();
+        let mut triangles_b = Vec::new();
+        let mut origins_a = Vec::new();
+        let mut origins_b = Vec::new();
+        for f in 0..self.faces.len() {
+            let (triangles, origins) = if f < self.n_faces_a {
+                (&mut triangles_a, &mut origins_a)
+            } else {
+                (&mut triangles_b, &mut origins_b)
+            };
+            self.split_face(f, triangles)?;
+            origins.resize(triangles.len(), f);
+        }
+
+        let (faces_a, faces_b) = self.faces.split_at(self.n_faces_a);
+        let inside_a = classify(&self.points, &triangles_a, &constraints, faces_b);
+        let inside_b = classify(&self.points, &triangles_b, &constraints, faces_a);
+
+        let (keep_inside_a, keep_inside_b, flip_b) = match operation {
+            BooleanOperation::Union => (false, false, false),
+            BooleanOperation::Intersection => (true, true, false),
+            BooleanOperation::Difference => (false, true, true),
+        };
+
+        let mut faces = Vec::new();
+        let mut face_origins = Vec::new();
+        for ((t, inside), origin) in triangles_a.into_iter().zip(inside_a).zip(origins_a) {
+            if inside == keep_inside_a {
+                faces.push(t);
+                face_origins.push(origin);
+            }
+        }
+        for (([x, y, z], inside), origin) in triangles_b.into_iter().zip(inside_b).zip(origins_b) {
+            if inside == keep_inside_b {
+                faces.push(if flip_b { [x, z, y] } else { [x, y, z] });
+                face_origins.push(origin);
+            }
+        }
+
+        Some(Combined {
+            points: self.points,
+            faces,
+            face_origins,
+            hit_edges: self.hit_edges,
+        })
+    }
+
+    /// Finds the intersection points and segments of all pairs of faces
+    fn intersect(&mut self) -> Option<()> {
+        let boxes = (self.n_faces_a..self.faces.len())
+            .map(|f| FaceBox {
+                face: f,
+                bb: self.face_box(f),
+            })
+            .collect::<Vec<_>>();
+        let tree = AABBTree3D::new_sah(boxes, 4);
+
+        let mut candidates = Vec::new();
+        for fa in 0..self.n_faces_a {
+            candidates.clear();
+            tree.for_each_collision_candidate(&self.face_box(fa), &mut |x| candidates.push(x.face));
+            candidates.sort();
+            candidates.dedup();
+
+            let tri_a = self.sat_triangle(fa);
+            for fb in candidates.as_slice().iter() {
+                if !SATCollider::collide(&tri_a, &self.sat_triangle(*fb)) {
+                    continue;
+                }
+
+                let mut ends = Vec::new();
+                for (p, q) in self.face_edges(fa).iter() {
+                    if let Some(x) = self.edge_hit(*p, *q, *fb)? {
+                        ends.push(x);
+                    }
+                }
+                for (p, q) in self.face_edges(*fb).iter() {
+                    if let Some(x) = self.edge_hit(*p, *q, fa)? {
+                        ends.push(x);
+                    }
+                }
+
+                match ends.len() {
+                    0 => (),
+                    2 => {
+                        self.face_segments[fa].push((ends[0], ends[1]));
+                        self.face_segments[*fb].push((ends[0], ends[1]));
+                    }
+                    _ => return None,
+                }
+            }
+        }
+        Some(())
+    }
+
+    /// Intersection point of the edge and face, memoized so faces sharing the edge use the same point
+    fn edge_hit(&mut self, p: usize, q: usize, face: usize) -> Option<Option<usize>> {
+        let (p, q) = edge_key(p, q);
+        if let Some(hit) = self.edge_hits.get(&(p, q, face)) {
+            return Some(*hit);
+        }
+
+        let [a, b, c] = self.faces[face];
+        let hit = intersect_edge_face(
+            &self.points[p],
+            &self.points[q],
+            [&self.points[a], &self.points[b], &self.points[c]],
+            self.eps,
+        )?
+        .map(|x| {
+            let edge = conn(&self.points[p], &self.points[q]);
+            let t = conn(&self.points[p], &x).dot(&edge) / edge.dot(&edge);
+            self.hit_edges.push((p, q, t));
+            self.points.push(x);
+            let id = self.points.len() - 1;
+            self.edge_points.entry((p, q)).or_default().push(id);
+            self.face_points[face].push(id);
+            id
+        });
+
+        self.edge_hits.insert((p, q, face), hit);
+        Some(hit)
+    }
+
+    /// Appends the triangles of the face, split at the intersection points and segments
+    fn split_face(&self, f: usize, result: &mut Vec<[usize; 3]>) -> Option<()> {
+        let corners = self.faces[f];
+        let edge_points = self
+            .face_edges(f)
+            .map(|(p, q)| self.sorted_edge_points(p, q));
+
+        if self.face_segments[f].is_empty()
+            && self.face_points[f].is_empty()
+            && edge_points.iter().all(|x| x.is_empty())
+        {
+            result.push(corners);
+            return Some(());
+        }
+
+        // all points must be connected by the segments
+        let mut ends = self.face_segments[f]
+            .iter()
+            .flat_map(|(p, q)| vec![*p, *q])
+            .collect::<Vec<_>>();
+        ends.sort();
+        for x in edge_points
+            .iter()
+            .flatten()
+            .chain(self.face_points[f].as_slice().iter())
+        {
+            if ends.binary_search(x).is_err() {
+                return None;
+            }
+        }
+
+        let mut triangulation = LocalTriangulation::new(&self.points, corners)?;
+        for k in 0..3 {
+            let mut tail = corners[k];
+            for x in edge_points[k].as_slice().iter() {
+                triangulation.split_boundary_edge(&self.points, tail, corners[(k + 1) % 3], *x);
+                tail = *x;
+            }
+        }
+        for x in self.face_points[f].as_slice().iter() {
+            triangulation.insert(&self.points, *x)?;
+        }
+        for (p, q) in self.face_segments[f].iter() {
+            triangulation.enforce_edge(*p, *q)?;
+        }
+        triangulation.make_delaunay();
+        triangulation.append(result);
+        Some(())
+    }
+
+    /// The intersection points on the edge, sorted from p to q
+    fn sorted_edge_points(&self, p: usize, q: usize) -> Vec<usize> {
+        let mut result = match self.edge_points.get(&edge_key(p, q)) {
+            None => return Vec::new(),
+            Some(x) => x.clone(),
+        };
+        let dir = conn(&self.points[p], &self.points[q]);
+        let param = |x: &usize| dir.dot(&conn(&self.points[p], &self.points[*x]));
+        result.sort_by(|x, y| param(x).partial_cmp(&param(y)).unwrap_or(Ordering::Equal));
+        result
+    }
+
+    fn face_edges(&self, f: usize) -> [(usize, usize); 3] {
+        let [a, b, c] = self.faces[f];
+        [(a, b), (b, c), (c, a)]
+    }
+
+    fn sat_triangle(&self, f: usize) -> SatTriangle {
+        let [a, b, c] = self.faces[f];
+        SatTriangle([
+            self.points[a].clone(),
+            self.points[b].clone(),
+            self.points[c].clone(),
+        ])
+    }
+
+    /// Bounding box of the face, padded to never be empty
+    fn face_box(&self, f: usize) -> BoundingBox3D {
+        let [a, b, c] = self.faces[f];
+        let mut min = self.points[a].clone();
+        let mut max = self.points[a].clone();
+        for p in [&self.points[b], &self.points[c]].iter() {
+            min = Point3D::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+            max = Point3D::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+        }
+        let pad = 2.0 * self.eps;
+        min.move_by(-pad, -pad, -pad);
+        max.move_by(pad, pad, pad);
+        // unwrap fine, since padded
+        BoundingBox3D::new(&min, &max).unwrap()
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Triangulation of a single face, projected to 2D, used to insert points and constrained edges
+struct LocalTriangulation {
+    ids: Vec<usize>,
+    positions: Vec<[f64; 2]>,
+    triangles: Vec<[usize; 3]>,
+    constrained: FnvHashSet<(usize, usize)>,
+    axes: (usize, usize),
+    eps: f64,
+}
+
+impl LocalTriangulation {
+    fn new(points: &[Point3D], corners: [usize; 3]) -> Option<Self> {
+        let [a, b, c] = [
+            &points[corners[0]],
+            &points[corners[1]],
+            &points[corners[2]],
+        ];
+        let n = cross(&conn(a, b), &conn(a, c));
+        let n = [n.x, n.y, n.z];
+        // drop the dominant axis of the normal, keeping the orientation
+        let dominant = (0..3)
+            .max_by(|i, j| {
+                n[*i]
+                    .abs()
+                    .partial_cmp(&n[*j].abs())
+                    .unwrap_or(Ordering::Equal)
+            })
+            .unwrap_or(2);
+        let axes = match (dominant, n[dominant] >= 0.0) {
+            (0, true) => (1, 2),
+            (0, false) => (2, 1),
+            (1, true) => (2, 0),
+            (1, false) => (0, 2),
+            (_, true) => (0, 1),
+            (_, false) => (1, 0),
+        };
+        if n[dominant] == 0.0 {
+            return None;
+        }
+
+        let scale = [a, b, c]
+            .iter()
+            .map(|p| sqr_dist_3d(*p, a))
+            .fold(sqr_dist_3d(b, c), f64::max);
+
+        let mut result = Self {
+            ids: Vec::new(),
+            positions: Vec::new(),
+            triangles: vec![[0, 1, 2]],
+            constrained: FnvHashSet::default(),
+            axes,
+            eps: EPS * scale.sqrt(),
+        };
+        for id in corners.iter() {
+            result.add_point(points, *id);
+        }
+        Some(result)
+    }
+
+    fn add_point(&mut self, points: &[Point3D], id: usize) -> usize {
+        let p = [points[id].x, points[id].y, points[id].z];
+        self.ids.push(id);
+        self.positions.push([p[self.axes.0], p[self.axes.1]]);
+        self.ids.len() - 1
+    }
+
+    fn local(&self, id: usize) -> Option<usize> {
+        self.ids.as_slice().iter().position(|x| *x == id)
+    }
+
+    fn orient(&self, a: usize, b: usize, c: usize) -> f64 {
+        let [pa, pb, pc] = [self.positions[a], self.positions[b], self.positions[c]];
+        (pb[0] - pa[0]) * (pc[1] - pa[1]) - (pb[1] - pa[1]) * (pc[0] - pa[0])
+    }
+
+    fn length(&self, a: usize, b: usize) -> f64 {
+        let [pa, pb] = [self.positions[a], self.positions[b]];
+        (pb[0] - pa[0]).hypot(pb[1] - pa[1])
+    }
+
+    /// Signed distance of c to the line through a and b, positive if left of it
+    fn distance(&self, a: usize, b: usize, c: usize) -> f64 {
+        let length = self.length(a, b);
+        if length == 0.0 {
+            0.0
+        } else {
+            self.orient(a, b, c) / length
+        }
+    }
+
+    /// Index of the triangle containing the directed edge and the index of the edge within it
+    fn triangle_with_edge(&self, u: usize, v: usize) -> Option<(usize, usize)> {
+        self.triangles.iter().enumerate().find_map(|(t, tri)| {
+            (0..3)
+                .find(|k| tri[*k] == u && tri[(k + 1) % 3] == v)
+                .map(|k| (t, k))
+        })
+    }
+
+    /// Splits the edge of the face's border from tail (global id) towards head by the point
+    fn split_boundary_edge(&mut self, points: &[Point3D], tail: usize, head: usize, id: usize) {
+        let x = self.add_point(points, id);
+        // unwraps fine, since tail and head of the face's edges are always inserted
+        let u = self.local(tail).unwrap();
+        let v = self.local(head).unwrap();
+        self.split_edge(u, v, x);
+    }
+
+    /// Splits the triangles sharing the edge by the point
+    fn split_edge(&mut self, u: usize, v: usize, x: usize) {
+        for (p, q) in [(u, v), (v, u)].iter() {
+            if let Some((t, k)) = self.triangle_with_edge(*p, *q) {
+                let w = self.triangles[t][(k + 2) % 3];
+                self.triangles[t] = [*p, x, w];
+                self.triangles.push([x, *q, w]);
+            }
+        }
+    }
+
+    /// Inserts a point within the face
+    fn insert(&mut self, points: &[Point3D], id: usize) -> Option<()> {
+        let x = self.add_point(points, id);
+
+        for t in 0..self.triangles.len() {
+            let tri = self.triangles[t];
+            let o = [
+                self.distance(tri[0], tri[1], x),
+                self.distance(tri[1], tri[2], x),
+                self.distance(tri[2], tri[0], x),
+            ];
+            if o.iter().any(|v| *v < -self.eps) {
+                continue;
+            }
+            // points matching a vertex can't be inserted, points close to edges split the closest one
+            if tri.iter().any(|v| self.length(*v, x) <= self.eps) {
+                return None;
+            }
+            let closest = (0..3)
+                .min_by(|i, j| o[*i].partial_cmp(&o[*j]).unwrap_or(Ordering::Equal))
+                .unwrap_or(0);
+            if o[closest] > self.eps {
+                self.triangles[t] = [tri[0], tri[1], x];
+                self.triangles.push([tri[1], tri[2], x]);
+                self.triangles.push([tri[2], tri[0], x]);
+            } else {
+                let (u, v) = (tri[closest], tri[(closest + 1) % 3]);
+                // points on the border of the face are never inserted here
+                self.triangle_with_edge(v, u)?;
+                self.split_edge(u, v, x);
+            }
+            return Some(());
+        }
+        None
+    }
+
+    /// Flips edges until the edge between the points (global ids) exists
+    fn enforce_edge(&mut self, p: usize, q: usize) -> Option<()> {
+        let p = self.local(p)?;
+        let q = self.local(q)?;
+        let max_flips = 10 * self.triangles.len() * self.triangles.len() + 10;
+
+        for _ in 0..max_flips {
+            if self.triangle_with_edge(p, q).is_some() || self.triangle_with_edge(q, p).is_some() {
+                self.constrained.insert(edge_key(p, q));
+                return Some(());
+            }
+
+            let mut flip = None;
+            'search: for t in 0..self.triangles.len() {
+                for k in 0..3 {
+                    let tri = self.triangles[t];
+                    let (u, v, w) = (tri[k], tri[(k + 1) % 3], tri[(k + 2) % 3]);
+                    if u > v
+                        || self.orient(p, q, u) * self.orient(p, q, v) >= 0.0
+                        || self.orient(u, v, p) * self.orient(u, v, q) >= 0.0
+                    {
+                        continue;
+                    }
+                    // points close to the other line
+                    if [u, v]
+                        .iter()
+                        .any(|x| self.distance(p, q, *x).abs() <= self.eps)
+                        || [p, q]
+                            .iter()
+                            .any(|x| self.distance(u, v, *x).abs() <= self.eps)
+                    {
+                        return None;
+                    }
+                    if self.constrained.contains(&edge_key(u, v)) {
+                        return None;
+                    }
+                    let (n, m) = self.triangle_with_edge(v, u)?;
+                    let x = self.triangles[n][(m + 2) % 3];
+                    if self.is_convex(u, v, w, x) {
+                        flip = Some((t, n, u, v, w, x));
+                        break 'search;
+                    }
+                }
+            }
+
+            let (t, n, u, v, w, x) = flip?;
+            self.triangles[t] = [u, x, w];
+            self.triangles[n] = [x, v, w];
+        }
+        None
+    }
+
+    /// Whether the quad formed by the triangles (u, v, w) and (v, u, x) is strictly convex
+    fn is_convex(&self, u: usize, v: usize, w: usize, x: usize) -> bool {
+        self.distance(w, x, u) < -self.eps && self.distance(w, x, v) > self.eps
+    }
+
+    /// Flips unconstrained edges to improve the shape of the triangles
+    fn make_delaunay(&mut self) {
+        let max_flips = 10 * self.triangles.len() * self.triangles.len();
+        for _ in 0..max_flips {
+            let mut flipped = false;
+            for t in 0..self.triangles.len() {
+                for k in 0..3 {
+                    let tri = self.triangles[t];
+                    let (u, v, w) = (tri[k], tri[(k + 1) % 3], tri[(k + 2) % 3]);
+                    if self.constrained.contains(&edge_key(u, v)) {
+                        continue;
+                    }
+                    let (n, m) = match self.triangle_with_edge(v, u) {
+                        None => continue,
+                        Some(x) => x,
+                    };
+                    let x = self.triangles[n][(m + 2) % 3];
+                    if self.in_circle(u, v, w, x) && self.is_convex(u, v, w, x) {
+                        self.triangles[t] = [u, x, w];
+                        self.triangles[n] = [x, v, w];
+                        flipped = true;
+                        break;
+                    }
+                }
+            }
+            if !flipped {
+                return;
+            }
+        }
+    }
+
+    /// Whether x is within the circumcircle of the counter clockwise triangle (a, b, c)
+    fn in_circle(&self, a: usize, b: usize, c: usize, x: usize) -> bool {
+        let px = self.positions[x];
+        let rows = [a, b, c]
+            .iter()
+            .map(|i| {
+                let dx = self.positions[*i][0] - px[0];
+                let dy = self.positions[*i][1] - px[1];
+                [dx, dy, dx * dx + dy * dy]
+            })
+            .collect::<Vec<_>>();
+        let det = rows[0][0] * (rows[1][1] * rows[2][2] - rows[2][1] * rows[1][2])
+            - rows[1][0] * (rows[0][1] * rows[2][2] - rows[2][1] * rows[0][2])
+            + rows[2][0] * (rows[0][1] * rows[1][2] - rows[1][1] * rows[0][2]);
+        det > 0.0
+    }
+
+    fn append(&self, result: &mut Vec<[usize; 3]>) {
+        for [a, b, c] in self.triangles.iter() {
+            result.push([self.ids[*a], self.ids[*b], self.ids[*c]]);
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Intersection of the segment and triangle. None if degenerate (touching or coplanar)
+fn intersect_edge_face(
+    p: &Point3D,
+    q: &Point3D,
+    [a, b, c]: [&Point3D; 3],
+    eps: f64,
+) -> Option<Option<Point3D>> {
+    let n = match Norm3D::new(cross(&conn(a, b), &conn(a, c))) {
+        Ok(n) => n,
+        // degenerated faces have no area to intersect with
+        Err(_) => return Some(None),
+    };
+    let dp = n.dot(&conn(a, p));
+    let dq = n.dot(&conn(a, q));
+    let sign = |d: f64| {
+        if d > eps {
+            1
+        } else if d < -eps {
+            -1
+        } else {
+            0
+        }
+    };
+    let (sp, sq) = (sign(dp), sign(dq));
+
+    if sp * sq > 0 {
+        return Some(None);
+    }
+    if sp == 0 && sq == 0 {
+        return None;
+    }
+
+    let x = if sp == 0 {
+        p.clone()
+    } else if sq == 0 {
+        q.clone()
+    } else {
+        let t = dp / (dp - dq);
+        Point3D::new(
+            p.x + t * (q.x - p.x),
+            p.y + t * (q.y - p.y),
+            p.z + t * (q.z - p.z),
+        )
+    };
+
+    let area = *cross(&conn(a, b), &conn(a, c)).abs();
+    let barycentric = [(b, c), (c, a), (a, b)]
+        .iter()
+        .map(|(u, v)| n.dot(&cross(&conn(&x, u), &conn(&x, v))) / area)
+        .collect::<Vec<_>>();
+
+    if barycentric.iter().any(|l| *l < -EPS_BARYCENTRIC) {
+        Some(None)
+    } else if sp == 0 || sq == 0 || barycentric.iter().any(|l| *l <= EPS_BARYCENTRIC) {
+        None
+    } else {
+        Some(Some(x))
+    }
+}
+
+/// Whether the triangles are inside the other solid, equal for all triangles of a region bounded by the constraints
+fn classify(
+    points: &[Point3D],
+    triangles: &[[usize; 3]],
+    constraints: &FnvHashSet<(usize, usize)>,
+    other: &[[usize; 3]],
+) -> Vec<bool> {
+    let mut edge_triangles = FnvHashMap::<(usize, usize), Vec<usize>>::default();
+    for (t, tri) in triangles.iter().enumerate() {
+        for k in 0..3 {
+            edge_triangles
+                .entry(edge_key(tri[k], tri[(k + 1) % 3]))
+                .or_default()
+                .push(t);
+        }
+    }
+
+    let area = |t: usize| {
+        let [a, b, c] = triangles[t];
+        *cross(&conn(&points[a], &points[b]), &conn(&points[a], &points[c])).abs()
+    };
+
+    let mut result = vec![false; triangles.len()];
+    let mut visited = vec![false; triangles.len()];
+    let mut region = Vec::new();
+    let mut stack = Vec::new();
+
+    for start in 0..triangles.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        region.clear();
+        stack.push(start);
+        while let Some(t) = stack.pop() {
+            region.push(t);
+            let tri = triangles[t];
+            for k in 0..3 {
+                let key = edge_key(tri[k], tri[(k + 1) % 3]);
+                if constraints.contains(&key) {
+                    continue;
+                }
+                if let Some(neighbours) = edge_triangles.get(&key) {
+                    for n in neighbours {
+                        if !visited[*n] {
+                            visited[*n] = true;
+                            stack.push(*n);
+                        }
+                    }
+                }
+            }
+        }
+
+        // the largest triangle is the least likely to be close to the other solid
+        let largest = region
+            .as_slice()
+            .iter()
+            .cloned()
+            .max_by(|x, y| area(*x).partial_cmp(&area(*y)).unwrap_or(Ordering::Equal))
+            .unwrap_or(start);
+        let [a, b, c] = triangles[largest];
+        let center = Point3D::new(
+            (points[a].x + points[b].x + points[c].x) / 3.0,
+            (points[a].y + points[b].y + points[c].y) / 3.0,
+            (points[a].z + points[b].z + points[c].z) / 3.0,
+        );
+        let inside = winding_number(points, other, &center).abs() > 0.5;
+        for t in region.as_slice().iter() {
+            result[*t] = inside;
+        }
+    }
+
+    result
+}
+
+/// Generalized winding number of the point with respect to the faces (1 inside, 0 outside of a closed mesh)
+fn winding_number(points: &[Point3D], faces: &[[usize; 3]], x: &Point3D) -> f64 {
+    let mut sum = 0.0;
+    for [a, b, c] in faces.iter() {
+        let [va, vb, vc] = [
+            conn(x, &points[*a]),
+            conn(x, &points[*b]),
+            conn(x, &points[*c]),
+        ];
+        let (la, lb, lc) = (*va.abs(), *vb.abs(), *vc.abs());
+        let det = va.dot(&cross(&vb, &vc));
+        let denominator = la * lb * lc + va.dot(&vb) * lc + vb.dot(&vc) * la + vc.dot(&va) * lb;
+        sum += 2.0 * det.atan2(denominator);
+    }
+    sum / (4.0 * PI)
+}
+
+/// Creates the mesh of the used points and faces, interpolating the attributes of a and b
+fn build_mesh<P, MA, MB, M>(
+    a: &MA,
+    b: &MB,
+    combined: &Combined,
+    n_vertices_a: usize,
+    n_faces_a: usize,
+) -> Result<M>
+where
+    MA: IsMesh<P, Face3>,
+    MB: IsMesh<P, Face3>,
+    M: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+    P: IsBuildable3D,
+{
+    let Combined {
+        points,
+        faces,
+        face_origins,
+        hit_edges,
+    } = combined;
+    let n_vertices = points.len() - hit_edges.len();
+
+    let mut used = vec![false; points.len()];
+    for f in faces.iter() {
+        for v in f.iter() {
+            used[*v] = true;
+        }
+    }
+
+    // the weighted vertices of a or b a point originates from
+    let source = |i: usize| -> (bool, Vec<(VId, f64)>) {
+        let (in_b, weights) = if i < n_vertices {
+            (i >= n_vertices_a, vec![(i, 1.0)])
+        } else {
+            let (p, q, t) = hit_edges[i - n_vertices];
+            (p >= n_vertices_a, vec![(p, 1.0 - t), (q, t)])
+        };
+        let offset = if in_b { n_vertices_a } else { 0 };
+        (
+            in_b,
+            weights
+                .into_iter()
+                .map(|(v, w)| (VId(v - offset), w))
+                .collect(),
+        )
+    };
+
+    let mut ids = vec![None; points.len()];
+
+    let mut result = M::default();
+    result.reserve_faces(faces.len());
+    for (i, id) in ids.iter_mut().enumerate() {
+        if used[i] {
+            let (in_b, weights) = source(i);
+            let attributes = if in_b {
+                b.vertex_attributes()
+            } else {
+                a.vertex_attributes()
+            };
+            *id = Some(result.add_vertex_derived(P::new_from(&points[i]), attributes, &weights)?);
+        }
+    }
+    for ([x, y, z], origin) in faces.iter().zip(face_origins.as_slice()) {
+        let (attributes, f) = if *origin < n_faces_a {
+            (a.face_attributes(), *origin)
+        } else {
+            (b.face_attributes(), *origin - n_faces_a)
+        };
+        // unwraps fine, since all vertices of the faces were added
+        result.try_add_connection_derived(
+            ids[*x].unwrap(),
+            ids[*y].unwrap(),
+            ids[*z].unwrap(),
+            attributes,
+            FId(f),
+        )?;
+    }
+    Ok(result)
+}
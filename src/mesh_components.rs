@@ -0,0 +1,167 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Algorithms to find and separate the connected components of a mesh
+
+use crate::*;
+
+//------------------------------------------------------------------------------
+
+/// Labels the faces of a mesh by the connected component they belong to
+/// Faces are connected if they share an edge, this includes non-manifold edges used by more than two faces
+/// Meshes with duplicated vertices (e.g. STL files loaded via load_stl_mesh_duped) should be loaded unique / healed first
+/// Returns the labels and the number of components, labels are ordered by the lowest face id within each component
+pub fn face_components<P, M>(mesh: &M) -> Result<(Vec<usize>, usize)>
+where
+    M: IsMesh<P, Face3>,
+{
+    Ok(MeshTopology::new(mesh)?.face_components())
+}
+
+/// Labels the vertices of a mesh by the connected component they belong to (see face_components)
+/// Vertices which aren't part of any face have no label
+pub fn vertex_components<P, M>(mesh: &M) -> Result<(Vec<Option<usize>>, usize)>
+where
+    M: IsMesh<P, Face3>,
+{
+    let (face_labels, n_components) = face_components(mesh)?;
+    let mut labels = vec![None; mesh.num_vertices()];
+
+    for (f, label) in face_labels.into_iter().enumerate() {
+        let vids = mesh
+            .face_vertex_ids(FId(f))
+            .ok_or(ErrorKind::IncorrectFaceID)?;
+        for v in [vids.a, vids.b, vids.c].iter() {
+            labels[v.0] = Some(label);
+        }
+    }
+
+    Ok((labels, n_components))
+}
+
+//------------------------------------------------------------------------------
+
+/// Splits a mesh into one mesh per connected component (see face_components)
+pub fn split_components<P, MI, MO>(mesh: &MI) -> Result<Vec<MO>>
+where
+    MI: IsMesh<P, Face3>,
+    MO: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+{
+    let (labels, n_components) = face_components(mesh)?;
+    let mut faces = vec![Vec::new(); n_components];
+    for (f, label) in labels.into_iter().enumerate() {
+        faces[label].push(f);
+    }
+
+    faces.into_iter().map(|x| mesh_of_faces(mesh, &x)).collect()
+}
+
+/// Keeps only the connected component with the most faces (see face_components)
+pub fn keep_largest_component<P, MI, MO>(mesh: &MI) -> Result<MO>
+where
+    MI: IsMesh<P, Face3>,
+    MO: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+{
+    let (labels, n_components) = face_components(mesh)?;
+    let sizes = component_sizes(&labels, n_components);
+    // the first one on ties
+    let largest = (0..n_components).rev().max_by_key(|x| sizes[*x]);
+
+    let faces = labels
+        .into_iter()
+        .enumerate()
+        .filter(|(_, label)| Some(*label) == largest)
+        .map(|(f, _)| f)
+        .collect::<Vec<_>>();
+
+    mesh_of_faces(mesh, &faces)
+}
+
+/// Removes all connected components with less than min_faces faces (see face_components)
+pub fn remove_small_components<P, MI, MO>(mesh: &MI, min_faces: usize) -> Result<MO>
+where
+    MI: IsMesh<P, Face3>,
+    MO: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+{
+    let (labels, n_components) = face_components(mesh)?;
+    let sizes = component_sizes(&labels, n_components);
+
+    let faces = labels
+        .into_iter()
+        .enumerate()
+        .filter(|(_, label)| sizes[*label] >= min_faces)
+        .map(|(f, _)| f)
+        .collect::<Vec<_>>();
+
+    mesh_of_faces(mesh, &faces)
+}
+
+//------------------------------------------------------------------------------
+
+fn component_sizes(labels: &[usize], n_components: usize) -> Vec<usize> {
+    let mut result = vec![0; n_components];
+    for label in labels {
+        result[*label] += 1;
+    }
+    result
+}
+
+/// Creates a mesh of the given faces, only containing the vertices used by them, keeping their attributes
+fn mesh_of_faces<P, MI, MO>(mesh: &MI, faces: &[usize]) -> Result<MO>
+where
+    MI: IsMesh<P, Face3>,
+    MO: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+{
+    let mut result = MO::default();
+    result.reserve_faces(faces.len());
+    let mut new_ids = vec![None; mesh.num_vertices()];
+
+    for f in faces {
+        let vids = mesh
+            .face_vertex_ids(FId(*f))
+            .ok_or(ErrorKind::IncorrectFaceID)?;
+        let mut ids = [VId(0); 3];
+        for (id, v) in ids.iter_mut().zip([vids.a, vids.b, vids.c].iter()) {
+            *id = match new_ids[v.0] {
+                Some(x) => x,
+                None => {
+                    let x = result.add_vertex_derived(
+                        mesh.vertex(*v).ok_or(ErrorKind::IncorrectVertexID)?,
+                        mesh.vertex_attributes(),
+                        &[(*v, 1.0)],
+                    )?;
+                    new_ids[v.0] = Some(x);
+                    x
+                }
+            };
+        }
+        result.try_add_connection_derived(
+            ids[0],
+            ids[1],
+            ids[2],
+            mesh.face_attributes(),
+            FId(*f),
+        )?;
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,99 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! MeshDiagnostics, a report of the topological problems of a mesh
+
+use crate::*;
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Default, Clone, PartialEq)]
+/// MeshDiagnostics, a report of the topological problems of a mesh
+pub struct MeshDiagnostics {
+    /// Edges shared by more than two faces
+    pub non_manifold_edges: Vec<(VId, VId)>,
+    /// Vertices whose faces don't form a single fan
+    pub non_manifold_vertices: Vec<VId>,
+    /// Edges whose two faces use them in the same direction
+    pub inconsistent_edges: Vec<(VId, VId)>,
+    /// Edges used by a single face
+    pub boundary_edges: Vec<(VId, VId)>,
+    /// Number of connected components (see face_components)
+    pub n_components: usize,
+}
+
+impl MeshDiagnostics {
+    /// Returns whether all edges and vertices are manifold
+    pub fn is_manifold(&self) -> bool {
+        self.non_manifold_edges.is_empty() && self.non_manifold_vertices.is_empty()
+    }
+    /// Returns whether neighbouring faces are oriented consistently
+    pub fn is_consistently_oriented(&self) -> bool {
+        self.inconsistent_edges.is_empty()
+    }
+    /// Returns whether the mesh has no boundary
+    pub fn is_closed(&self) -> bool {
+        self.boundary_edges.is_empty()
+    }
+    /// Returns whether the mesh is manifold and consistently oriented, so it can be used for HalfEdge and SearchableMesh
+    pub fn is_valid_for_half_edge(&self) -> bool {
+        self.is_manifold() && self.is_consistently_oriented()
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Analyses the topology of a mesh, reporting non-manifold edges and vertices, inconsistent orientation, boundary edges and connected components
+/// All reported edges and vertices are sorted by their ids
+pub fn diagnose_mesh<P, M>(mesh: &M) -> Result<MeshDiagnostics>
+where
+    M: IsMesh<P, Face3>,
+{
+    let topology = MeshTopology::new(mesh)?;
+    let mut result = MeshDiagnostics::default();
+
+    for ((a, b), faces) in topology.edge_faces.iter() {
+        let edge = (VId(*a), VId(*b));
+        match faces.len() {
+            1 => result.boundary_edges.push(edge),
+            2 => {
+                if faces[0].1 == faces[1].1 {
+                    result.inconsistent_edges.push(edge)
+                }
+            }
+            _ => result.non_manifold_edges.push(edge),
+        }
+    }
+
+    for v in 0..mesh.num_vertices() {
+        if topology.vertex_fans(v).len() > 1 {
+            result.non_manifold_vertices.push(VId(v));
+        }
+    }
+
+    result.non_manifold_edges.sort();
+    result.inconsistent_edges.sort();
+    result.boundary_edges.sort();
+    result.n_components = topology.face_components().1;
+
+    Ok(result)
+}
@@ -0,0 +1,312 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Distances between point clouds and meshes and between meshes
+
+use crate::*;
+
+use fnv::FnvHashSet;
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+/// DistanceReport, the distances of points towards a mesh
+pub struct DistanceReport {
+    /// The distance of each point
+    pub distances: Vec<f64>,
+    /// The smallest distance
+    pub min: f64,
+    /// The largest distance
+    pub max: f64,
+    /// The mean distance
+    pub mean: f64,
+    /// The root mean square of the distances
+    pub rms: f64,
+}
+
+impl DistanceReport {
+    fn new(distances: Vec<f64>) -> Self {
+        let n = distances.len().max(1) as f64;
+        Self {
+            min: distances.iter().cloned().fold(f64::MAX, f64::min),
+            max: distances.iter().cloned().fold(0.0, f64::max),
+            mean: distances.iter().sum::<f64>() / n,
+            rms: (distances.iter().map(|d| d * d).sum::<f64>() / n).sqrt(),
+            distances,
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+/// MeshDistanceReport, the deviation between two meshes
+pub struct MeshDistanceReport {
+    /// The distances of the samples of the first mesh towards the second one
+    pub a_to_b: DistanceReport,
+    /// The distances of the samples of the second mesh towards the first one
+    pub b_to_a: DistanceReport,
+    /// The symmetric Hausdorff distance
+    pub hausdorff: f64,
+    /// The mean distance of the samples of both meshes
+    pub mean: f64,
+    /// The root mean square of the distances of the samples of both meshes
+    pub rms: f64,
+}
+
+//------------------------------------------------------------------------------
+
+/// MeshDistanceQuery, finds the closest points on a mesh, accelerated by an AABBTree3D of its faces
+pub struct MeshDistanceQuery {
+    tree: AABBTree3D<DistanceFace>,
+}
+
+impl MeshDistanceQuery {
+    /// Creates a new query for the faces of the mesh
+    pub fn new<P, M>(mesh: &M) -> Result<Self>
+    where
+        M: IsMesh3D<P>,
+        P: IsBuildable3D,
+    {
+        let mut faces = Vec::with_capacity(mesh.num_faces());
+        for i in 0..mesh.num_faces() {
+            let [a, b, c] = mesh
+                .face_vertices(FId(i))
+                .ok_or(ErrorKind::IncorrectFaceID)?;
+            faces.push(DistanceFace::new(
+                FId(i),
+                [
+                    Point3D::new_from(&a),
+                    Point3D::new_from(&b),
+                    Point3D::new_from(&c),
+                ],
+            ));
+        }
+
+        Ok(Self {
+            tree: AABBTree3D::new_sah(faces, 4),
+        })
+    }
+
+    /// Returns the closest face and the closest point on it, None if the mesh has no faces
+    pub fn closest_point<P>(&self, p: &P) -> Option<(FId, Point3D)>
+    where
+        P: Is3D,
+    {
+        let (face, _) = self.tree.nearest(p, &|x: &DistanceFace| {
+            let [a, b, c] = &x.corners;
+            sqr_dist_point_triangle_3d(p, a, b, c)
+        })?;
+        let [a, b, c] = &face.corners;
+        Some((face.id, closest_point_on_triangle_3d(p, a, b, c)))
+    }
+
+    /// Returns the distance towards the closest face, None if the mesh has no faces
+    pub fn distance<P>(&self, p: &P) -> Option<f64>
+    where
+        P: Is3D,
+    {
+        let (_, closest) = self.closest_point(p)?;
+        Some(dist_3d(p, &closest))
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Calculates the distance of each point of the cloud towards the mesh
+/// Error if the cloud or mesh are empty
+pub fn cloud_to_mesh_distance<P, RA, PM, M>(cloud: &RA, mesh: &M) -> Result<DistanceReport>
+where
+    RA: IsRandomAccessible<P>,
+    P: Is3D,
+    M: IsMesh3D<PM>,
+    PM: IsBuildable3D,
+{
+    if cloud.len() == 0 {
+        return Err(ErrorKind::TooFewPoints);
+    }
+    let query = MeshDistanceQuery::new(mesh)?;
+
+    let mut distances = Vec::with_capacity(cloud.len());
+    for i in 0..cloud.len() {
+        distances.push(query.distance(&cloud[i]).ok_or(ErrorKind::MeshDegenerate)?);
+    }
+    Ok(DistanceReport::new(distances))
+}
+
+/// Calculates the deviation between the meshes, sampling the vertices of both meshes towards the other one
+/// For subdivisions > 1 each edge is additionally sampled at subdivisions - 1 and each face at (subdivisions - 1)(subdivisions - 2) / 2 inner points
+/// Error if any mesh is empty
+pub fn mesh_to_mesh_distance<PA, PB, MA, MB>(
+    a: &MA,
+    b: &MB,
+    subdivisions: usize,
+) -> Result<MeshDistanceReport>
+where
+    MA: IsMesh3D<PA>,
+    MB: IsMesh3D<PB>,
+    PA: IsBuildable3D,
+    PB: IsBuildable3D,
+{
+    let a_to_b = samples_to_mesh(&samples(a, subdivisions)?, b)?;
+    let b_to_a = samples_to_mesh(&samples(b, subdivisions)?, a)?;
+
+    let n = (a_to_b.distances.len() + b_to_a.distances.len()) as f64;
+    let all = || a_to_b.distances.iter().chain(b_to_a.distances.iter());
+    let mean = all().sum::<f64>() / n;
+    let rms = (all().map(|d| d * d).sum::<f64>() / n).sqrt();
+
+    Ok(MeshDistanceReport {
+        hausdorff: a_to_b.max.max(b_to_a.max),
+        mean,
+        rms,
+        a_to_b,
+        b_to_a,
+    })
+}
+
+//------------------------------------------------------------------------------
+
+/// Face within the AABBTree3D, with a padded bounding box since axis aligned faces are flat
+#[derive(Clone)]
+struct DistanceFace {
+    id: FId,
+    corners: [Point3D; 3],
+    bb: BoundingBox3D,
+}
+
+impl DistanceFace {
+    fn new(id: FId, corners: [Point3D; 3]) -> Self {
+        let [a, b, c] = &corners;
+        let mut min = [
+            a.x.min(b.x).min(c.x),
+            a.y.min(b.y).min(c.y),
+            a.z.min(b.z).min(c.z),
+        ];
+        let mut max = [
+            a.x.max(b.x).max(c.x),
+            a.y.max(b.y).max(c.y),
+            a.z.max(b.z).max(c.z),
+        ];
+        for i in 0..3 {
+            let pad = 1e-9 * (1.0 + min[i].abs().max(max[i].abs()));
+            min[i] -= pad;
+            max[i] += pad;
+        }
+        // unwrap fine, since padded
+        let bb = BoundingBox3D::new(
+            &Point3D::new(min[0], min[1], min[2]),
+            &Point3D::new(max[0], max[1], max[2]),
+        )
+        .unwrap();
+        Self { id, corners, bb }
+    }
+}
+
+impl HasBoundingBox3D for DistanceFace {
+    fn bounding_box(&self) -> BoundingBox3D {
+        self.bb.clone()
+    }
+}
+
+impl HasBoundingBox3DMaybe for DistanceFace {
+    fn bounding_box_maybe(&self) -> Option<BoundingBox3D> {
+        Some(self.bb.clone())
+    }
+}
+
+//------------------------------------------------------------------------------
+
+fn samples_to_mesh<P, M>(samples: &[Point3D], mesh: &M) -> Result<DistanceReport>
+where
+    M: IsMesh3D<P>,
+    P: IsBuildable3D,
+{
+    let query = MeshDistanceQuery::new(mesh)?;
+    let mut distances = Vec::with_capacity(samples.len());
+    for p in samples.iter() {
+        distances.push(query.distance(p).ok_or(ErrorKind::MeshDegenerate)?);
+    }
+    Ok(DistanceReport::new(distances))
+}
+
+/// The vertices and the points on the edges and within the faces of the mesh, see mesh_to_mesh_distance
+fn samples<P, M>(mesh: &M, subdivisions: usize) -> Result<Vec<Point3D>>
+where
+    M: IsMesh3D<P>,
+    P: IsBuildable3D,
+{
+    if mesh.num_faces() == 0 {
+        return Err(ErrorKind::MeshDegenerate);
+    }
+
+    let mut result = Vec::new();
+    for i in 0..mesh.num_vertices() {
+        let v = mesh.vertex(VId(i)).ok_or(ErrorKind::IncorrectVertexID)?;
+        result.push(Point3D::new_from(&v));
+    }
+    if subdivisions < 2 {
+        return Ok(result);
+    }
+
+    let n = subdivisions as f64;
+    let at = |vs: &[P; 3], weights: [f64; 3]| {
+        Point3D::new(
+            (0..3).map(|k| weights[k] * vs[k].x()).sum::<f64>(),
+            (0..3).map(|k| weights[k] * vs[k].y()).sum::<f64>(),
+            (0..3).map(|k| weights[k] * vs[k].z()).sum::<f64>(),
+        )
+    };
+
+    let mut edges = FnvHashSet::default();
+    for i in 0..mesh.num_faces() {
+        let f = mesh
+            .face_vertex_ids(FId(i))
+            .ok_or(ErrorKind::IncorrectFaceID)?;
+        let vs = mesh
+            .face_vertices(FId(i))
+            .ok_or(ErrorKind::IncorrectFaceID)?;
+        let ids = [f.a.0, f.b.0, f.c.0];
+
+        for k in 0..3 {
+            let (p, q) = (ids[k], ids[(k + 1) % 3]);
+            if !edges.insert(edge_key(p, q)) {
+                continue;
+            }
+            for s in 1..subdivisions {
+                let t = s as f64 / n;
+                let mut weights = [0.0; 3];
+                weights[k] = 1.0 - t;
+                weights[(k + 1) % 3] = t;
+                result.push(at(&vs, weights));
+            }
+        }
+
+        for s in 1..subdivisions {
+            for t in 1..(subdivisions - s) {
+                let (ws, wt) = (s as f64 / n, t as f64 / n);
+                result.push(at(&vs, [ws, wt, 1.0 - ws - wt]));
+            }
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,249 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Helpers to analyse the connectivity and geometry of meshes, shared by the mesh algorithms
+
+use crate::*;
+
+use fnv::FnvHashMap;
+
+//------------------------------------------------------------------------------
+
+/// Connectivity of a mesh, edges are keyed by their vertex ids with the smaller one first
+pub(crate) struct MeshTopology {
+    pub faces: Vec<[usize; 3]>,
+    pub vertex_faces: Vec<Vec<usize>>,
+    /// The faces using the edge and whether they use it in the direction of the key
+    pub edge_faces: FnvHashMap<(usize, usize), Vec<(usize, bool)>>,
+}
+
+impl MeshTopology {
+    pub fn new<P, M>(mesh: &M) -> Result<Self>
+    where
+        M: IsMesh<P, Face3>,
+    {
+        let nv = mesh.num_vertices();
+        let nf = mesh.num_faces();
+
+        let mut faces = Vec::with_capacity(nf);
+        let mut vertex_faces = vec![Vec::new(); nv];
+        let mut edge_faces = FnvHashMap::default();
+
+        for i in 0..nf {
+            let f = mesh
+                .face_vertex_ids(FId(i))
+                .ok_or(ErrorKind::IncorrectFaceID)?;
+            let f = [f.a.0, f.b.0, f.c.0];
+            for k in 0..3 {
+                let (a, b) = (f[k], f[(k + 1) % 3]);
+                if a >= nv {
+                    return Err(ErrorKind::IncorrectVertexID);
+                }
+                vertex_faces[a].push(i);
+                edge_faces
+                    .entry(edge_key(a, b))
+                    .or_insert_with(Vec::new)
+                    .push((i, a < b));
+            }
+            faces.push(f);
+        }
+
+        Ok(Self {
+            faces,
+            vertex_faces,
+            edge_faces,
+        })
+    }
+
+    /// The faces using the edge between a and b
+    pub fn faces_of_edge(&self, a: usize, b: usize) -> &[(usize, bool)] {
+        match self.edge_faces.get(&edge_key(a, b)) {
+            None => &[],
+            Some(x) => x,
+        }
+    }
+
+    /// Whether the edge between a and b is shared by exactly two faces
+    pub fn is_manifold_edge(&self, a: usize, b: usize) -> bool {
+        self.faces_of_edge(a, b).len() == 2
+    }
+
+    /// Groups the faces of a vertex into fans, connected via manifold edges
+    pub fn vertex_fans(&self, v: usize) -> Vec<Vec<usize>> {
+        self.vertex_fans_where(v, |_, _| true)
+    }
+
+    /// Groups the faces of a vertex into fans, connected via manifold edges for which connected(face, other_face) holds
+    pub fn vertex_fans_where<F>(&self, v: usize, connected: F) -> Vec<Vec<usize>>
+    where
+        F: Fn(usize, usize) -> bool,
+    {
+        let faces: &[usize] = &self.vertex_faces[v];
+        let mut assigned = vec![false; faces.len()];
+        let mut result = Vec::new();
+
+        for start in 0..faces.len() {
+            if assigned[start] {
+                continue;
+            }
+            assigned[start] = true;
+            let mut fan = vec![faces[start]];
+            let mut frontier = vec![faces[start]];
+
+            while let Some(f) = frontier.pop() {
+                for other in self.faces[f].iter() {
+                    if *other == v || !self.is_manifold_edge(v, *other) {
+                        continue;
+                    }
+                    for (neighbour, _) in self.faces_of_edge(v, *other) {
+                        if let Some(i) = faces.iter().position(|x| x == neighbour) {
+                            if !assigned[i] && connected(f, *neighbour) {
+                                assigned[i] = true;
+                                fan.push(*neighbour);
+                                frontier.push(*neighbour);
+                            }
+                        }
+                    }
+                }
+            }
+            result.push(fan);
+        }
+        result
+    }
+
+    /// Labels the faces by the connected component they belong to, faces are connected via shared edges (including non-manifold ones)
+    /// Returns the labels and the number of components
+    pub fn face_components(&self) -> (Vec<usize>, usize) {
+        let nf = self.faces.len();
+        let mut labels = vec![usize::MAX; nf];
+        let mut n_components = 0;
+        let mut frontier = Vec::new();
+
+        for start in 0..nf {
+            if labels[start] != usize::MAX {
+                continue;
+            }
+            labels[start] = n_components;
+            frontier.push(start);
+
+            while let Some(f) = frontier.pop() {
+                for k in 0..3 {
+                    let (a, b) = (self.faces[f][k], self.faces[f][(k + 1) % 3]);
+                    for (neighbour, _) in self.faces_of_edge(a, b) {
+                        if labels[*neighbour] == usize::MAX {
+                            labels[*neighbour] = n_components;
+                            frontier.push(*neighbour);
+                        }
+                    }
+                }
+            }
+            n_components += 1;
+        }
+
+        (labels, n_components)
+    }
+}
+
+//------------------------------------------------------------------------------
+
+pub(crate) fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// Cotangent of the angle between a and b
+pub(crate) fn cot(a: &Point3D, b: &Point3D) -> f64 {
+    let sin = *cross::<_, Point3D>(a, b).abs();
+    if sin == 0.0 {
+        0.0
+    } else {
+        a.dot(b) / sin
+    }
+}
+
+/// A unit vector orthogonal to the unit vector
+pub(crate) fn any_orthogonal(n: &Point3D) -> Point3D {
+    let other = if n.x.abs() < 0.9 {
+        Point3D::new(1.0, 0.0, 0.0)
+    } else {
+        Point3D::new(0.0, 1.0, 0.0)
+    };
+    let u: Point3D = cross(n, &other);
+    let length = *u.abs();
+    Point3D::new(u.x / length, u.y / length, u.z / length)
+}
+
+pub(crate) fn orient(a: &[f64; 2], b: &[f64; 2], c: &[f64; 2]) -> f64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// Triangulates the counter clockwise polygon by repeatedly cutting off ears
+pub(crate) fn ear_clip(
+    positions: &[[f64; 2]],
+    mut polygon: Vec<usize>,
+    result: &mut Vec<[usize; 3]>,
+) {
+    while polygon.len() > 3 {
+        let n = polygon.len();
+        let corners = |i: usize| (polygon[(i + n - 1) % n], polygon[i], polygon[(i + 1) % n]);
+
+        let is_ear = |i: usize| {
+            let (a, b, c) = corners(i);
+            let (pa, pb, pc) = (&positions[a], &positions[b], &positions[c]);
+            orient(pa, pb, pc) > 0.0
+                && polygon.as_slice().iter().all(|v| {
+                    let pv = &positions[*v];
+                    *v == a
+                        || *v == b
+                        || *v == c
+                        || orient(pa, pb, pv) < 0.0
+                        || orient(pb, pc, pv) < 0.0
+                        || orient(pc, pa, pv) < 0.0
+                })
+        };
+
+        // fall back to the most convex corner if numerical issues prevent finding an ear
+        let ear = (0..n).find(|i| is_ear(*i)).unwrap_or_else(|| {
+            (0..n)
+                .max_by(|i, j| {
+                    let area = |k: usize| {
+                        let (a, b, c) = corners(k);
+                        orient(&positions[a], &positions[b], &positions[c])
+                    };
+                    area(*i)
+                        .partial_cmp(&area(*j))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .unwrap_or(0)
+        });
+
+        let (a, b, c) = corners(ear);
+        result.push([a, b, c]);
+        polygon.remove(ear);
+    }
+    if polygon.len() == 3 {
+        result.push([polygon[0], polygon[1], polygon[2]]);
+    }
+}
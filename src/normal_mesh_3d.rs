@@ -0,0 +1,146 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! NormalMesh3D, a mesh with tri-faces within 3D space and a normal per vertex
+
+use crate::*;
+
+//------------------------------------------------------------------------------
+
+#[derive(Default, Debug, PartialEq, Clone)]
+/// NormalMesh3D, a mesh with tri-faces within 3D space and a normal per vertex
+/// Normals can be saved via the save_*_with_normals functions, .stl files only store face normals which are derived from the positions
+pub struct NormalMesh3D<P, ID, IC>
+where
+    P: Is3D,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+{
+    mesh: Mesh3D<P, ID, IC>,
+    normals: Vec<Norm3D>,
+}
+
+impl<P, ID, IC> NormalMesh3D<P, ID, IC>
+where
+    P: Is3D + Clone,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+{
+    /// Creates a new NormalMesh3D from a mesh and its vertex normals. Error if the number of normals doesn't match the number of vertices
+    pub fn new(mesh: Mesh3D<P, ID, IC>, normals: Vec<Norm3D>) -> Result<Self> {
+        if mesh.num_vertices() != normals.len() {
+            return Err(ErrorKind::DimensionsDontMatch);
+        }
+        Ok(Self { mesh, normals })
+    }
+    /// Creates a new NormalMesh3D, calculating the vertex normals of the mesh
+    pub fn with_normals(mesh: Mesh3D<P, ID, IC>, weighting: &NormalWeighting) -> Result<Self> {
+        let normals = vertex_normals(&mesh, weighting)?;
+        Ok(Self { mesh, normals })
+    }
+    /// Returns the mesh
+    pub fn mesh(&self) -> &Mesh3D<P, ID, IC> {
+        &self.mesh
+    }
+    /// Returns the vertex normals
+    pub fn normals(&self) -> &[Norm3D] {
+        &self.normals
+    }
+    /// Returns the normal of a vertex. None if id invalid
+    pub fn vertex_normal(&self, vertexid: VId) -> Option<Norm3D> {
+        self.normals.get(vertexid.0).cloned()
+    }
+    /// Changes the normal of a vertex. Error if id invalid
+    pub fn change_vertex_normal(&mut self, vertexid: VId, normal: Norm3D) -> Result<()> {
+        let n = self
+            .normals
+            .get_mut(vertexid.0)
+            .ok_or(ErrorKind::IncorrectVertexID)?;
+        *n = normal;
+        Ok(())
+    }
+}
+
+impl<P, ID, IC> NormalMesh3D<P, ID, IC>
+where
+    P: IsEditable3D + IsBuildable3D + Clone + Default,
+    ID: IsDataContainer<P> + Default,
+    IC: IsIndexContainer + Default,
+{
+    /// Creates a new NormalMesh3D, splitting the vertices at creases (see split_creases) before calculating the vertex normals
+    pub fn with_creases(
+        mesh: &Mesh3D<P, ID, IC>,
+        crease_angle: Rad,
+        weighting: &NormalWeighting,
+    ) -> Result<Self> {
+        Self::with_normals(split_creases(mesh, crease_angle)?, weighting)
+    }
+}
+
+impl<P, ID, IC> IsMesh<P, Face3> for NormalMesh3D<P, ID, IC>
+where
+    P: Is3D + Clone,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+{
+    fn num_faces(&self) -> usize {
+        self.mesh.num_faces()
+    }
+
+    fn num_vertices(&self) -> usize {
+        self.mesh.num_vertices()
+    }
+
+    fn face_vertex_ids(&self, faceid: FId) -> Option<Face3> {
+        self.mesh.face_vertex_ids(faceid)
+    }
+
+    fn face_vertices(&self, faceid: FId) -> Option<[P; 3]> {
+        self.mesh.face_vertices(faceid)
+    }
+
+    fn vertex(&self, vertexid: VId) -> Option<P> {
+        self.mesh.vertex(vertexid)
+    }
+}
+
+impl<P, ID, IC> HasBoundingBox3DMaybe for NormalMesh3D<P, ID, IC>
+where
+    P: Is3D,
+    ID: IsDataContainer<P> + HasBoundingBox3DMaybe,
+    IC: IsIndexContainer,
+{
+    fn bounding_box_maybe(&self) -> Option<BoundingBox3D> {
+        self.mesh.bounding_box_maybe()
+    }
+}
+
+impl<P, ID, IC> From<NormalMesh3D<P, ID, IC>> for (Mesh3D<P, ID, IC>, Vec<Norm3D>)
+where
+    P: Is3D,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+{
+    fn from(nm: NormalMesh3D<P, ID, IC>) -> Self {
+        (nm.mesh, nm.normals)
+    }
+}
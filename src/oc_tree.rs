@@ -26,6 +26,9 @@ use std::{collections::HashSet, iter::IntoIterator};
 
 use crate::*;
 
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
 //------------------------------------------------------------------------------
 
 #[derive(Default, Debug, PartialEq, PartialOrd, Ord, Eq, Clone, Hash)]
@@ -70,6 +73,29 @@ where
     }
 }
 
+#[cfg(feature = "rayon")]
+impl<P> OcTree<P>
+where
+    P: IsBuildable3D + Clone + Default + Send,
+{
+    /// Creates the tree from a given point cloud, building the subtrees in parallel
+    pub fn build_par(&mut self, pc: PointCloud3D<P>) -> Result<()> {
+        self.bb = pc
+            .bounding_box_maybe()
+            .ok_or(ErrorKind::BoundingBoxMissing)?;
+        let mut unique_data = Vec::new();
+        let mut set = HashSet::new();
+        for p in pc.data {
+            set.insert(p);
+        }
+
+        unique_data.extend(set);
+        self.root = Some(OcNode::new_par(&self.bb, unique_data)?);
+
+        Ok(())
+    }
+}
+
 impl<P> IsOcTree<P> for OcTree<P>
 where
     P: IsBuildable3D + Clone + Default,
@@ -195,6 +221,39 @@ where
         if pc.len() == 1 {
             return Ok(OcNode::Leaf(pc[0].clone()));
         };
+
+        let children = Self::split(bb, pc)?
+            .into_iter()
+            .map(|(pc, bb)| Self::build_subnode(pc, &bb))
+            .collect();
+
+        Ok(OcNode::Node(Self::internal_of(children)))
+    }
+
+    #[cfg(feature = "rayon")]
+    pub fn new_par(bb: &BoundingBox3D, pc: Vec<P>) -> Result<OcNode<P>>
+    where
+        P: Send,
+    {
+        if pc.len() < PARALLEL_MIN_SIZE {
+            return Self::new(bb, pc);
+        }
+
+        let children = Self::split(bb, pc)?
+            .into_par_iter()
+            .map(|(pc, bb)| match pc.len() {
+                0 => None,
+                _ => match OcNode::new_par(&bb, pc) {
+                    Err(_) => None,
+                    Ok(x) => Some(Box::new(x)),
+                },
+            })
+            .collect();
+
+        Ok(OcNode::Node(Self::internal_of(children)))
+    }
+    /// Splits the data into the sub nodes' data and bounding boxes (order PPP, PPN, PNP, PNN, NPP, NPN, NNP, NNN)
+    fn split(bb: &BoundingBox3D, pc: Vec<P>) -> Result<Vec<(Vec<P>, BoundingBox3D)>> {
         let mut pcppp = Vec::new();
         let mut pcppn = Vec::new();
         let mut pcpnp = Vec::new();
@@ -233,27 +292,31 @@ where
             }
         }
 
-        let ppp = Self::build_subnode(pcppp, &bbppp);
-        let ppn = Self::build_subnode(pcppn, &bbppn);
-        let pnp = Self::build_subnode(pcpnp, &bbpnp);
-        let pnn = Self::build_subnode(pcpnn, &bbpnn);
-        let npp = Self::build_subnode(pcnpp, &bbnpp);
-        let npn = Self::build_subnode(pcnpn, &bbnpn);
-        let nnp = Self::build_subnode(pcnnp, &bbnnp);
-        let nnn = Self::build_subnode(pcnnn, &bbnnn);
-
-        let result: Internal<P> = Internal {
-            ppp,
-            ppn,
-            pnp,
-            pnn,
-            npp,
-            npn,
-            nnp,
-            nnn,
-        };
-
-        Ok(OcNode::Node(result))
+        Ok(vec![
+            (pcppp, bbppp),
+            (pcppn, bbppn),
+            (pcpnp, bbpnp),
+            (pcpnn, bbpnn),
+            (pcnpp, bbnpp),
+            (pcnpn, bbnpn),
+            (pcnnp, bbnnp),
+            (pcnnn, bbnnn),
+        ])
+    }
+    /// Creates the internal node from the children (same order as split)
+    fn internal_of(children: Vec<Option<Box<OcNode<P>>>>) -> Internal<P> {
+        let mut children = children.into_iter();
+        // unwraps fine, since split always returns 8 children
+        Internal {
+            ppp: children.next().unwrap(),
+            ppn: children.next().unwrap(),
+            pnp: children.next().unwrap(),
+            pnn: children.next().unwrap(),
+            npp: children.next().unwrap(),
+            npn: children.next().unwrap(),
+            nnp: children.next().unwrap(),
+            nnn: children.next().unwrap(),
+        }
     }
     /// Calculates the min and max values of sub nodes of an OcTree
     fn calc_sub_min_max(dir: Direction, bb: &BoundingBox3D) -> Result<BoundingBox3D> {
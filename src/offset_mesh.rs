@@ -0,0 +1,228 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Offsetting and shelling of meshes along their vertex normals
+
+use crate::*;
+
+use fnv::FnvHashSet;
+
+//------------------------------------------------------------------------------
+
+/// Algorithm to offset the surface of a mesh, moving each vertex along its normal by the signed distance
+/// The movement is scaled so flat regions, edges and corners keep the distance towards the original faces
+/// Self intersections are mitigated by shortening the movement of vertices whose faces would flip, intersections of distant parts aren't resolved
+/// The mesh must be manifold and consistently oriented
+/// The vertices and faces keep their attributes
+pub fn offset_mesh<P, MI, MO>(mesh: &MI, distance: f64) -> Result<MO>
+where
+    MI: IsMesh3D<P>,
+    MO: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+    P: IsBuildable3D,
+{
+    let (points, faces) = parts_of(mesh)?;
+    let offset = offset_points(mesh, &points, &faces, distance)?;
+
+    let mut result = MO::default();
+    result.reserve_vertices(offset.len());
+    result.reserve_faces(faces.len());
+    for (i, p) in offset.iter().enumerate() {
+        result.add_vertex_derived(P::new_from(p), mesh.vertex_attributes(), &[(VId(i), 1.0)])?;
+    }
+    for (f, [a, b, c]) in faces.iter().enumerate() {
+        result.try_add_connection_derived(
+            VId(*a),
+            VId(*b),
+            VId(*c),
+            mesh.face_attributes(),
+            FId(f),
+        )?;
+    }
+    Ok(result)
+}
+
+/// Algorithm to create a solid with walls of the given thickness from a mesh
+/// The walls grow inwards, against the normals of the mesh, for closed meshes the result has an inner cavity
+/// Open meshes are closed by walls along their boundaries
+/// The mesh must be manifold and consistently oriented, see offset_mesh
+/// Both sides keep the attributes of the vertices and faces they are copied from, the boundary walls get default face attributes
+pub fn shell_mesh<P, MI, MO>(mesh: &MI, thickness: Positive) -> Result<MO>
+where
+    MI: IsMesh3D<P>,
+    MO: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+    P: IsBuildable3D,
+{
+    let (points, faces) = parts_of(mesh)?;
+    let inner = offset_points(mesh, &points, &faces, -*thickness)?;
+    let n = points.len();
+
+    let mut result = MO::default();
+    result.reserve_vertices(2 * n);
+    result.reserve_faces(2 * faces.len());
+    for (i, p) in points.iter().chain(inner.iter()).enumerate() {
+        result.add_vertex_derived(
+            P::new_from(p),
+            mesh.vertex_attributes(),
+            &[(VId(i % n), 1.0)],
+        )?;
+    }
+
+    let mut directed = FnvHashSet::default();
+    for (f, [a, b, c]) in faces.iter().enumerate() {
+        let attributes = mesh.face_attributes();
+        result.try_add_connection_derived(VId(*a), VId(*b), VId(*c), attributes, FId(f))?;
+        result.try_add_connection_derived(
+            VId(*a + n),
+            VId(*c + n),
+            VId(*b + n),
+            attributes,
+            FId(f),
+        )?;
+        directed.extend([(*a, *b), (*b, *c), (*c, *a)].iter().cloned());
+    }
+
+    // the boundary edges are connected to their copies on the inner side
+    for [a, b, c] in faces.iter() {
+        for (p, q) in [(*a, *b), (*b, *c), (*c, *a)].iter() {
+            if directed.contains(&(*q, *p)) {
+                continue;
+            }
+            result.try_add_connection(VId(*q), VId(*p), VId(*p + n))?;
+            result.try_add_connection(VId(*q), VId(*p + n), VId(*q + n))?;
+        }
+    }
+    Ok(result)
+}
+
+//------------------------------------------------------------------------------
+
+/// Smallest cosine between a vertex normal and the normals of its faces, limiting the scaling of the movement
+const MIN_COS: f64 = 0.25;
+
+/// Maximum number of times the movement of vertices is shortened to prevent flipped faces
+const MAX_SHORTENINGS: usize = 10;
+
+fn parts_of<P, M>(mesh: &M) -> Result<(Vec<Point3D>, Vec<[usize; 3]>)>
+where
+    M: IsMesh3D<P>,
+    P: IsBuildable3D,
+{
+    if !diagnose_mesh(mesh)?.is_valid_for_half_edge() {
+        return Err(ErrorKind::MeshNotManifold);
+    }
+
+    let mut points = Vec::with_capacity(mesh.num_vertices());
+    for i in 0..mesh.num_vertices() {
+        let v = mesh.vertex(VId(i)).ok_or(ErrorKind::IncorrectVertexID)?;
+        points.push(Point3D::new_from(&v));
+    }
+
+    let mut faces = Vec::with_capacity(mesh.num_faces());
+    for i in 0..mesh.num_faces() {
+        let f = mesh
+            .face_vertex_ids(FId(i))
+            .ok_or(ErrorKind::IncorrectFaceID)?;
+        faces.push([f.a.0, f.b.0, f.c.0]);
+    }
+    Ok((points, faces))
+}
+
+/// The points moved along the vertex normals, see offset_mesh
+fn offset_points<P, M>(
+    mesh: &M,
+    points: &[Point3D],
+    faces: &[[usize; 3]],
+    distance: f64,
+) -> Result<Vec<Point3D>>
+where
+    M: IsMesh3D<P>,
+    P: IsBuildable3D,
+{
+    let normals = vertex_normals(mesh, &NormalWeighting::Angle)?;
+    let face_normals = faces
+        .iter()
+        .map(|f| face_normal_of(points, f))
+        .collect::<Vec<_>>();
+
+    // mean cosine between the vertex normal and the normals of its faces
+    let mut cos_sums = vec![0.0; points.len()];
+    let mut n_faces = vec![0usize; points.len()];
+    for (f, normal) in faces.iter().zip(face_normals.iter()) {
+        let normal = match Norm3D::new(normal.clone()) {
+            Ok(x) => x,
+            Err(_) => continue,
+        };
+        for v in f.iter() {
+            cos_sums[*v] += normals[*v].dot(&normal);
+            n_faces[*v] += 1;
+        }
+    }
+
+    let movements = (0..points.len())
+        .map(|v| {
+            if n_faces[v] == 0 {
+                return Point3D::default();
+            }
+            let cos = (cos_sums[v] / n_faces[v] as f64).max(MIN_COS);
+            Point3D::new_from(&normals[v]) * (distance / cos)
+        })
+        .collect::<Vec<_>>();
+
+    let mut factors = vec![1.0; points.len()];
+    let moved = |factors: &[f64]| {
+        points
+            .iter()
+            .zip(movements.iter())
+            .zip(factors.iter())
+            .map(|((p, m), f)| p.clone() + m.clone() * *f)
+            .collect::<Vec<_>>()
+    };
+
+    for i in 0..=MAX_SHORTENINGS {
+        let result = moved(&factors);
+        let mut flipped = false;
+        for (f, normal) in faces.iter().zip(face_normals.iter()) {
+            if normal.dot(&face_normal_of(&result, f)) > 0.0 {
+                continue;
+            }
+            flipped = true;
+            for v in f.iter() {
+                factors[*v] = if i == MAX_SHORTENINGS {
+                    0.0
+                } else {
+                    0.5 * factors[*v]
+                };
+            }
+        }
+        if !flipped {
+            return Ok(result);
+        }
+    }
+    Ok(moved(&factors))
+}
+
+fn face_normal_of(points: &[Point3D], [a, b, c]: &[usize; 3]) -> Point3D {
+    cross(
+        &conn(&points[*a], &points[*b]),
+        &conn(&points[*a], &points[*c]),
+    )
+}
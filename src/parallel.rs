@@ -0,0 +1,117 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Parallel batch queries and helpers, available with the rayon feature
+
+use crate::*;
+
+use rayon::prelude::*;
+
+use std::collections::HashSet;
+
+//------------------------------------------------------------------------------
+
+/// Data sizes below this are processed sequentially, since the overhead of spawning tasks would dominate
+pub(crate) const PARALLEL_MIN_SIZE: usize = 1024;
+
+//------------------------------------------------------------------------------
+
+/// Searches the k nearest neighbours of all searches in parallel
+pub fn knearest_batch<S, PSearch, PFind>(
+    searchable: &S,
+    searches: &[PSearch],
+    n: usize,
+) -> Vec<Vec<PFind>>
+where
+    S: IsKNearestSearchable<PSearch, PFind> + Sync,
+    PSearch: Sync,
+    PFind: Send,
+{
+    searches
+        .par_iter()
+        .map(|search| {
+            let mut result = Vec::with_capacity(n);
+            searchable.knearest(search, n, &mut result);
+            result
+        })
+        .collect()
+}
+
+/// Searches the nearest neighbour of all searches in parallel
+pub fn nearest_batch<S, PSearch, PFind>(searchable: &S, searches: &[PSearch]) -> Vec<Option<PFind>>
+where
+    S: IsKNearestSearchable<PSearch, PFind> + Sync,
+    PSearch: Sync,
+    PFind: Send,
+{
+    searches
+        .par_iter()
+        .map(|search| searchable.nearest(search))
+        .collect()
+}
+
+/// Searches all elements within the spheres in parallel
+pub fn in_sphere_batch<S, T>(searchable: &S, spheres: &[Sphere]) -> Vec<Vec<T>>
+where
+    S: IsSphereSearchable<T> + Sync,
+    T: Send,
+{
+    spheres
+        .par_iter()
+        .map(|sphere| {
+            let mut result = Vec::new();
+            searchable.in_sphere(sphere, &mut result);
+            result
+        })
+        .collect()
+}
+
+//------------------------------------------------------------------------------
+
+/// Restricts the view to the indices within 0..n for which is_allowed holds, evaluated in parallel
+pub(crate) fn filter_view_par(
+    n: usize,
+    view: &mut View,
+    is_allowed: &(dyn Fn(usize) -> bool + Sync),
+) {
+    if n == 0 {
+        *view = View::Full;
+        return;
+    }
+    match view {
+        View::Full => {
+            let indices: Vec<usize> = (0..n).into_par_iter().filter(|i| is_allowed(*i)).collect();
+            *view = View::Restricted(indices.into_iter().collect::<HashSet<_>>());
+        }
+        View::Restricted(indices) => {
+            let indices_to_remove: Vec<usize> = indices
+                .par_iter()
+                .filter(|i| **i >= n || !is_allowed(**i))
+                .cloned()
+                .collect();
+
+            for index_to_remove in indices_to_remove {
+                indices.remove(&index_to_remove);
+            }
+        }
+    }
+}
@@ -0,0 +1,311 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! UV parameterization of meshes with the topology of a disk
+
+use crate::*;
+
+use std::f64::consts::PI;
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+/// FaceDistortion, describes how a face of a mesh is distorted by its parameterization
+pub struct FaceDistortion {
+    /// The largest factor by which lengths within the face are scaled
+    pub max_stretch: f64,
+    /// The smallest factor by which lengths within the face are scaled
+    pub min_stretch: f64,
+    /// Ratio of max_stretch and min_stretch, 1.0 if angles are preserved
+    pub conformal: f64,
+    /// Ratio of the parameterized and the original area, negative if the face got flipped
+    pub area: f64,
+}
+
+//------------------------------------------------------------------------------
+
+/// Maps a mesh with the topology of a disk to the plane by fixing its boundary to the unit circle and placing each inner vertex at the weighted center of its neighbours
+/// Uniform weights result in Tutte's embedding which is guaranteed to be free of flipped faces, cotangent weights result in the harmonic map which preserves angles better
+/// Returns the UV of each vertex
+pub fn harmonic_parameterization<P2, P, M>(
+    mesh: &M,
+    weighting: &LaplacianWeighting,
+) -> Result<Vec<P2>>
+where
+    M: IsSearchableMesh<P, Face3>,
+    P: Is3D,
+    P2: IsBuildable2D,
+{
+    let disk = Disk::new(mesh)?;
+    let n = disk.positions.len();
+
+    let mut laplacian = SparseMatrix::new(n);
+    match weighting {
+        LaplacianWeighting::Uniform => {
+            for (a, b) in disk.topology.edge_faces.keys() {
+                laplacian.add_edge(*a, *b, 1.0);
+            }
+        }
+        LaplacianWeighting::Cotangent => {
+            for [a, b, c] in disk.topology.faces.iter() {
+                for (i, j, k) in [(a, b, c), (b, c, a), (c, a, b)].iter() {
+                    let (pi, pj, pk) = (
+                        &disk.positions[**i],
+                        &disk.positions[**j],
+                        &disk.positions[**k],
+                    );
+                    laplacian.add_edge(**i, **j, 0.5 * cot(&conn(pk, pi), &conn(pk, pj)));
+                }
+            }
+        }
+    }
+
+    // the boundary is placed on the circle according to its arc length
+    let lengths: Vec<f64> = (0..disk.boundary.len())
+        .map(|i| {
+            dist_3d(
+                &disk.positions[disk.boundary[i]],
+                &disk.positions[disk.boundary[(i + 1) % disk.boundary.len()]],
+            )
+        })
+        .collect();
+    let total: f64 = lengths.iter().sum();
+    if total == 0.0 {
+        return Err(ErrorKind::MeshDegenerate);
+    }
+
+    let mut fixed_u = vec![None; n];
+    let mut fixed_v = vec![None; n];
+    let mut length = 0.0;
+    for (v, l) in disk.boundary.as_slice().iter().zip(lengths.iter()) {
+        let angle = 2.0 * PI * length / total;
+        fixed_u[*v] = Some(angle.cos());
+        fixed_v[*v] = Some(angle.sin());
+        length += l;
+    }
+
+    let zero = vec![0.0; n];
+    let us = laplacian.solve_fixed(&zero, &fixed_u);
+    let vs = laplacian.solve_fixed(&zero, &fixed_v);
+
+    Ok(us.into_iter().zip(vs).map(|(u, v)| P2::new(u, v)).collect())
+}
+
+/// Maps a mesh with the topology of a disk to the plane via least squares conformal maps (Lévy et al.), preserving angles as well as possible
+/// The boundary is free, the two boundary vertices furthest apart are fixed to (0, 0) and (distance, 0) to keep the scale of the mesh
+/// Returns the UV of each vertex
+pub fn lscm_parameterization<P2, P, M>(mesh: &M) -> Result<Vec<P2>>
+where
+    M: IsSearchableMesh<P, Face3>,
+    P: Is3D,
+    P2: IsBuildable2D,
+{
+    let disk = Disk::new(mesh)?;
+    let n = disk.positions.len();
+
+    // normal equations of the conformal energy, with u of vertex i at 2 * i and v at 2 * i + 1
+    let mut system = SparseMatrix::new(2 * n);
+    for face in disk.topology.faces.iter() {
+        let local = match local_coordinates(&disk.positions, face) {
+            None => continue,
+            Some(x) => x,
+        };
+        let scale = 1.0 / (local[1][0] * local[2][1]).sqrt();
+
+        // real and imaginary part of the complex equation of the face
+        let mut rows = [[(0, 0.0); 6]; 2];
+        for j in 0..3 {
+            let (k, l) = ((j + 1) % 3, (j + 2) % 3);
+            let a = scale * (local[k][0] - local[l][0]);
+            let b = scale * (local[k][1] - local[l][1]);
+            let (u, v) = (2 * face[j], 2 * face[j] + 1);
+            rows[0][2 * j] = (u, a);
+            rows[0][2 * j + 1] = (v, -b);
+            rows[1][2 * j] = (u, b);
+            rows[1][2 * j + 1] = (v, a);
+        }
+
+        for row in rows.iter() {
+            for (p, (i, x)) in row.iter().enumerate() {
+                system.add(*i, *i, x * x);
+                for (j, y) in row[p + 1..].iter() {
+                    system.add(*i, *j, x * y);
+                }
+            }
+        }
+    }
+
+    let mut pins = (disk.boundary[0], disk.boundary[0]);
+    let mut max_distance = 0.0;
+    for (i, a) in disk.boundary.as_slice().iter().enumerate() {
+        for b in disk.boundary[i + 1..].iter() {
+            let d = dist_3d(&disk.positions[*a], &disk.positions[*b]);
+            if d > max_distance {
+                max_distance = d;
+                pins = (*a, *b);
+            }
+        }
+    }
+    if max_distance == 0.0 {
+        return Err(ErrorKind::MeshDegenerate);
+    }
+
+    let mut fixed = vec![None; 2 * n];
+    fixed[2 * pins.0] = Some(0.0);
+    fixed[2 * pins.0 + 1] = Some(0.0);
+    fixed[2 * pins.1] = Some(max_distance);
+    fixed[2 * pins.1 + 1] = Some(0.0);
+
+    let uvs = system.solve_fixed(&vec![0.0; 2 * n], &fixed);
+    Ok(uvs.chunks(2).map(|uv| P2::new(uv[0], uv[1])).collect())
+}
+
+/// Calculates the distortion of each face of a mesh caused by the given per vertex UVs
+/// Faces without area have infinite distortion
+pub fn parameterization_distortion<P2, P, M>(mesh: &M, uvs: &[P2]) -> Result<Vec<FaceDistortion>>
+where
+    M: IsMesh<P, Face3>,
+    P: Is3D,
+    P2: Is2D,
+{
+    let n = mesh.num_vertices();
+    if uvs.len() != n {
+        return Err(ErrorKind::DimensionsDontMatch);
+    }
+
+    let mut positions = Vec::with_capacity(n);
+    for i in 0..n {
+        let v = mesh.vertex(VId(i)).ok_or(ErrorKind::IncorrectVertexID)?;
+        positions.push(Point3D::new_from(&v));
+    }
+
+    let mut result = Vec::with_capacity(mesh.num_faces());
+    for i in 0..mesh.num_faces() {
+        let f = mesh
+            .face_vertex_ids(FId(i))
+            .ok_or(ErrorKind::IncorrectFaceID)?;
+        let face = [f.a.0, f.b.0, f.c.0];
+        let local = match local_coordinates(&positions, &face) {
+            None => {
+                result.push(FaceDistortion {
+                    max_stretch: f64::INFINITY,
+                    min_stretch: f64::INFINITY,
+                    conformal: f64::INFINITY,
+                    area: f64::INFINITY,
+                });
+                continue;
+            }
+            Some(x) => x,
+        };
+
+        // jacobian of the mapping from the local coordinates of the face to the UVs
+        let (a, c, d) = (local[1][0], local[2][0], local[2][1]);
+        let (uv0, uv1, uv2) = (&uvs[face[0]], &uvs[face[1]], &uvs[face[2]]);
+        let (du1, dv1) = (uv1.x() - uv0.x(), uv1.y() - uv0.y());
+        let (du2, dv2) = (uv2.x() - uv0.x(), uv2.y() - uv0.y());
+        let j00 = du1 / a;
+        let j01 = (du2 - c * j00) / d;
+        let j10 = dv1 / a;
+        let j11 = (dv2 - c * j10) / d;
+
+        // singular values of the 2x2 jacobian
+        let q = 0.5 * ((j00 + j11).powi(2) + (j10 - j01).powi(2)).sqrt();
+        let r = 0.5 * ((j00 - j11).powi(2) + (j10 + j01).powi(2)).sqrt();
+        let (max_stretch, min_stretch) = (q + r, (q - r).abs());
+
+        result.push(FaceDistortion {
+            max_stretch,
+            min_stretch,
+            conformal: if min_stretch > 0.0 {
+                max_stretch / min_stretch
+            } else {
+                f64::INFINITY
+            },
+            area: j00 * j11 - j01 * j10,
+        });
+    }
+    Ok(result)
+}
+
+//------------------------------------------------------------------------------
+
+/// Mesh which was verified to have the topology of a disk
+struct Disk {
+    positions: Vec<Point3D>,
+    topology: MeshTopology,
+    boundary: Vec<usize>,
+}
+
+impl Disk {
+    fn new<P, M>(mesh: &M) -> Result<Self>
+    where
+        M: IsSearchableMesh<P, Face3>,
+        P: Is3D,
+    {
+        let diagnostics = diagnose_mesh(mesh)?;
+        if !diagnostics.is_valid_for_half_edge() {
+            return Err(ErrorKind::MeshNotManifold);
+        }
+
+        let mut loops = Vec::new();
+        mesh.boundary_loops(&mut loops)?;
+        let topology = MeshTopology::new(mesh)?;
+        let n = mesh.num_vertices();
+
+        // a single component with a single boundary and the euler characteristic 1
+        let euler = n as i64 - topology.edge_faces.len() as i64 + topology.faces.len() as i64;
+        if diagnostics.n_components != 1 || loops.len() != 1 || euler != 1 {
+            return Err(ErrorKind::MeshNotDisk);
+        }
+
+        let mut positions = Vec::with_capacity(n);
+        for i in 0..n {
+            let v = mesh.vertex(VId(i)).ok_or(ErrorKind::IncorrectVertexID)?;
+            positions.push(Point3D::new_from(&v));
+        }
+
+        Ok(Self {
+            positions,
+            topology,
+            boundary: loops[0].iter().map(|v| v.0).collect(),
+        })
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Coordinates of the corners of the face within its plane, with the first at the origin and the second on the positive x-axis
+/// None if the face has no area
+fn local_coordinates(positions: &[Point3D], face: &[usize; 3]) -> Option<[[f64; 2]; 3]> {
+    let (p0, p1, p2) = (
+        &positions[face[0]],
+        &positions[face[1]],
+        &positions[face[2]],
+    );
+    let (e1, e2) = (conn(p0, p1), conn(p0, p2));
+    let a = *e1.abs();
+    let double_area = *cross::<_, Point3D>(&e1, &e2).abs();
+    if a == 0.0 || double_area == 0.0 {
+        return None;
+    }
+    Some([[0.0, 0.0], [a, 0.0], [e1.dot(&e2) / a, double_area / a]])
+}
@@ -0,0 +1,258 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! PolyMesh3D, a mesh with faces of arbitrary size (triangles, quads and n-gons) within 3D space
+
+use crate::*;
+
+use std::marker::PhantomData;
+
+//------------------------------------------------------------------------------
+
+#[derive(Default, Debug, PartialEq, PartialOrd, Ord, Eq, Clone, Hash)]
+/// PolyMesh3D, a mesh with faces of arbitrary size (triangles, quads and n-gons) within 3D space
+/// Since the algorithms of rust-3d require tri-faces, use triangulate to convert it to a tri mesh
+pub struct PolyMesh3D<P, ID, IC>
+where
+    P: Is3D,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+{
+    pc: ID,
+    topology: IC,
+    face_starts: Vec<usize>,
+    _phantom: PhantomData<P>,
+}
+
+impl<P, ID, IC> PolyMesh3D<P, ID, IC>
+where
+    P: Is3D + Clone,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+{
+    /// Returns the number of faces
+    pub fn num_faces(&self) -> usize {
+        self.face_starts.len()
+    }
+    /// Returns the number of vertices
+    pub fn num_vertices(&self) -> usize {
+        self.pc.len_d()
+    }
+    /// Returns the number of vertices of the given face. None if id invalid
+    pub fn face_size(&self, faceid: FId) -> Option<usize> {
+        let (start, end) = self.face_range(faceid)?;
+        Some(end - start)
+    }
+    /// Returns the ids of the vertices of the given face. None if id invalid
+    pub fn face_vertex_ids(&self, faceid: FId) -> Option<FaceN> {
+        let (start, end) = self.face_range(faceid)?;
+        Some(FaceN::new(
+            (start..end).map(|i| VId(self.topology.get(i))).collect(),
+        ))
+    }
+    /// Returns the ids of the vertices of the given face if it is a quad. None if id invalid or not a quad
+    pub fn face_quad(&self, faceid: FId) -> Option<Face4> {
+        let (start, end) = self.face_range(faceid)?;
+        if end - start != 4 {
+            return None;
+        }
+        let vid = |i: usize| VId(self.topology.get(start + i));
+        Some(Face4::new(vid(0), vid(1), vid(2), vid(3)))
+    }
+    /// Returns the vertices of the given face. None if id invalid
+    pub fn face_vertices(&self, faceid: FId) -> Option<Vec<P>> {
+        let (start, end) = self.face_range(faceid)?;
+        (start..end)
+            .map(|i| self.vertex(VId(self.topology.get(i))))
+            .collect()
+    }
+    /// Returns the vertex with the given id. None if id invalid
+    pub fn vertex(&self, vertexid: VId) -> Option<P> {
+        self.pc.get_d(vertexid.0)
+    }
+
+    fn face_range(&self, faceid: FId) -> Option<(usize, usize)> {
+        let start = *self.face_starts.as_slice().get(faceid.0)?;
+        let end = self
+            .face_starts
+            .as_slice()
+            .get(faceid.0 + 1)
+            .cloned()
+            .unwrap_or_else(|| self.topology.len());
+        Some((start, end))
+    }
+}
+
+impl<P, ID, IC> PolyMesh3D<P, ID, IC>
+where
+    P: IsEditable3D + IsBuildable3D + Clone,
+    ID: IsDataContainer<P>,
+    IC: IsIndexContainer,
+{
+    /// Adds a vertex to the end and returns its id
+    pub fn add_vertex(&mut self, vertex: P) -> VId {
+        self.pc.push_d(vertex);
+        VId(self.pc.len_d() - 1)
+    }
+    /// Changes the vertex at vid. Error if id invalid
+    pub fn change_vertex(&mut self, vid: VId, vertex: P) -> Result<()> {
+        if vid.0 < self.pc.len_d() {
+            self.pc.set_d(vid.0, vertex);
+            Ok(())
+        } else {
+            Err(ErrorKind::IncorrectVertexID)
+        }
+    }
+    /// Reserves space for n additional vertices
+    pub fn reserve_vertices(&mut self, n: usize) {
+        self.pc.reserve_d(n)
+    }
+    /// Reserves space for n additional faces, assuming they are quads
+    pub fn reserve_faces(&mut self, n: usize) {
+        self.face_starts.reserve(n);
+        self.topology.reserve(4 * n)
+    }
+    /// Adds a face connecting the vertices in order and returns its id
+    /// Error if there are fewer than 3 vertices, an id is invalid or used more than once
+    pub fn try_add_face(&mut self, vids: &[VId]) -> Result<FId> {
+        if vids.len() < 3 {
+            return Err(ErrorKind::TooFewPoints);
+        }
+        for (i, vid) in vids.iter().enumerate() {
+            if vid.0 >= self.pc.len_d() {
+                return Err(ErrorKind::IncorrectVertexID);
+            }
+            if vids[i + 1..].contains(vid) {
+                return Err(ErrorKind::FaceIDsNotUnique);
+            }
+        }
+
+        self.face_starts.push(self.topology.len());
+        for vid in vids.iter() {
+            self.topology.push(vid.0);
+        }
+        Ok(FId(self.face_starts.len() - 1))
+    }
+    /// Adds a quad and returns its id
+    /// Error if an id is invalid or used more than once
+    pub fn try_add_quad(&mut self, face: Face4) -> Result<FId> {
+        self.try_add_face(&[face.a, face.b, face.c, face.d])
+    }
+    /// Creates a new PolyMesh3D from the vertices and faces of a tri mesh
+    pub fn from_mesh<M>(mesh: &M) -> Result<Self>
+    where
+        M: IsMesh<P, Face3>,
+        P: Default,
+        ID: Default,
+        IC: Default,
+    {
+        let mut result = Self::default();
+        result.reserve_vertices(mesh.num_vertices());
+        result.face_starts.reserve(mesh.num_faces());
+        result.topology.reserve(3 * mesh.num_faces());
+
+        for i in 0..mesh.num_vertices() {
+            result.add_vertex(mesh.vertex(VId(i)).ok_or(ErrorKind::IncorrectVertexID)?);
+        }
+        for i in 0..mesh.num_faces() {
+            let f = mesh
+                .face_vertex_ids(FId(i))
+                .ok_or(ErrorKind::IncorrectFaceID)?;
+            result.try_add_face(&[f.a, f.b, f.c])?;
+        }
+        Ok(result)
+    }
+    /// Creates a tri mesh with the same vertices, splitting each face via triangulate_polygon_3d
+    pub fn triangulate<MO>(&self) -> Result<MO>
+    where
+        MO: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+    {
+        let mut result = MO::default();
+        result.reserve_vertices(self.num_vertices());
+        result.reserve_faces(self.topology.len().saturating_sub(2 * self.num_faces()));
+
+        for i in 0..self.num_vertices() {
+            // safe since iterating num_vertices
+            result.add_vertex(self.vertex(VId(i)).unwrap());
+        }
+
+        for i in 0..self.num_faces() {
+            // safe since iterating num_faces
+            let face = self.face_vertex_ids(FId(i)).unwrap();
+            let vertices = self.face_vertices(FId(i)).unwrap();
+            for [a, b, c] in triangulate_polygon_3d(&vertices).iter() {
+                result.try_add_connection(face.vids[*a], face.vids[*b], face.vids[*c])?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl<P, ID, IC> HasBoundingBox3DMaybe for PolyMesh3D<P, ID, IC>
+where
+    P: Is3D,
+    ID: IsDataContainer<P> + HasBoundingBox3DMaybe,
+    IC: IsIndexContainer,
+{
+    fn bounding_box_maybe(&self) -> Option<BoundingBox3D> {
+        self.pc.bounding_box_maybe()
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Triangulates a planar polygon in 3D space via ear clipping, returning the triangles as indices into polygon
+/// The triangles keep the orientation of the polygon. Non-planar polygons are triangulated within their best fitting plane
+pub fn triangulate_polygon_3d<P>(polygon: &[P]) -> Vec<[usize; 3]>
+where
+    P: Is3D,
+{
+    let n = polygon.len();
+    if n < 3 {
+        return Vec::new();
+    }
+    if n == 3 {
+        return vec![[0, 1, 2]];
+    }
+
+    // Newell's method, the normal is oriented according to the order of the polygon
+    let mut normal = Point3D::default();
+    for i in 0..n {
+        let (p, q) = (&polygon[i], &polygon[(i + 1) % n]);
+        normal.x += (p.y() - q.y()) * (p.z() + q.z());
+        normal.y += (p.z() - q.z()) * (p.x() + q.x());
+        normal.z += (p.x() - q.x()) * (p.y() + q.y());
+    }
+    let length = *normal.abs();
+    if length == 0.0 {
+        return (1..n - 1).map(|i| [0, i, i + 1]).collect();
+    }
+    let normal = normal / length;
+
+    let u = any_orthogonal(&normal);
+    let v: Point3D = cross(&normal, &u);
+    let positions: Vec<[f64; 2]> = polygon.iter().map(|p| [u.dot(p), v.dot(p)]).collect();
+
+    let mut result = Vec::with_capacity(n - 2);
+    ear_clip(&positions, (0..n).collect(), &mut result);
+    result
+}
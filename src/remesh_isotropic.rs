@@ -0,0 +1,455 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Algorithm to remesh a mesh with triangles of uniform size
+
+use crate::*;
+
+use std::cmp::Ordering;
+
+//------------------------------------------------------------------------------
+
+/// Algorithm to remesh a mesh to triangles with edges of about target_edge_length (Botsch and Kobbelt)
+/// Each iteration splits long edges, collapses short edges, flips edges to equalize the valences and relaxes the vertices tangentially
+/// Edges whose face normals differ by more than feature_angle are feature edges, these are never flipped and their vertices never moved
+/// If preserve_boundary is set, the boundary isn't changed at all, otherwise it is resampled but keeps its shape
+/// The mesh must be manifold and consistently oriented, otherwise MeshNotManifold is returned
+/// All vertices and faces are resampled, so attributes of the mesh are not carried over
+pub fn remesh_isotropic<P, MI, MO>(
+    mesh: &MI,
+    target_edge_length: Positive,
+    feature_angle: Rad,
+    preserve_boundary: bool,
+    iterations: usize,
+) -> Result<MO>
+where
+    MI: IsMesh<P, Face3>,
+    MO: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+    P: IsBuildable3D + Clone,
+{
+    let mut remesher = Remesher {
+        mesh: HalfEdgeMesh3D::new(mesh)?,
+        sqr_max_length: (4.0 / 3.0 * *target_edge_length).powi(2),
+        sqr_min_length: (4.0 / 5.0 * *target_edge_length).powi(2),
+        cos_feature: feature_angle.0.cos(),
+        preserve_boundary,
+    };
+
+    for _ in 0..iterations {
+        remesher.split_long_edges()?;
+        remesher.collapse_short_edges()?;
+        remesher.equalize_valences()?;
+        remesher.relax_tangentially()?;
+    }
+
+    let (vertices, faces) = remesher.mesh.into_parts();
+    let mut result = MO::default();
+    result.reserve_vertices(vertices.len());
+    result.reserve_faces(faces.len());
+    for v in vertices {
+        result.add_vertex(v);
+    }
+    for [a, b, c] in faces {
+        result.try_add_connection(VId(a), VId(b), VId(c))?;
+    }
+    Ok(result)
+}
+
+//------------------------------------------------------------------------------
+
+/// Faces of a collapse or relaxation may only change their normals up to this (cosine of the angle)
+const MIN_NORMAL_COS: f64 = 0.5;
+
+struct Remesher<P> {
+    mesh: HalfEdgeMesh3D<P>,
+    sqr_max_length: f64,
+    sqr_min_length: f64,
+    cos_feature: f64,
+    preserve_boundary: bool,
+}
+
+impl<P> Remesher<P>
+where
+    P: IsBuildable3D + Clone,
+{
+    fn split_long_edges(&mut self) -> Result<()> {
+        // longest edges first, splitting in arbitrary order can refine around a vertex endlessly
+        loop {
+            let mut long_edges = Vec::new();
+            for e in 0..self.mesh.num_edges() {
+                let eid = EId(e);
+                let (a, b) = self.ends(eid)?;
+                let boundary = self.mesh.is_edge_on_boundary(eid);
+                // each edge only once
+                if (!boundary && a.0 > b.0) || (boundary && self.preserve_boundary) {
+                    continue;
+                }
+                let sqr_length = self.sqr_length(a, b);
+                if sqr_length > self.sqr_max_length {
+                    long_edges.push((sqr_length, a, b));
+                }
+            }
+            if long_edges.is_empty() {
+                return Ok(());
+            }
+            long_edges.sort_by(|x, y| y.0.partial_cmp(&x.0).unwrap_or(Ordering::Equal));
+
+            for (_, a, b) in long_edges {
+                // splits reorder the edges of the split faces, so search the edge again
+                let eid = match self.mesh.edge_between(a, b) {
+                    Some(x) => x,
+                    None => continue,
+                };
+                let mid = center_3d(&self.position(a)?, &self.position(b)?);
+                self.mesh.split_edge(eid, mid)?;
+            }
+        }
+    }
+
+    fn collapse_short_edges(&mut self) -> Result<()> {
+        // collapses move faces and vertices to other ids, so repeat until nothing changes
+        loop {
+            let mut changed = false;
+            let mut e = 0;
+            while e < self.mesh.num_edges() {
+                if self.try_collapse(EId(e))? {
+                    changed = true;
+                } else {
+                    e += 1;
+                }
+            }
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Collapses the edge, removing its head, if that is allowed
+    fn try_collapse(&mut self, eid: EId) -> Result<bool> {
+        let (a, b) = self.ends(eid)?;
+        if self.sqr_length(a, b) >= self.sqr_min_length {
+            return Ok(false);
+        }
+        if self.is_feature_vertex(b)? {
+            return Ok(false);
+        }
+        if self.is_boundary(b)?
+            && (self.preserve_boundary
+                || !self.mesh.is_edge_on_boundary(eid)
+                || !self.is_straight_boundary_vertex(b)?)
+        {
+            return Ok(false);
+        }
+        if !self.mesh.is_collapse_ok(eid) {
+            return Ok(false);
+        }
+
+        let target = if self.is_fixed(a)? {
+            P::new_from(&self.position(a)?)
+        } else {
+            center_3d(&self.position(a)?, &self.position(b)?)
+        };
+
+        let mut neighbours = Vec::new();
+        self.mesh.neighbours(a, &mut neighbours)?;
+        self.mesh.neighbours(b, &mut neighbours)?;
+        for n in neighbours {
+            if n != a && n != b && sqr_dist_3d(&target, &self.position(n)?) > self.sqr_max_length {
+                return Ok(false);
+            }
+        }
+
+        if !self.keeps_normals(&[a, b], &target)? {
+            return Ok(false);
+        }
+
+        self.mesh.collapse_edge(eid, target)?;
+        Ok(true)
+    }
+
+    fn equalize_valences(&mut self) -> Result<()> {
+        for e in 0..self.mesh.num_edges() {
+            let eid = EId(e);
+            let twin = match self.mesh.edge_twin(eid) {
+                None => continue,
+                Some(x) => x,
+            };
+            let (a, b) = self.ends(eid)?;
+            if a.0 > b.0 || self.is_feature_edge(eid)? {
+                continue;
+            }
+            let c = self.opposite(eid)?;
+            let d = self.opposite(twin)?;
+
+            let (va, vb, vc, vd) = (
+                self.valence(a)?,
+                self.valence(b)?,
+                self.valence(c)?,
+                self.valence(d)?,
+            );
+            if va <= 3 || vb <= 3 {
+                continue;
+            }
+
+            // optimal valences are 6 in the interior and 4 on the boundary
+            let (ta, tb, tc, td) = (
+                self.target_valence(a)?,
+                self.target_valence(b)?,
+                self.target_valence(c)?,
+                self.target_valence(d)?,
+            );
+            let deviation = |valence: usize, target: i64| (valence as i64 - target).abs();
+            let before =
+                deviation(va, ta) + deviation(vb, tb) + deviation(vc, tc) + deviation(vd, td);
+            let after = deviation(va - 1, ta)
+                + deviation(vb - 1, tb)
+                + deviation(vc + 1, tc)
+                + deviation(vd + 1, td);
+            if after >= before {
+                continue;
+            }
+
+            // the flipped faces must neither fold over nor introduce a new feature
+            let n0 = self.face_normal(&[a, b, c])?;
+            let n1 = self.face_normal(&[b, a, d])?;
+            let m0 = self.face_normal(&[a, d, c])?;
+            let m1 = self.face_normal(&[d, b, c])?;
+            if m0.dot(&m1) < self.cos_feature
+                || [&n0, &n1]
+                    .iter()
+                    .any(|n| n.dot(&m0) <= 0.0 || n.dot(&m1) <= 0.0)
+            {
+                continue;
+            }
+
+            // might fail if c and d are already connected
+            let _ = self.mesh.flip_edge(eid);
+        }
+        Ok(())
+    }
+
+    fn relax_tangentially(&mut self) -> Result<()> {
+        let mut neighbours = Vec::new();
+        let mut faces = Vec::new();
+        for i in 0..self.mesh.num_vertices() {
+            let vid = VId(i);
+            if self.is_fixed(vid)? {
+                continue;
+            }
+
+            neighbours.clear();
+            self.mesh.neighbours(vid, &mut neighbours)?;
+            if neighbours.is_empty() {
+                continue;
+            }
+            let mut center = [0.0; 3];
+            for n in neighbours.iter() {
+                let p = &self.position(*n)?;
+                center[0] += p.x();
+                center[1] += p.y();
+                center[2] += p.z();
+            }
+            let k = neighbours.len() as f64;
+            let center = Point3D::new(center[0] / k, center[1] / k, center[2] / k);
+
+            // area weighted normal
+            faces.clear();
+            self.mesh.faces_of_vertex(vid, &mut faces)?;
+            let mut normal = Point3D::default();
+            for fid in faces.iter() {
+                let [fa, fb, fc] = self.face(*fid)?;
+                let (pa, pb, pc) = (
+                    &self.position(fa)?,
+                    &self.position(fb)?,
+                    &self.position(fc)?,
+                );
+                let n = cross(&conn(pa, pb), &Point3D::new_from(&conn(pa, pc)));
+                normal.x += n.x();
+                normal.y += n.y();
+                normal.z += n.z();
+            }
+            let normal = match Norm3D::new(normal) {
+                Ok(x) => x,
+                Err(_) => continue,
+            };
+
+            // move to the center, but only within the tangent plane
+            let p = Point3D::new_from(&self.position(vid)?);
+            let offset = normal.dot(&conn(&center, &p));
+            let target = P::new(
+                center.x + offset * normal.x(),
+                center.y + offset * normal.y(),
+                center.z + offset * normal.z(),
+            );
+            if self.keeps_normals(&[vid], &target)? {
+                self.mesh.change_vertex(vid, target)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether moving the vertices to the target keeps the normals of their faces (ignoring faces which would collapse)
+    fn keeps_normals(&self, moved: &[VId], target: &P) -> Result<bool> {
+        let mut faces = Vec::new();
+        for vid in moved {
+            self.mesh.faces_of_vertex(*vid, &mut faces)?;
+        }
+        for fid in faces {
+            let face = self.face(fid)?;
+            if face.iter().filter(|v| moved.contains(v)).count() > 1 {
+                continue;
+            }
+            let mut after = [
+                Point3D::new_from(&self.position(face[0])?),
+                Point3D::new_from(&self.position(face[1])?),
+                Point3D::new_from(&self.position(face[2])?),
+            ];
+            for (i, v) in face.iter().enumerate() {
+                if moved.contains(v) {
+                    after[i] = Point3D::new_from(target);
+                }
+            }
+            let before = self.face_normal(&face)?;
+            match Norm3D::new(cross(
+                &conn(&after[0], &after[1]),
+                &conn(&after[0], &after[2]),
+            )) {
+                Ok(n) if before.dot(&n) >= MIN_NORMAL_COS => (),
+                _ => return Ok(false),
+            }
+        }
+        Ok(true)
+    }
+
+    fn ends(&self, eid: EId) -> Result<(VId, VId)> {
+        match (self.mesh.edge_tail(eid), self.mesh.edge_head(eid)) {
+            (Some(a), Some(b)) => Ok((a, b)),
+            _ => Err(ErrorKind::IncorrectEdgeID),
+        }
+    }
+
+    fn opposite(&self, eid: EId) -> Result<VId> {
+        let prev = self.mesh.edge_prev(eid).ok_or(ErrorKind::IncorrectEdgeID)?;
+        self.mesh.edge_tail(prev).ok_or(ErrorKind::IncorrectEdgeID)
+    }
+
+    fn position(&self, vid: VId) -> Result<P> {
+        self.mesh.vertex(vid).ok_or(ErrorKind::IncorrectVertexID)
+    }
+
+    fn face(&self, fid: FId) -> Result<[VId; 3]> {
+        let f = self
+            .mesh
+            .face_vertex_ids(fid)
+            .ok_or(ErrorKind::IncorrectFaceID)?;
+        Ok([f.a, f.b, f.c])
+    }
+
+    fn is_boundary(&self, vid: VId) -> Result<bool> {
+        self.mesh.is_vertex_on_boundary(vid, &mut Vec::new())
+    }
+
+    fn sqr_length(&self, a: VId, b: VId) -> f64 {
+        match (self.mesh.vertex(a), self.mesh.vertex(b)) {
+            (Some(pa), Some(pb)) => sqr_dist_3d(&pa, &pb),
+            _ => 0.0,
+        }
+    }
+
+    fn valence(&self, vid: VId) -> Result<usize> {
+        let mut neighbours = Vec::new();
+        self.mesh.neighbours(vid, &mut neighbours)?;
+        Ok(neighbours.len())
+    }
+
+    fn target_valence(&self, vid: VId) -> Result<i64> {
+        Ok(if self.is_boundary(vid)? { 4 } else { 6 })
+    }
+
+    fn face_normal(&self, face: &[VId; 3]) -> Result<Norm3D> {
+        Ok(normal_of_face(
+            &self.position(face[0])?,
+            &self.position(face[1])?,
+            &self.position(face[2])?,
+        ))
+    }
+
+    fn is_feature_edge(&self, eid: EId) -> Result<bool> {
+        let twin = match self.mesh.edge_twin(eid) {
+            None => return Ok(false),
+            Some(x) => x,
+        };
+        let mut normals = Vec::with_capacity(2);
+        for e in [eid, twin].iter() {
+            let fid = self.mesh.edge_face(*e).ok_or(ErrorKind::IncorrectEdgeID)?;
+            let face = self.face(fid)?;
+            normals.push(self.face_normal(&face)?);
+        }
+        Ok(normals[0].dot(&normals[1]) < self.cos_feature)
+    }
+
+    fn is_feature_vertex(&self, vid: VId) -> Result<bool> {
+        let mut edges = Vec::new();
+        self.mesh.edges_originating_from_vertex(vid, &mut edges)?;
+        for eid in edges {
+            if self.is_feature_edge(eid)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Vertices which must not be moved
+    fn is_fixed(&self, vid: VId) -> Result<bool> {
+        Ok(self.is_boundary(vid)? || self.is_feature_vertex(vid)?)
+    }
+
+    /// Whether the vertex is on a single boundary which doesn't bend at it by more than the feature angle
+    fn is_straight_boundary_vertex(&self, vid: VId) -> Result<bool> {
+        let mut edges = Vec::new();
+        self.mesh.edges_originating_from_vertex(vid, &mut edges)?;
+        let mut outgoing = Vec::new();
+        let mut incoming = Vec::new();
+        for eid in edges {
+            if self.mesh.is_edge_on_boundary(eid) {
+                outgoing.push(eid);
+            }
+            let prev = self.mesh.edge_prev(eid).ok_or(ErrorKind::IncorrectEdgeID)?;
+            if self.mesh.is_edge_on_boundary(prev) {
+                incoming.push(prev);
+            }
+        }
+        if outgoing.len() != 1 || incoming.len() != 1 {
+            return Ok(false);
+        }
+        let (prev, _) = self.ends(incoming[0])?;
+        let (_, next) = self.ends(outgoing[0])?;
+        let p = &self.position(vid)?;
+        let dir_in = conn(&self.position(prev)?, p);
+        let dir_out = conn(p, &self.position(next)?);
+        match (Norm3D::new(dir_in), Norm3D::new(dir_out)) {
+            (Ok(i), Ok(o)) => Ok(i.dot(&o) >= self.cos_feature),
+            _ => Ok(false),
+        }
+    }
+}
@@ -0,0 +1,186 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Algorithms to repair the topology of meshes
+
+use crate::*;
+
+//------------------------------------------------------------------------------
+
+/// Algorithm to split non-manifold vertices
+/// The faces of a vertex are grouped into fans connected via manifold edges, each additional fan gets its own copy of the vertex
+/// This also cuts the mesh at edges shared by more than two faces
+pub fn split_non_manifold_vertices<P, M>(mesh: &M) -> Result<M>
+where
+    M: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+{
+    let topology = MeshTopology::new(mesh)?;
+    split_vertex_fans(mesh, &topology, |v| topology.vertex_fans(v))
+}
+
+/// Creates a copy of the mesh where each additional fan of a vertex gets its own copy of the vertex
+pub(crate) fn split_vertex_fans<P, M, F>(mesh: &M, topology: &MeshTopology, fans_of: F) -> Result<M>
+where
+    M: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+    F: Fn(usize) -> Vec<Vec<usize>>,
+{
+    let nv = mesh.num_vertices();
+    let mut faces = topology.faces.clone();
+
+    let mut result = M::default();
+    result.reserve_vertices(nv);
+    result.reserve_faces(faces.len());
+
+    for i in 0..nv {
+        result.add_vertex_derived(
+            mesh.vertex(VId(i)).ok_or(ErrorKind::IncorrectVertexID)?,
+            mesh.vertex_attributes(),
+            &[(VId(i), 1.0)],
+        )?;
+    }
+
+    for v in 0..nv {
+        for fan in fans_of(v).into_iter().skip(1) {
+            let copy = result.add_vertex_derived(
+                mesh.vertex(VId(v)).ok_or(ErrorKind::IncorrectVertexID)?,
+                mesh.vertex_attributes(),
+                &[(VId(v), 1.0)],
+            )?;
+            for f in fan {
+                for x in faces[f].iter_mut() {
+                    if *x == v {
+                        *x = copy.0;
+                    }
+                }
+            }
+        }
+    }
+
+    for (f, [a, b, c]) in faces.into_iter().enumerate() {
+        result.try_add_connection_derived(
+            VId(a),
+            VId(b),
+            VId(c),
+            mesh.face_attributes(),
+            FId(f),
+        )?;
+    }
+
+    Ok(result)
+}
+
+//------------------------------------------------------------------------------
+
+/// Algorithm to orient the faces of a mesh consistently
+/// Faces are flipped to match their neighbours across manifold edges, purely based on the topology
+/// Closed components are additionally oriented to enclose a positive volume, so their normals point outwards
+/// Non-orientable components (such as a Möbius strip) keep the orientation found first for each face
+pub fn orient_faces_consistently<P, M>(mesh: &M) -> Result<M>
+where
+    M: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+    P: Is3D,
+{
+    let topology = MeshTopology::new(mesh)?;
+    let nv = mesh.num_vertices();
+    let nf = topology.faces.len();
+
+    let mut flip = vec![false; nf];
+    let mut visited = vec![false; nf];
+    let mut frontier = Vec::new();
+
+    for start in 0..nf {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        frontier.push(start);
+        let mut component = vec![start];
+        let mut closed = true;
+
+        while let Some(f) = frontier.pop() {
+            for k in 0..3 {
+                let (a, b) = (topology.faces[f][k], topology.faces[f][(k + 1) % 3]);
+                let faces = topology.faces_of_edge(a, b);
+                if faces.len() != 2 {
+                    closed = false;
+                    continue;
+                }
+                let (this, other) = if faces[0].0 == f {
+                    (faces[0], faces[1])
+                } else {
+                    (faces[1], faces[0])
+                };
+                if visited[other.0] {
+                    continue;
+                }
+                // both using the edge in the same direction requires one of them to be flipped
+                flip[other.0] = flip[f] ^ (this.1 == other.1);
+                visited[other.0] = true;
+                frontier.push(other.0);
+                component.push(other.0);
+            }
+        }
+
+        if closed {
+            let mut volume = 0.0;
+            for f in &component {
+                let [a, b, c] = mesh
+                    .face_vertices(FId(*f))
+                    .ok_or(ErrorKind::IncorrectFaceID)?;
+                let signed = a.x() * (b.y() * c.z() - b.z() * c.y())
+                    - a.y() * (b.x() * c.z() - b.z() * c.x())
+                    + a.z() * (b.x() * c.y() - b.y() * c.x());
+                volume += if flip[*f] { -signed } else { signed };
+            }
+            if volume < 0.0 {
+                for f in component {
+                    flip[f] = !flip[f];
+                }
+            }
+        }
+    }
+
+    let mut result = M::default();
+    result.reserve_vertices(nv);
+    result.reserve_faces(nf);
+
+    for i in 0..nv {
+        result.add_vertex_derived(
+            mesh.vertex(VId(i)).ok_or(ErrorKind::IncorrectVertexID)?,
+            mesh.vertex_attributes(),
+            &[(VId(i), 1.0)],
+        )?;
+    }
+
+    for (f, [a, b, c]) in topology.faces.into_iter().enumerate() {
+        let (b, c) = if flip[f] { (c, b) } else { (b, c) };
+        result.try_add_connection_derived(
+            VId(a),
+            VId(b),
+            VId(c),
+            mesh.face_attributes(),
+            FId(f),
+        )?;
+    }
+
+    Ok(result)
+}
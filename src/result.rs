@@ -50,6 +50,7 @@ pub enum ErrorKind {
     IncorrectVoxelID,
     IncorrectUnitID,
     IncorrectSegmentID,
+    IncorrectObjectID,
     IncorrectDimension,
     DimensionsDontMatch,
     NumberConversionError,
@@ -58,6 +59,12 @@ pub enum ErrorKind {
     ClusterTooBig,
     CantCalculateAngleIfZeroLength,
     TriFace3DNotSpanningVolume,
+    MeshNotManifold,
+    InvalidMeshOperation,
+    MeshNotClosed,
+    MeshDegenerate,
+    NoPath,
+    MeshNotDisk,
     PlyError(PlyError),
     StlError(StlError),
     PtxError(PtxError),
@@ -89,6 +96,7 @@ impl fmt::Debug for ErrorKind {
             Self::IncorrectVoxelID => write!(f, "Used an incorrect voxel id"),
             Self::IncorrectUnitID => write!(f, "Used an incorrect unit id"),
             Self::IncorrectSegmentID => write!(f, "Used an incorrect segment id"),
+            Self::IncorrectObjectID => write!(f, "Used an incorrect object id"),
             Self::IncorrectDimension => write!(f, "Trying to access an incorrect dimension"),
             Self::DimensionsDontMatch => write!(f, "Trying to mix types with different dimensions"),
             Self::NumberConversionError => {
@@ -104,6 +112,17 @@ impl fmt::Debug for ErrorKind {
                 f,
                 "TriFace3D must be constructed from points spanning a volume"
             ),
+            Self::MeshNotManifold => {
+                write!(f, "Mesh must be manifold and consistently oriented")
+            }
+            Self::InvalidMeshOperation => write!(
+                f,
+                "Operation would result in a non-manifold or degenerate mesh"
+            ),
+            Self::MeshNotClosed => write!(f, "Mesh must be closed"),
+            Self::MeshDegenerate => write!(f, "Mesh has no area or volume"),
+            Self::NoPath => write!(f, "There is no path between the vertices"),
+            Self::MeshNotDisk => write!(f, "Mesh does not have the topology of a disk"),
             Self::PlyError(x) => x.fmt(f),
             Self::StlError(x) => x.fmt(f),
             Self::PtxError(x) => x.fmt(f),
@@ -34,4 +34,21 @@ impl Rgb {
     pub fn new(r: u8, g: u8, b: u8) -> Self {
         Rgb { r, g, b }
     }
+    /// Maps a value within [min, max] to a color ranging from blue via green to red. Values outside of the range are clamped
+    pub fn heat_map(value: f64, min: f64, max: f64) -> Self {
+        let t = if max > min {
+            ((value - min) / (max - min)).clamp(0.0, 1.0)
+        } else {
+            0.5
+        };
+        // NaN values end up in the center of the range
+        let t = if t.is_nan() { 0.5 } else { t };
+
+        let channel = |x: f64| (255.0 * x).round() as u8;
+        if t < 0.5 {
+            Rgb::new(0, channel(2.0 * t), channel(1.0 - 2.0 * t))
+        } else {
+            Rgb::new(channel(2.0 * t - 1.0), channel(2.0 - 2.0 * t), 0)
+        }
+    }
 }
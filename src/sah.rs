@@ -0,0 +1,233 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Helpers to partition data using the surface area heuristic (SAH), as used by the AABB trees
+
+use crate::*;
+
+//------------------------------------------------------------------------------
+
+/// Number of bins used for the binned SAH evaluation
+const N_BINS: usize = 12;
+
+//------------------------------------------------------------------------------
+
+/// Element which should be partitioned via SAH in 3D
+pub(crate) struct SahItem3D {
+    pub index: usize,
+    pub bb: BoundingBox3D,
+    pub center: [f64; 3],
+}
+
+impl SahItem3D {
+    pub fn new(index: usize, bb: BoundingBox3D) -> Self {
+        let c = bb.center_bb();
+        Self {
+            index,
+            bb,
+            center: [c.x(), c.y(), c.z()],
+        }
+    }
+}
+
+/// Element which should be partitioned via SAH in 2D
+pub(crate) struct SahItem2D {
+    pub index: usize,
+    pub bb: BoundingBox2D,
+    pub center: [f64; 2],
+}
+
+impl SahItem2D {
+    pub fn new(index: usize, bb: BoundingBox2D) -> Self {
+        let c = bb.center_bb();
+        Self {
+            index,
+            bb,
+            center: [c.x(), c.y()],
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Surface area of a bounding box
+pub(crate) fn area_3d(bb: &BoundingBox3D) -> f64 {
+    let [x, y, z] = bb.sizes();
+    2.0 * (*x * *y + *x * *z + *y * *z)
+}
+
+/// The 2D equivalent of the surface area (the perimeter)
+pub(crate) fn area_2d(bb: &BoundingBox2D) -> f64 {
+    let [x, y] = bb.sizes();
+    2.0 * (*x + *y)
+}
+
+/// Combined bounding box of all items (None if empty)
+pub(crate) fn bb_of_items_3d(items: &[SahItem3D]) -> Option<BoundingBox3D> {
+    let mut result: Option<BoundingBox3D> = None;
+    for item in items {
+        match result {
+            None => result = Some(item.bb.clone()),
+            Some(ref mut bb) => bb.consume(item.bb.clone()),
+        }
+    }
+    result
+}
+
+/// Combined bounding box of all items (None if empty)
+pub(crate) fn bb_of_items_2d(items: &[SahItem2D]) -> Option<BoundingBox2D> {
+    let mut result: Option<BoundingBox2D> = None;
+    for item in items {
+        match result {
+            None => result = Some(item.bb.clone()),
+            Some(ref mut bb) => bb.consume(item.bb.clone()),
+        }
+    }
+    result
+}
+
+//------------------------------------------------------------------------------
+
+/// Partitions the items in place by the split with the lowest SAH cost
+/// Returns the number of items within the first partition, both partitions are never empty
+/// Returns None if no split is possible (e.g. all centers equal)
+pub(crate) fn sah_partition_3d(items: &mut [SahItem3D]) -> Option<usize> {
+    sah_partition(
+        items,
+        3,
+        &|x: &SahItem3D, dim| x.center[dim],
+        &|xs: &[&SahItem3D]| {
+            let mut bb = xs[0].bb.clone();
+            for x in xs.iter().skip(1) {
+                bb.consume(x.bb.clone());
+            }
+            area_3d(&bb)
+        },
+    )
+}
+
+/// Partitions the items in place by the split with the lowest SAH cost
+/// Returns the number of items within the first partition, both partitions are never empty
+/// Returns None if no split is possible (e.g. all centers equal)
+pub(crate) fn sah_partition_2d(items: &mut [SahItem2D]) -> Option<usize> {
+    sah_partition(
+        items,
+        2,
+        &|x: &SahItem2D, dim| x.center[dim],
+        &|xs: &[&SahItem2D]| {
+            let mut bb = xs[0].bb.clone();
+            for x in xs.iter().skip(1) {
+                bb.consume(x.bb.clone());
+            }
+            area_2d(&bb)
+        },
+    )
+}
+
+//------------------------------------------------------------------------------
+
+fn sah_partition<T>(
+    items: &mut [T],
+    n_dimensions: usize,
+    center: &dyn Fn(&T, usize) -> f64,
+    area: &dyn Fn(&[&T]) -> f64,
+) -> Option<usize> {
+    let n = items.len();
+    if n < 2 {
+        return None;
+    }
+
+    // (cost, dimension, min, extent, index of first bin of the second partition)
+    let mut best: Option<(f64, usize, f64, f64, usize)> = None;
+    let mut bins: Vec<Vec<&T>> = (0..N_BINS).map(|_| Vec::new()).collect();
+
+    for dim in 0..n_dimensions {
+        let (mut min, mut max) = (center(&items[0], dim), center(&items[0], dim));
+        for x in items.iter() {
+            let c = center(x, dim);
+            if c < min {
+                min = c;
+            }
+            if c > max {
+                max = c;
+            }
+        }
+        let extent = max - min;
+        if extent <= 0.0 {
+            continue;
+        }
+
+        for bin in bins.iter_mut() {
+            bin.clear();
+        }
+        for x in items.iter() {
+            bins[bin_of(center(x, dim), min, extent)].push(x);
+        }
+
+        // areas and counts of all bins left of (and including) / right of each split
+        let mut cost_left = [0.0; N_BINS];
+        let mut buffer = Vec::new();
+        for i in 0..N_BINS - 1 {
+            buffer.extend(bins[i].iter().cloned());
+            if !buffer.is_empty() {
+                cost_left[i] = area(&buffer) * buffer.len() as f64;
+            }
+        }
+        buffer.clear();
+        for i in (1..N_BINS).rev() {
+            buffer.extend(bins[i].iter().cloned());
+            let n_left = n - buffer.len();
+            if buffer.is_empty() || n_left == 0 {
+                continue;
+            }
+            let cost = cost_left[i - 1] + area(&buffer) * buffer.len() as f64;
+            let is_better = match best {
+                None => true,
+                Some(b) => cost < b.0,
+            };
+            if is_better {
+                best = Some((cost, dim, min, extent, i));
+            }
+        }
+    }
+
+    let (_, dim, min, extent, split) = best?;
+
+    let mut n_left = 0;
+    for i in 0..n {
+        if bin_of(center(&items[i], dim), min, extent) < split {
+            items.swap(i, n_left);
+            n_left += 1;
+        }
+    }
+
+    Some(n_left)
+}
+
+fn bin_of(c: f64, min: f64, extent: f64) -> usize {
+    let bin = (N_BINS as f64 * (c - min) / extent) as usize;
+    if bin >= N_BINS {
+        N_BINS - 1
+    } else {
+        bin
+    }
+}
@@ -47,7 +47,8 @@ where
 {
     /// Creates a new SearchableMesh3D from an IsMesh3D
     /// This only stays valid if IMesh3D is not changed after creation
-    /// The mesh must be manifold
+    /// The mesh must be manifold and consistently oriented
+    /// Use diagnose_mesh to check this and split_non_manifold_vertices / orient_faces_consistently to repair arbitrary meshes
     pub fn new(mesh: M) -> Self {
         let he = HalfEdge::new(&mesh);
 
@@ -96,6 +97,14 @@ where
     fn vertex(&self, vertexid: VId) -> Option<T> {
         self.mesh.vertex(vertexid)
     }
+
+    fn vertex_attributes(&self) -> Option<&MeshAttributes> {
+        self.mesh.vertex_attributes()
+    }
+
+    fn face_attributes(&self) -> Option<&MeshAttributes> {
+        self.mesh.face_attributes()
+    }
 }
 
 impl<M, T, IC> IsVertexEditableMesh<T, Face3> for SearchableMesh<M, T, IC>
@@ -115,6 +124,15 @@ where
     fn reserve_vertices(&mut self, n: usize) {
         self.mesh.reserve_vertices(n)
     }
+
+    fn add_vertex_derived(
+        &mut self,
+        vertex: T,
+        source: Option<&MeshAttributes>,
+        weights: &[(VId, f64)],
+    ) -> Result<VId> {
+        self.mesh.add_vertex_derived(vertex, source, weights)
+    }
 }
 
 impl<M, T, IC> IsSearchableMesh<T, Face3> for SearchableMesh<M, T, IC>
@@ -0,0 +1,197 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Slicing of meshes by planes into closed contours
+
+use crate::*;
+
+use fnv::FnvHashMap;
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+/// SliceContour, a closed contour where a mesh intersects a plane
+pub struct SliceContour<P2, P3>
+where
+    P2: IsBuildable2D,
+    P3: IsBuildable3D,
+{
+    /// The contour within the coordinates of the plane
+    pub polygon_2d: Polygon2D<P2>,
+    /// The contour within 3D space
+    pub polygon_3d: Polygon3D<P3>,
+    /// Whether the contour bounds a hole instead of the outside of the mesh
+    pub is_hole: bool,
+}
+
+//------------------------------------------------------------------------------
+
+/// Slices the mesh by the plane into closed contours
+/// The mesh must be closed and consistently oriented outwards, outer contours are then counter clockwise within the plane, holes clockwise
+/// Vertices on the plane are handled as if they were slightly above it (in direction of u x v)
+/// Error if a contour can't be closed
+pub fn slice_mesh<P2, P3, M, PL, N>(mesh: &M, plane: &PL) -> Result<Vec<SliceContour<P2, P3>>>
+where
+    M: IsMesh3D<P3>,
+    P2: IsBuildable2D,
+    P3: IsBuildable3D + IsTransFormableTo2D,
+    PL: IsPlane3D<P3, N>,
+    N: IsNormalized3D,
+{
+    let origin = plane.origin();
+    let normal: Point3D = cross(&plane.u(), &Point3D::new_from(&plane.v()));
+
+    let n_vertices = mesh.num_vertices();
+    let mut distances = Vec::with_capacity(n_vertices);
+    for i in 0..n_vertices {
+        let v = mesh.vertex(VId(i)).ok_or(ErrorKind::IncorrectVertexID)?;
+        distances.push(normal.dot(&conn(&origin, &v)));
+    }
+    let is_above = |v: usize| distances[v] >= 0.0;
+
+    // each crossed face yields a segment between two crossed edges, stored as start -> end
+    let mut next = FnvHashMap::<(usize, usize), (usize, usize)>::default();
+    for i in 0..mesh.num_faces() {
+        let face = mesh
+            .face_vertex_ids(FId(i))
+            .ok_or(ErrorKind::IncorrectFaceID)?;
+        let [a, b, c] = [face.a.0, face.b.0, face.c.0];
+
+        let mut entry = None;
+        let mut exit = None;
+        for (p, q) in [(a, b), (b, c), (c, a)].iter() {
+            match (is_above(*p), is_above(*q)) {
+                (false, true) => entry = Some(edge_key(*p, *q)),
+                (true, false) => exit = Some(edge_key(*p, *q)),
+                _ => (),
+            }
+        }
+
+        if let (Some(entry), Some(exit)) = (entry, exit) {
+            if next.insert(exit, entry).is_some() {
+                return Err(ErrorKind::MeshNotManifold);
+            }
+        }
+    }
+
+    let mut result = Vec::new();
+    while let Some(start) = next.keys().next().cloned() {
+        let mut points = Vec::new();
+        let mut edge = start;
+        loop {
+            let (p, q) = edge;
+            points.push(edge_point(mesh, &distances, p, q)?);
+
+            edge = next.remove(&edge).ok_or(ErrorKind::MeshNotClosed)?;
+            if edge == start {
+                break;
+            }
+        }
+        // vertices on the plane are reached via several edges
+        points.dedup_by(|x, y| sqr_dist_3d(x, y) == 0.0);
+        while points.len() > 1 && sqr_dist_3d(&points[0], &points[points.len() - 1]) == 0.0 {
+            points.pop();
+        }
+
+        // contours of touching vertices or edges don't enclose anything
+        if points.len() < 3 {
+            continue;
+        }
+
+        let mut pc_2d = PointCloud2D::<P2>::default();
+        let mut pc_3d = PointCloud3D::<P3>::default();
+        let mut area = 0.0;
+        for x in points.as_slice().iter() {
+            let projected: P2 = project_point_on_plane(plane, x);
+            if let Some(last) = pc_2d.data.last() {
+                area += last.x() * projected.y() - projected.x() * last.y();
+            }
+            pc_2d.push(projected);
+            pc_3d.push(P3::new_from(x));
+        }
+        if let (Some(first), Some(last)) = (pc_2d.data.first(), pc_2d.data.last()) {
+            area += last.x() * first.y() - first.x() * last.y();
+        }
+
+        result.push(SliceContour {
+            polygon_2d: pc_2d.into(),
+            polygon_3d: pc_3d.into(),
+            is_hole: area < 0.0,
+        });
+    }
+
+    Ok(result)
+}
+
+/// Slices the mesh by count parallel planes, the first one being the given plane, the others offset by spacing in direction of u x v
+/// Returns the contours of each plane, see slice_mesh
+pub fn slice_mesh_stack<P2, P3, M, PL, N>(
+    mesh: &M,
+    plane: &PL,
+    spacing: Positive,
+    count: usize,
+) -> Result<Vec<Vec<SliceContour<P2, P3>>>>
+where
+    M: IsMesh3D<P3>,
+    P2: IsBuildable2D,
+    P3: IsBuildable3D + IsTransFormableTo2D,
+    PL: IsPlane3D<P3, N>,
+    N: IsNormalized3D,
+{
+    let origin = plane.origin();
+    let normal: Point3D = cross(&plane.u(), &Point3D::new_from(&plane.v()));
+
+    let mut result = Vec::with_capacity(count);
+    for i in 0..count {
+        let offset = i as f64 * *spacing;
+        let moved = PL::new(
+            P3::new(
+                origin.x() + offset * normal.x(),
+                origin.y() + offset * normal.y(),
+                origin.z() + offset * normal.z(),
+            ),
+            plane.u(),
+            plane.v(),
+        );
+        result.push(slice_mesh(mesh, &moved)?);
+    }
+    Ok(result)
+}
+
+//------------------------------------------------------------------------------
+
+/// Intersection of the plane with the edge between a vertex below and one above the plane
+fn edge_point<P, M>(mesh: &M, distances: &[f64], p: usize, q: usize) -> Result<P>
+where
+    M: IsMesh3D<P>,
+    P: IsBuildable3D,
+{
+    let vp = mesh.vertex(VId(p)).ok_or(ErrorKind::IncorrectVertexID)?;
+    let vq = mesh.vertex(VId(q)).ok_or(ErrorKind::IncorrectVertexID)?;
+    let (dp, dq) = (distances[p], distances[q]);
+    let t = if dp == dq { 0.0 } else { dp / (dp - dq) };
+    Ok(P::new(
+        vp.x() + t * (vq.x() - vp.x()),
+        vp.y() + t * (vq.y() - vp.y()),
+        vp.z() + t * (vq.z() - vp.z()),
+    ))
+}
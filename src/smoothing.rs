@@ -0,0 +1,206 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Algorithms to smooth the vertices of meshes
+
+use crate::*;
+
+//------------------------------------------------------------------------------
+
+/// Algorithm to smooth a mesh by moving each vertex towards the weighted center of its neighbours
+/// Each iteration moves the vertices by lambda (usually within (0.0, 1.0]) times the offset to that center
+/// Vertices on the boundary keep their position if pin_boundary is set
+/// This shrinks the mesh, see taubin_smoothing for a volume preserving alternative
+pub fn laplacian_smoothing<P, M>(
+    mesh: &mut M,
+    weighting: &LaplacianWeighting,
+    lambda: f64,
+    iterations: usize,
+    pin_boundary: bool,
+) -> Result<()>
+where
+    M: IsSearchableMesh<P, Face3> + IsVertexEditableMesh<P, Face3>,
+    P: IsBuildable3D,
+{
+    let data = SmoothingData::new(mesh, pin_boundary)?;
+    for _ in 0..iterations {
+        data.step(mesh, weighting, lambda)?;
+    }
+    Ok(())
+}
+
+/// Algorithm to smooth a mesh without shrinking it, alternating Laplacian steps with lambda and mu
+/// lambda should be positive and mu negative with a larger absolute value (e.g. 0.5 and -0.53)
+/// Each iteration consists of one step with lambda and one with mu
+/// Vertices on the boundary keep their position if pin_boundary is set
+pub fn taubin_smoothing<P, M>(
+    mesh: &mut M,
+    weighting: &LaplacianWeighting,
+    lambda: f64,
+    mu: f64,
+    iterations: usize,
+    pin_boundary: bool,
+) -> Result<()>
+where
+    M: IsSearchableMesh<P, Face3> + IsVertexEditableMesh<P, Face3>,
+    P: IsBuildable3D,
+{
+    let data = SmoothingData::new(mesh, pin_boundary)?;
+    for _ in 0..iterations {
+        data.step(mesh, weighting, lambda)?;
+        data.step(mesh, weighting, mu)?;
+    }
+    Ok(())
+}
+
+//------------------------------------------------------------------------------
+
+/// Topology of the mesh required for smoothing, which doesn't change while smoothing
+struct SmoothingData {
+    neighbours: Vec<Vec<VId>>,
+    faces: Vec<Vec<FId>>,
+    pinned: Vec<bool>,
+}
+
+impl SmoothingData {
+    fn new<P, M>(mesh: &M, pin_boundary: bool) -> Result<Self>
+    where
+        M: IsSearchableMesh<P, Face3>,
+    {
+        let nv = mesh.num_vertices();
+        let mut neighbours = Vec::with_capacity(nv);
+        let mut faces = Vec::with_capacity(nv);
+        let mut pinned = Vec::with_capacity(nv);
+        let mut cache = Vec::new();
+
+        for i in 0..nv {
+            let vid = VId(i);
+            let mut ns = Vec::new();
+            mesh.vertex_neighbours(vid, &mut cache, &mut ns)?;
+            neighbours.push(ns);
+
+            let mut fs = Vec::new();
+            mesh.faces_of_vertex(vid, &mut cache, &mut fs)?;
+            faces.push(fs);
+
+            pinned.push(pin_boundary && mesh.is_vertex_on_boundary(vid, &mut cache)?);
+        }
+
+        Ok(Self {
+            neighbours,
+            faces,
+            pinned,
+        })
+    }
+
+    fn step<P, M>(&self, mesh: &mut M, weighting: &LaplacianWeighting, factor: f64) -> Result<()>
+    where
+        M: IsSearchableMesh<P, Face3> + IsVertexEditableMesh<P, Face3>,
+        P: IsBuildable3D,
+    {
+        let nv = mesh.num_vertices();
+        let mut positions = Vec::with_capacity(nv);
+        for i in 0..nv {
+            let v = mesh.vertex(VId(i)).ok_or(ErrorKind::IncorrectVertexID)?;
+            positions.push(Point3D::new_from(&v));
+        }
+
+        let mut weights = Vec::new();
+        let mut moved = Vec::with_capacity(nv);
+        for i in 0..nv {
+            if self.pinned[i] || self.neighbours[i].is_empty() {
+                moved.push(None);
+                continue;
+            }
+
+            weights.clear();
+            if *weighting == LaplacianWeighting::Cotangent {
+                self.cotangent_weights(mesh, &positions, VId(i), &mut weights)?;
+            }
+            // obtuse triangles can result in negative weights, which are ignored, falling back to uniform ones if none remain
+            if weights.iter().all(|(_, w)| *w <= 0.0) {
+                weights.clear();
+                weights.extend(self.neighbours[i].iter().map(|n| (*n, 1.0)));
+            }
+
+            let mut center = Point3D::default();
+            let mut sum = 0.0;
+            for (n, w) in weights.iter() {
+                if *w > 0.0 {
+                    center = center + positions[n.0].clone() * *w;
+                    sum += w;
+                }
+            }
+            let center = center / sum;
+            let p = &positions[i];
+            moved.push(Some(Point3D::new(
+                p.x + factor * (center.x - p.x),
+                p.y + factor * (center.y - p.y),
+                p.z + factor * (center.z - p.z),
+            )));
+        }
+
+        for (i, p) in moved.into_iter().enumerate() {
+            if let Some(p) = p {
+                let mut v = mesh.vertex(VId(i)).ok_or(ErrorKind::IncorrectVertexID)?;
+                v.from(&p);
+                mesh.change_vertex(VId(i), v)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Appends the cotangent weights of the neighbours of a vertex, summing cot α + cot β of the faces of each edge
+    fn cotangent_weights<P, M>(
+        &self,
+        mesh: &M,
+        positions: &[Point3D],
+        vid: VId,
+        result: &mut Vec<(VId, f64)>,
+    ) -> Result<()>
+    where
+        M: IsMesh<P, Face3>,
+    {
+        let neighbours = &self.neighbours[vid.0];
+        result.extend(neighbours.iter().map(|n| (*n, 0.0)));
+        let mut add = |n: VId, w: f64| {
+            if let Some(k) = neighbours.iter().position(|x| *x == n) {
+                result[k].1 += w;
+            }
+        };
+        for f in self.faces[vid.0].iter() {
+            let face = mesh.face_vertex_ids(*f).ok_or(ErrorKind::IncorrectFaceID)?;
+            let (a, b) = if face.a == vid {
+                (face.b, face.c)
+            } else if face.b == vid {
+                (face.c, face.a)
+            } else {
+                (face.a, face.b)
+            };
+            let (pv, pa, pb) = (&positions[vid.0], &positions[a.0], &positions[b.0]);
+            add(a, cot(&conn(pb, pv), &conn(pb, pa)));
+            add(b, cot(&conn(pa, pv), &conn(pa, pb)));
+        }
+        Ok(())
+    }
+}
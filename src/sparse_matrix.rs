@@ -0,0 +1,147 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Sparse symmetric linear systems, as used by the Laplacian based algorithms
+
+//------------------------------------------------------------------------------
+
+/// Symmetric sparse matrix
+#[derive(Clone)]
+pub(crate) struct SparseMatrix {
+    diagonal: Vec<f64>,
+    off_diagonal: Vec<Vec<(usize, f64)>>,
+}
+
+impl SparseMatrix {
+    pub fn new(n: usize) -> Self {
+        Self {
+            diagonal: vec![0.0; n],
+            off_diagonal: vec![Vec::new(); n],
+        }
+    }
+
+    /// Adds value to the entries (i, j) and (j, i), or once to the diagonal if i == j
+    pub fn add(&mut self, i: usize, j: usize, value: f64) {
+        if i == j {
+            self.diagonal[i] += value;
+        } else {
+            self.off_diagonal[i].push((j, value));
+            self.off_diagonal[j].push((i, value));
+        }
+    }
+
+    /// Adds the weight of the edge between i and j as in a Laplacian
+    pub fn add_edge(&mut self, i: usize, j: usize, weight: f64) {
+        self.add(i, i, weight);
+        self.add(j, j, weight);
+        self.add(i, j, -weight);
+    }
+
+    pub fn add_diagonal(&mut self, values: &[f64]) {
+        for (d, x) in self.diagonal.iter_mut().zip(values.iter()) {
+            *d += x;
+        }
+    }
+
+    pub fn scale(&mut self, factor: f64) {
+        for d in self.diagonal.iter_mut() {
+            *d *= factor;
+        }
+        for (_, x) in self.off_diagonal.iter_mut().flatten() {
+            *x *= factor;
+        }
+    }
+
+    pub fn multiply(&self, x: &[f64], result: &mut [f64]) {
+        for (i, r) in result.iter_mut().enumerate() {
+            *r = self.diagonal[i] * x[i]
+                + self.off_diagonal[i]
+                    .iter()
+                    .map(|(j, w)| w * x[*j])
+                    .sum::<f64>();
+        }
+    }
+
+    /// Solves the system via conjugate gradients, the matrix must be positive (semi-)definite
+    pub fn solve(&self, b: &[f64]) -> Vec<f64> {
+        self.solve_fixed(b, &vec![None; b.len()])
+    }
+
+    /// Solves the system for the unknowns which aren't fixed to a value, the matrix restricted to those must be positive (semi-)definite
+    pub fn solve_fixed(&self, b: &[f64], fixed: &[Option<f64>]) -> Vec<f64> {
+        let n = b.len();
+        let dot = |x: &[f64], y: &[f64]| x.iter().zip(y.iter()).map(|(a, b)| a * b).sum::<f64>();
+        let mask = |x: &mut [f64]| {
+            for (v, f) in x.iter_mut().zip(fixed.iter()) {
+                if f.is_some() {
+                    *v = 0.0;
+                }
+            }
+        };
+
+        let mut x: Vec<f64> = fixed.iter().map(|f| f.unwrap_or(0.0)).collect();
+        let mut r = vec![0.0; n];
+        self.multiply(&x, &mut r);
+        for (ri, bi) in r.iter_mut().zip(b.iter()) {
+            *ri = bi - *ri;
+        }
+        mask(&mut r);
+
+        let mut p = r.clone();
+        let mut ap = vec![0.0; n];
+        let mut rr = dot(&r, &r);
+        let tolerance = CG_TOLERANCE * CG_TOLERANCE * dot(b, b).max(rr);
+
+        for _ in 0..(CG_MAX_ITERATIONS_FACTOR * n + 10) {
+            if rr <= tolerance || rr == 0.0 {
+                break;
+            }
+            self.multiply(&p, &mut ap);
+            mask(&mut ap);
+            let pap = dot(&p, &ap);
+            if pap <= 0.0 {
+                break;
+            }
+            let alpha = rr / pap;
+            for ((xi, ri), (pi, api)) in x.iter_mut().zip(r.iter_mut()).zip(p.iter().zip(ap.iter()))
+            {
+                *xi += alpha * pi;
+                *ri -= alpha * api;
+            }
+            let rr_next = dot(&r, &r);
+            let beta = rr_next / rr;
+            for (pi, ri) in p.iter_mut().zip(r.iter()) {
+                *pi = ri + beta * *pi;
+            }
+            rr = rr_next;
+        }
+        x
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Relative residual at which the conjugate gradients stop
+const CG_TOLERANCE: f64 = 1e-12;
+
+/// Maximum number of conjugate gradient iterations, relative to the number of unknowns
+const CG_MAX_ITERATIONS_FACTOR: usize = 10;
@@ -94,6 +94,7 @@ strong_usize!(VId);
 strong_usize!(FId);
 strong_usize!(EId);
 strong_usize!(SId);
+strong_usize!(OId);
 
 strong_f64!(Deg);
 strong_f64!(Rad);
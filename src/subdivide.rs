@@ -27,6 +27,7 @@ use crate::*;
 use std::{
     cmp::{max, min},
     collections::HashMap,
+    f64::consts::PI,
 };
 
 //------------------------------------------------------------------------------
@@ -51,7 +52,11 @@ where
 
     for i in 0..n_vertices {
         // safe since iterating n_vertices
-        mo.add_vertex(mi.vertex(VId(i)).unwrap());
+        mo.add_vertex_derived(
+            mi.vertex(VId(i)).unwrap(),
+            mi.vertex_attributes(),
+            &[(VId(i), 1.0)],
+        )?;
     }
 
     for i in 0..n_faces {
@@ -61,21 +66,296 @@ where
         // safe since iterating n_faces
         let [v1, v2, v3] = mi.face_vertices(FId(i)).unwrap();
 
-        let ia = *added_edges
-            .entry((min(vi1, vi2), max(vi1, vi2)))
-            .or_insert_with(|| mo.add_vertex(v1.center_nd(&v2, &mut center_buffer).unwrap()));
-        let ib = *added_edges
-            .entry((min(vi2, vi3), max(vi2, vi3)))
-            .or_insert_with(|| mo.add_vertex(v2.center_nd(&v3, &mut center_buffer).unwrap()));
-        let ic = *added_edges
-            .entry((min(vi3, vi1), max(vi3, vi1)))
-            .or_insert_with(|| mo.add_vertex(v3.center_nd(&v1, &mut center_buffer).unwrap()));
-
-        mo.try_add_connection(vi1, ia, ic)?;
-        mo.try_add_connection(ia, vi2, ib)?;
-        mo.try_add_connection(ia, ib, ic)?;
-        mo.try_add_connection(ic, ib, vi3)?;
+        let mut edge_vertex = |a: VId, b: VId, va: &V, vb: &V| -> Result<VId> {
+            let key = (min(a, b), max(a, b));
+            match added_edges.get(&key) {
+                Some(vid) => Ok(*vid),
+                None => {
+                    let vid = mo.add_vertex_derived(
+                        va.center_nd(vb, &mut center_buffer)?,
+                        mi.vertex_attributes(),
+                        &[(a, 0.5), (b, 0.5)],
+                    )?;
+                    added_edges.insert(key, vid);
+                    Ok(vid)
+                }
+            }
+        };
+
+        let ia = edge_vertex(vi1, vi2, &v1, &v2)?;
+        let ib = edge_vertex(vi2, vi3, &v2, &v3)?;
+        let ic = edge_vertex(vi3, vi1, &v3, &v1)?;
+
+        let source = mi.face_attributes();
+        mo.try_add_connection_derived(vi1, ia, ic, source, FId(i))?;
+        mo.try_add_connection_derived(ia, vi2, ib, source, FId(i))?;
+        mo.try_add_connection_derived(ia, ib, ic, source, FId(i))?;
+        mo.try_add_connection_derived(ic, ib, vi3, source, FId(i))?;
+    }
+
+    Ok(mo)
+}
+
+/// Subdivides a mesh using Loop's scheme by creating four faces for each input face
+/// This smoothens the mesh, since all vertices are moved towards a weighted average of their neighbours
+/// Boundary edges and vertices only take neighbouring boundary vertices into account
+pub fn loop_subdivision<V, MI, MO>(mi: &MI) -> Result<MO>
+where
+    MI: IsSearchableMesh<V, Face3>,
+    MO: IsFaceEditableMesh<V, Face3> + IsVertexEditableMesh<V, Face3> + Default,
+    V: IsBuildable3D,
+{
+    let n_vertices = mi.num_vertices();
+
+    let mut mo = MO::default();
+    mo.reserve_vertices(4 * n_vertices);
+    mo.reserve_faces(4 * mi.num_faces());
+
+    let mut cache = Vec::new();
+    let mut neighbours = Vec::new();
+
+    for i in 0..n_vertices {
+        let vid = VId(i);
+        let p = position(mi, vid)?;
+
+        boundary_neighbours(mi, vid, &mut cache, &mut neighbours)?;
+        let moved = if !neighbours.is_empty() {
+            if neighbours.len() == 2 {
+                p * 0.75 + (position(mi, neighbours[0])? + position(mi, neighbours[1])?) * 0.125
+            } else {
+                // corners of non-manifold boundaries are kept
+                p
+            }
+        } else {
+            neighbours.clear();
+            mi.vertex_neighbours(vid, &mut cache, &mut neighbours)?;
+            let n = neighbours.len();
+            if n == 0 {
+                p
+            } else {
+                let x = 3.0 / 8.0 + 0.25 * (2.0 * PI / n as f64).cos();
+                let beta = (5.0 / 8.0 - x * x) / n as f64;
+                let mut sum = Point3D::default();
+                for nb in neighbours.iter() {
+                    sum = sum + position(mi, *nb)?;
+                }
+                p * (1.0 - n as f64 * beta) + sum * beta
+            }
+        };
+
+        mo.add_vertex_derived(V::new_from(&moved), mi.vertex_attributes(), &[(vid, 1.0)])?;
     }
 
+    split_faces(mi, &mut mo, &mut |e| {
+        let (a, b) = edge_positions(mi, e)?;
+        match mi.edge_twin(e) {
+            None => Ok((a + b) * 0.5),
+            Some(twin) => {
+                let c = position(mi, opposite(mi, e)?)?;
+                let d = position(mi, opposite(mi, twin)?)?;
+                Ok((a + b) * (3.0 / 8.0) + (c + d) * (1.0 / 8.0))
+            }
+        }
+    })?;
+
     Ok(mo)
 }
+
+/// Subdivides a mesh using the butterfly scheme by creating four faces for each input face
+/// This is an interpolating scheme, the input vertices are kept while the new ones are placed to smoothen the mesh
+/// Boundary edges use the four point scheme, missing wing vertices of interior edges are reflected at their edge
+pub fn butterfly<V, MI, MO>(mi: &MI) -> Result<MO>
+where
+    MI: IsSearchableMesh<V, Face3>,
+    MO: IsFaceEditableMesh<V, Face3> + IsVertexEditableMesh<V, Face3> + Default,
+    V: IsBuildable3D,
+{
+    let n_vertices = mi.num_vertices();
+
+    let mut mo = MO::default();
+    mo.reserve_vertices(4 * n_vertices);
+    mo.reserve_faces(4 * mi.num_faces());
+
+    for i in 0..n_vertices {
+        // safe since iterating n_vertices
+        mo.add_vertex_derived(
+            mi.vertex(VId(i)).unwrap(),
+            mi.vertex_attributes(),
+            &[(VId(i), 1.0)],
+        )?;
+    }
+
+    let mut cache = Vec::new();
+    let mut neighbours = Vec::new();
+
+    split_faces(mi, &mut mo, &mut |e| {
+        let (a, b) = edge_positions(mi, e)?;
+        let twin = match mi.edge_twin(e) {
+            Some(twin) => twin,
+            None => {
+                // four point scheme along the boundary
+                let vid_a = mi.edge_tail(e).ok_or(ErrorKind::IncorrectEdgeID)?;
+                let vid_b = mi.edge_head(e).ok_or(ErrorKind::IncorrectEdgeID)?;
+
+                boundary_neighbours(mi, vid_a, &mut cache, &mut neighbours)?;
+                let before = other_of_two(&neighbours, vid_b);
+                boundary_neighbours(mi, vid_b, &mut cache, &mut neighbours)?;
+                let after = other_of_two(&neighbours, vid_a);
+
+                return match (before, after) {
+                    (Some(before), Some(after)) => Ok((a + b) * (9.0 / 16.0)
+                        - (position(mi, before)? + position(mi, after)?) * (1.0 / 16.0)),
+                    _ => Ok((a + b) * 0.5),
+                };
+            }
+        };
+
+        let c = position(mi, opposite(mi, e)?)?;
+        let d = position(mi, opposite(mi, twin)?)?;
+
+        let mut wings = Point3D::default();
+        for (edge, opposite_position) in [
+            (mi.edge_next(e), &a),
+            (mi.edge_prev(e), &b),
+            (mi.edge_next(twin), &b),
+            (mi.edge_prev(twin), &a),
+        ]
+        .iter()
+        {
+            let edge = edge.ok_or(ErrorKind::IncorrectEdgeID)?;
+            wings = wings
+                + match mi.edge_twin(edge) {
+                    Some(wing_edge) => position(mi, opposite(mi, wing_edge)?)?,
+                    None => {
+                        let (x, y) = edge_positions(mi, edge)?;
+                        x + y - (*opposite_position).clone()
+                    }
+                };
+        }
+
+        Ok((a + b) * 0.5 + (c + d) * (1.0 / 8.0) - wings * (1.0 / 16.0))
+    })?;
+
+    Ok(mo)
+}
+
+//------------------------------------------------------------------------------
+
+/// Adds a new vertex for each edge and four faces for each face of mi to mo
+/// Expects the vertices of mi to be already present within mo (with the same ids)
+fn split_faces<V, MI, MO>(
+    mi: &MI,
+    mo: &mut MO,
+    edge_point: &mut dyn FnMut(EId) -> Result<Point3D>,
+) -> Result<()>
+where
+    MI: IsSearchableMesh<V, Face3>,
+    MO: IsFaceEditableMesh<V, Face3> + IsVertexEditableMesh<V, Face3>,
+    V: IsBuildable3D,
+{
+    let mut added_edges = HashMap::new();
+
+    for i in 0..mi.num_faces() {
+        // safe since iterating n_faces
+        let f = mi.face_vertex_ids(FId(i)).unwrap();
+        let (e1, e2, e3) = mi.edges_of_face(FId(i)).unwrap();
+
+        let mut edge_vertex = |e: EId| -> Result<VId> {
+            let tail = mi.edge_tail(e).ok_or(ErrorKind::IncorrectEdgeID)?;
+            let head = mi.edge_head(e).ok_or(ErrorKind::IncorrectEdgeID)?;
+            let key = (min(tail, head), max(tail, head));
+            match added_edges.get(&key) {
+                Some(vid) => Ok(*vid),
+                None => {
+                    let vid = mo.add_vertex_derived(
+                        V::new_from(&edge_point(e)?),
+                        mi.vertex_attributes(),
+                        &[(tail, 0.5), (head, 0.5)],
+                    )?;
+                    added_edges.insert(key, vid);
+                    Ok(vid)
+                }
+            }
+        };
+
+        // edges of a face are ordered as a -> b, b -> c and c -> a
+        let ia = edge_vertex(e1)?;
+        let ib = edge_vertex(e2)?;
+        let ic = edge_vertex(e3)?;
+
+        let source = mi.face_attributes();
+        mo.try_add_connection_derived(f.a, ia, ic, source, FId(i))?;
+        mo.try_add_connection_derived(ia, f.b, ib, source, FId(i))?;
+        mo.try_add_connection_derived(ia, ib, ic, source, FId(i))?;
+        mo.try_add_connection_derived(ic, ib, f.c, source, FId(i))?;
+    }
+
+    Ok(())
+}
+
+fn position<V, M>(mesh: &M, vid: VId) -> Result<Point3D>
+where
+    M: IsMesh<V, Face3>,
+    V: Is3D,
+{
+    mesh.vertex(vid)
+        .map(|v| Point3D::new_from(&v))
+        .ok_or(ErrorKind::IncorrectVertexID)
+}
+
+fn edge_positions<V, M>(mesh: &M, e: EId) -> Result<(Point3D, Point3D)>
+where
+    M: IsSearchableMesh<V, Face3>,
+    V: Is3D,
+{
+    let tail = mesh.edge_tail(e).ok_or(ErrorKind::IncorrectEdgeID)?;
+    let head = mesh.edge_head(e).ok_or(ErrorKind::IncorrectEdgeID)?;
+    Ok((position(mesh, tail)?, position(mesh, head)?))
+}
+
+/// The vertex opposite to the edge within its face
+fn opposite<V, M>(mesh: &M, e: EId) -> Result<VId>
+where
+    M: IsSearchableMesh<V, Face3>,
+{
+    mesh.edge_next(e)
+        .and_then(|next| mesh.edge_head(next))
+        .ok_or(ErrorKind::IncorrectEdgeID)
+}
+
+/// Overwrites result with the vertices connected to vid via boundary edges
+fn boundary_neighbours<V, M>(
+    mesh: &M,
+    vid: VId,
+    cache: &mut Vec<EId>,
+    result: &mut Vec<VId>,
+) -> Result<()>
+where
+    M: IsSearchableMesh<V, Face3>,
+{
+    result.clear();
+    let mut edges = Vec::new();
+    mesh.edges_of_vertex(vid, cache, &mut edges)?;
+
+    for e in edges {
+        if !mesh.is_edge_on_boundary(e) {
+            continue;
+        }
+        let tail = mesh.edge_tail(e).ok_or(ErrorKind::IncorrectEdgeID)?;
+        let head = mesh.edge_head(e).ok_or(ErrorKind::IncorrectEdgeID)?;
+        result.push(if tail == vid { head } else { tail });
+    }
+    result.sort();
+    result.dedup();
+
+    Ok(())
+}
+
+/// If there are exactly two vertices, returns the one not equal to vid
+fn other_of_two(vids: &[VId], vid: VId) -> Option<VId> {
+    match vids {
+        [a, b] if *a == vid => Some(*b),
+        [a, b] if *b == vid => Some(*a),
+        _ => None,
+    }
+}
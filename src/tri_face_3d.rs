@@ -26,6 +26,7 @@ use crate::*;
 
 //------------------------------------------------------------------------------
 
+#[derive(Debug, Clone)]
 /// Face with 3 corners in 3D space
 pub struct TriFace3D {
     a: Point3D,
@@ -54,6 +55,14 @@ impl TriFace3D {
     pub fn c(&self) -> &Point3D {
         &self.c
     }
+
+    /// Returns the point of the face closest to p
+    pub fn closest_point<P>(&self, p: &P) -> Point3D
+    where
+        P: Is3D,
+    {
+        closest_point_on_triangle_3d(p, &self.a, &self.b, &self.c)
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -104,3 +113,15 @@ impl HasBoundingBox3DMaybe for TriFace3D {
         Some(self.bounding_box())
     }
 }
+
+//------------------------------------------------------------------------------
+
+impl<P> HasDistanceTo<P> for TriFace3D
+where
+    P: Is3D,
+{
+    fn sqr_distance(&self, other: &P) -> NonNegative {
+        NonNegative::new(sqr_dist_point_triangle_3d(other, &self.a, &self.b, &self.c))
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,366 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! UniformGrid3D, a spatial hash of cubic cells for fast neighbour searches of positions in 3D
+
+use crate::*;
+
+use fnv::FnvHashMap;
+
+use std::cmp::Ordering;
+
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+/// UniformGrid3D, a spatial hash of cubic cells for fast neighbour searches of positions in 3D
+/// Only occupied cells are stored, so the extent of the data is unbounded
+/// Choose a cell size in the magnitude of typical search distances
+pub struct UniformGrid3D<P>
+where
+    P: Is3D,
+{
+    cell_size: Positive,
+    cells: FnvHashMap<Cell, Vec<P>>,
+    min: Cell,
+    max: Cell,
+    len: usize,
+}
+
+type Cell = (i64, i64, i64);
+
+//------------------------------------------------------------------------------
+
+impl<P> UniformGrid3D<P>
+where
+    P: Is3D,
+{
+    /// Creates a new, empty grid with the given cell size
+    pub fn new(cell_size: Positive) -> Self {
+        Self {
+            cell_size,
+            cells: FnvHashMap::default(),
+            min: (0, 0, 0),
+            max: (0, 0, 0),
+            len: 0,
+        }
+    }
+    /// Creates a new grid with the given cell size and data
+    pub fn with_data(cell_size: Positive, data: Vec<P>) -> Self {
+        let mut result = Self::new(cell_size);
+        for p in data {
+            result.insert(p);
+        }
+        result
+    }
+    /// Inserts a new position
+    pub fn insert(&mut self, p: P) {
+        let cell = self.cell_of(&p);
+        if self.len == 0 {
+            self.min = cell;
+            self.max = cell;
+        } else {
+            self.min = (
+                self.min.0.min(cell.0),
+                self.min.1.min(cell.1),
+                self.min.2.min(cell.2),
+            );
+            self.max = (
+                self.max.0.max(cell.0),
+                self.max.1.max(cell.1),
+                self.max.2.max(cell.2),
+            );
+        }
+        self.cells.entry(cell).or_default().push(p);
+        self.len += 1;
+    }
+    /// The edge length of the cells
+    pub fn cell_size(&self) -> Positive {
+        self.cell_size
+    }
+    /// Number of stored positions
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Whether no positions are stored
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Number of occupied cells
+    pub fn n_cells(&self) -> usize {
+        self.cells.len()
+    }
+    /// Applies a function to each position
+    pub fn for_each(&self, f: &mut dyn FnMut(&P)) {
+        for ps in self.cells.values() {
+            for p in ps {
+                f(p)
+            }
+        }
+    }
+
+    fn cell_of<Q>(&self, p: &Q) -> Cell
+    where
+        Q: Is3D,
+    {
+        (
+            self.index_of(p.x()),
+            self.index_of(p.y()),
+            self.index_of(p.z()),
+        )
+    }
+
+    fn index_of(&self, x: f64) -> i64 {
+        (x / *self.cell_size).floor() as i64
+    }
+
+    fn for_each_in_range(&self, min: Cell, max: Cell, f: &mut dyn FnMut(&P)) {
+        // clamp to the occupied range to avoid iterating huge empty areas
+        let min = (
+            min.0.max(self.min.0),
+            min.1.max(self.min.1),
+            min.2.max(self.min.2),
+        );
+        let max = (
+            max.0.min(self.max.0),
+            max.1.min(self.max.1),
+            max.2.min(self.max.2),
+        );
+
+        // for sparse data it's cheaper to check all occupied cells
+        if n_cells_in(min, max) > self.cells.len() as u64 {
+            for (cell, ps) in self.cells.iter() {
+                if (min.0..=max.0).contains(&cell.0)
+                    && (min.1..=max.1).contains(&cell.1)
+                    && (min.2..=max.2).contains(&cell.2)
+                {
+                    for p in ps {
+                        f(p)
+                    }
+                }
+            }
+            return;
+        }
+
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                for z in min.2..=max.2 {
+                    if let Some(ps) = self.cells.get(&(x, y, z)) {
+                        for p in ps {
+                            f(p)
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // cells with a chebyshev distance of exactly r to the center
+    // clamped to the occupied range to avoid iterating huge empty areas
+    fn for_each_in_shell(&self, center: Cell, r: i64, f: &mut dyn FnMut(&P)) {
+        let mut visit = |cell: Cell| {
+            if let Some(ps) = self.cells.get(&cell) {
+                for p in ps {
+                    f(p)
+                }
+            }
+        };
+
+        let z_min = (center.2 - r).max(self.min.2);
+        let z_max = (center.2 + r).min(self.max.2);
+
+        for x in (center.0 - r).max(self.min.0)..=(center.0 + r).min(self.max.0) {
+            for y in (center.1 - r).max(self.min.1)..=(center.1 + r).min(self.max.1) {
+                let on_border = (x - center.0).abs() == r || (y - center.1).abs() == r;
+                if on_border {
+                    for z in z_min..=z_max {
+                        visit((x, y, z))
+                    }
+                } else {
+                    if center.2 - r >= self.min.2 {
+                        visit((x, y, center.2 - r))
+                    }
+                    if center.2 + r <= self.max.2 {
+                        visit((x, y, center.2 + r))
+                    }
+                }
+            }
+        }
+    }
+
+    // number of occupied range cells within chebyshev distance r of the center
+    fn n_cells_around(&self, center: Cell, r: i64) -> u64 {
+        n_cells_in(
+            (
+                (center.0 - r).max(self.min.0),
+                (center.1 - r).max(self.min.1),
+                (center.2 - r).max(self.min.2),
+            ),
+            (
+                (center.0 + r).min(self.max.0),
+                (center.1 + r).min(self.max.1),
+                (center.2 + r).min(self.max.2),
+            ),
+        )
+    }
+
+    // chebyshev distance of the cell to the occupied range
+    fn distance_to_occupied(&self, cell: Cell) -> i64 {
+        let axis = |c: i64, min: i64, max: i64| (min - c).max(c - max).max(0);
+        axis(cell.0, self.min.0, self.max.0)
+            .max(axis(cell.1, self.min.1, self.max.1))
+            .max(axis(cell.2, self.min.2, self.max.2))
+    }
+
+    // whether the shell r around center covers the whole occupied range
+    fn covers_all(&self, center: Cell, r: i64) -> bool {
+        center.0 - r <= self.min.0
+            && center.1 - r <= self.min.1
+            && center.2 - r <= self.min.2
+            && center.0 + r >= self.max.0
+            && center.1 + r >= self.max.1
+            && center.2 + r >= self.max.2
+    }
+}
+
+//------------------------------------------------------------------------------
+
+// number of cells within the inclusive range, saturating for huge ranges
+fn n_cells_in(min: Cell, max: Cell) -> u64 {
+    let extent = |min: i64, max: i64| (max - min + 1).max(0) as u64;
+    extent(min.0, max.0)
+        .saturating_mul(extent(min.1, max.1))
+        .saturating_mul(extent(min.2, max.2))
+}
+
+fn chebyshev(a: Cell, b: Cell) -> i64 {
+    (a.0 - b.0)
+        .abs()
+        .max((a.1 - b.1).abs())
+        .max((a.2 - b.2).abs())
+}
+
+//------------------------------------------------------------------------------
+
+impl<P> IsSphereSearchable<P> for UniformGrid3D<P>
+where
+    P: Is3D + Clone,
+{
+    fn in_sphere(&self, sphere: &Sphere, result: &mut Vec<P>) {
+        if self.is_empty() {
+            return;
+        }
+        let r = *sphere.radius;
+        let c = &sphere.center;
+        let min = self.cell_of(&Point3D::new(c.x() - r, c.y() - r, c.z() - r));
+        let max = self.cell_of(&Point3D::new(c.x() + r, c.y() + r, c.z() + r));
+
+        self.for_each_in_range(min, max, &mut |p| {
+            if dist_3d(c, p) <= r {
+                result.push(p.clone())
+            }
+        })
+    }
+}
+
+impl<P> IsBox3DSearchable<P> for UniformGrid3D<P>
+where
+    P: Is3D + Clone,
+{
+    fn in_box(&self, box_3d: &Box3D, result: &mut Vec<P>) {
+        if self.is_empty() {
+            return;
+        }
+        let (bmin, bmax) = (box_3d.min_p(), box_3d.max_p());
+
+        self.for_each_in_range(self.cell_of(&bmin), self.cell_of(&bmax), &mut |p| {
+            if p.x() >= bmin.x()
+                && p.x() <= bmax.x()
+                && p.y() >= bmin.y()
+                && p.y() <= bmax.y()
+                && p.z() >= bmin.z()
+                && p.z() <= bmax.z()
+            {
+                result.push(p.clone())
+            }
+        })
+    }
+}
+
+impl<PSearch, PFind> IsKNearestSearchable<PSearch, PFind> for UniformGrid3D<PFind>
+where
+    PSearch: Is3D,
+    PFind: Is3D + Clone,
+{
+    fn knearest(&self, search: &PSearch, n: usize, result: &mut Vec<PFind>) {
+        if n < 1 || self.is_empty() {
+            return;
+        }
+
+        let center = self.cell_of(search);
+        let mut candidates: Vec<(f64, PFind)> = Vec::new();
+        // shells closer than the occupied range are empty
+        let mut r = self.distance_to_occupied(center);
+
+        // search within growing shells of cells
+        // after visiting shell r, all positions within r * cell_size have been found
+        loop {
+            // once the shells hold more cells than are occupied, check all remaining occupied cells at once
+            if self.n_cells_around(center, r) > self.cells.len() as u64 {
+                for (cell, ps) in self.cells.iter() {
+                    if chebyshev(*cell, center) >= r {
+                        for p in ps {
+                            candidates.push((sqr_dist_3d(search, p), p.clone()))
+                        }
+                    }
+                }
+                break;
+            }
+
+            self.for_each_in_shell(center, r, &mut |p| {
+                candidates.push((sqr_dist_3d(search, p), p.clone()))
+            });
+
+            if candidates.len() >= n {
+                candidates.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+                candidates.truncate(n);
+                let reached = r as f64 * *self.cell_size;
+                if candidates[n - 1].0 <= reached * reached {
+                    break;
+                }
+            }
+
+            if self.covers_all(center, r) {
+                break;
+            }
+            r += 1;
+        }
+
+        candidates.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        result.extend(candidates.into_iter().take(n).map(|(_, p)| p));
+    }
+
+    fn nearest(&self, search: &PSearch) -> Option<PFind> {
+        let mut result = Vec::new();
+        self.knearest(search, 1, &mut result);
+        result.pop()
+    }
+}
@@ -98,18 +98,18 @@ where
     result.reserve_vertices(n_v_total);
     result.reserve_faces(n_f_total);
     for i in 0..n_v_total {
-        result.add_vertex(mesh.vertex(VId(i)).unwrap()); // safe since index safe
+        // safe since index safe
+        result.add_vertex_derived(
+            mesh.vertex(VId(i)).unwrap(),
+            mesh.vertex_attributes(),
+            &[(VId(i), 1.0)],
+        )?;
     }
 
     for i in 0..n_f_total {
         let f = mesh.face_vertex_ids(FId(i)).unwrap(); // safe since index safe
-        if must_flip[i] {
-            //println!("add normal");
-            result.try_add_connection(f.a, f.c, f.b).unwrap(); // safe assuming original mesh was valid
-        } else {
-            //println!("add flipped");
-            result.try_add_connection(f.a, f.b, f.c).unwrap(); // safe assuming original mesh was valid
-        }
+        let (b, c) = if must_flip[i] { (f.c, f.b) } else { (f.b, f.c) };
+        result.try_add_connection_derived(f.a, b, c, mesh.face_attributes(), FId(i))?;
     }
 
     Ok(result)
@@ -0,0 +1,107 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Algorithms to calculate the normals of the vertices of a mesh
+
+use crate::*;
+
+//------------------------------------------------------------------------------
+
+/// Calculates the normals of the vertices of a mesh, weighting the normals of the adjacent faces
+/// Vertices without (non-degenerate) faces get the z axis as normal
+pub fn vertex_normals<P, M>(mesh: &M, weighting: &NormalWeighting) -> Result<Vec<Norm3D>>
+where
+    M: IsMesh<P, Face3>,
+    P: Is3D,
+{
+    let mut sums = vec![Point3D::default(); mesh.num_vertices()];
+
+    for i in 0..mesh.num_faces() {
+        let vids = mesh
+            .face_vertex_ids(FId(i))
+            .ok_or(ErrorKind::IncorrectFaceID)?;
+        let [a, b, c] = mesh
+            .face_vertices(FId(i))
+            .ok_or(ErrorKind::IncorrectFaceID)?;
+        let ps = [
+            Point3D::new_from(&a),
+            Point3D::new_from(&b),
+            Point3D::new_from(&c),
+        ];
+
+        let n: Point3D = cross(&conn(&ps[0], &ps[1]), &conn(&ps[0], &ps[2]));
+        let double_area = *n.abs();
+        if double_area == 0.0 {
+            continue;
+        }
+
+        for (k, vid) in [vids.a, vids.b, vids.c].iter().enumerate() {
+            let weight = match weighting {
+                NormalWeighting::Uniform => 1.0 / double_area,
+                NormalWeighting::Area => 1.0,
+                NormalWeighting::Angle => {
+                    let corner = &ps[k];
+                    let to_next = conn(corner, &ps[(k + 1) % 3]);
+                    let to_prev = conn(corner, &ps[(k + 2) % 3]);
+                    match to_next.rad_to(&to_prev) {
+                        Ok(x) => x.0 / double_area,
+                        Err(_) => 0.0,
+                    }
+                }
+            };
+            let sum = sums.get_mut(vid.0).ok_or(ErrorKind::IncorrectVertexID)?;
+            *sum = sum.clone() + n.clone() * weight;
+        }
+    }
+
+    Ok(sums
+        .into_iter()
+        .map(|x| Norm3D::new(x).unwrap_or(Norm3D::norm_z()))
+        .collect())
+}
+
+//------------------------------------------------------------------------------
+
+/// Algorithm to split vertices at sharp edges, so vertex normals can represent creases
+/// Faces of a vertex stay connected if the angle between their normals is at most crease_angle
+/// Each additional group of faces gets its own copy of the vertex, this also splits non-manifold vertices
+pub fn split_creases<P, M>(mesh: &M, crease_angle: Rad) -> Result<M>
+where
+    M: IsFaceEditableMesh<P, Face3> + IsVertexEditableMesh<P, Face3> + Default,
+    P: IsBuildable3D,
+{
+    let topology = MeshTopology::new(mesh)?;
+
+    let mut normals = Vec::with_capacity(mesh.num_faces());
+    for i in 0..mesh.num_faces() {
+        normals.push(mesh.face_normal(FId(i)).ok());
+    }
+
+    let cos_max = crease_angle.0.cos();
+    split_vertex_fans(mesh, &topology, |v| {
+        topology.vertex_fans_where(v, |f, g| match (&normals[f], &normals[g]) {
+            (Some(nf), Some(ng)) => nf.dot(ng) >= cos_max,
+            // degenerate faces don't form creases
+            _ => true,
+        })
+    })
+}
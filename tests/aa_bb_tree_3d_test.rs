@@ -0,0 +1,274 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+
+use rust_3d::*;
+
+fn boxes(size: f64) -> Vec<BoundingBox3D> {
+    let mut result = Vec::new();
+    for i in 0..20 {
+        for j in 0..10 {
+            // uneven distribution in x
+            let x = (i * i) as f64 * 0.1;
+            let y = j as f64;
+            let z = ((i + j) % 3) as f64;
+            result.push(
+                BoundingBox3D::new(
+                    &Point3D::new(x, y, z),
+                    &Point3D::new(x + size, y + size, z + size),
+                )
+                .unwrap(),
+            );
+        }
+    }
+    result
+}
+
+fn sorted_centers(bbs: &[&BoundingBox3D]) -> Vec<(f64, f64, f64)> {
+    let mut result = bbs
+        .iter()
+        .map(|x| {
+            let c = x.center_bb();
+            (c.x(), c.y(), c.z())
+        })
+        .collect::<Vec<_>>();
+    result.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    result
+}
+
+fn brute_force<'a>(data: &'a [BoundingBox3D], bb: &BoundingBox3D) -> Vec<&'a BoundingBox3D> {
+    data.iter().filter(|x| x.collides_with(bb)).collect()
+}
+
+#[test]
+fn aa_bb_tree_3d_sah_test() {
+    let data = boxes(0.5);
+    let tree = AABBTree3D::new_sah(data.clone(), 2);
+    let query =
+        BoundingBox3D::new(&Point3D::new(1.0, 2.0, 0.2), &Point3D::new(8.0, 5.0, 1.2)).unwrap();
+
+    let mut result = Vec::new();
+    tree.bb_colliding(&query, &mut result);
+    let expected = brute_force(&data, &query);
+
+    assert!(!expected.is_empty());
+    assert_eq!(sorted_centers(&result), sorted_centers(&expected));
+
+    let mut n_candidates = 0;
+    tree.for_each_collision_candidate(&query, &mut |_| n_candidates += 1);
+    assert_eq!(n_candidates, expected.len());
+}
+
+#[test]
+fn aa_bb_tree_flat_3d_test() {
+    let data = boxes(0.5);
+    let mut tree = AABBTreeFlat3D::new(data.clone(), 2);
+    assert_eq!(tree.len(), data.len());
+
+    let query =
+        BoundingBox3D::new(&Point3D::new(1.0, 2.0, 0.2), &Point3D::new(8.0, 5.0, 1.2)).unwrap();
+
+    let mut result = Vec::new();
+    tree.bb_colliding(&query, &mut result);
+    let expected = brute_force(&data, &query);
+    assert!(!expected.is_empty());
+    assert_eq!(sorted_centers(&result), sorted_centers(&expected));
+
+    let mut result = Vec::new();
+    tree.bb_crossing_x_value(3.7, &mut result);
+    let expected = data
+        .iter()
+        .filter(|x| x.crossing_x_value(3.7))
+        .collect::<Vec<_>>();
+    assert_eq!(sorted_centers(&result), sorted_centers(&expected));
+
+    let line = Line3D {
+        anchor: Point3D::new(-1.0, 2.2, 0.2),
+        dir: Norm3D::new(Point3D::new(1.0, 0.0, 0.0)).unwrap(),
+    };
+    let mut n_candidates = 0;
+    tree.for_each_intersection_candidate(&line, &mut |_| n_candidates += 1);
+    assert!(n_candidates > 0);
+    assert!(n_candidates < data.len());
+
+    // move everything and refit, the queries must follow
+    for x in tree.data_mut().iter_mut() {
+        x.move_by(100.0, 0.0, 0.0);
+    }
+    tree.refit();
+
+    let mut result = Vec::new();
+    tree.bb_colliding(&query, &mut result);
+    assert!(result.is_empty());
+
+    let mut moved_query = query.clone();
+    moved_query.move_by(100.0, 0.0, 0.0);
+    let mut result = Vec::new();
+    tree.bb_colliding(&moved_query, &mut result);
+    assert_eq!(result.len(), brute_force(&data, &query).len());
+
+    let bb = tree.bounding_box_maybe().unwrap();
+    assert!(bb.min_p().x() >= 100.0);
+}
+
+#[test]
+fn aa_bb_tree_flat_2d_test() {
+    let data = (0..50)
+        .map(|i| {
+            let x = (i * i) as f64 * 0.01;
+            BoundingBox2D::new(&Point2D::new(x, 0.0), &Point2D::new(x + 0.1, 1.0)).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let tree = AABBTreeFlat2D::new(data.clone(), 1);
+    let query = BoundingBox2D::new(&Point2D::new(2.0, 0.5), &Point2D::new(5.0, 0.6)).unwrap();
+
+    let mut result = Vec::new();
+    tree.bb_colliding(&query, &mut result);
+    let expected = data.iter().filter(|x| x.collides_with(&query)).count();
+    assert!(expected > 0);
+    assert_eq!(result.len(), expected);
+
+    let empty = AABBTreeFlat2D::<BoundingBox2D>::new(Vec::new(), 1);
+    assert!(empty.is_empty());
+    assert!(empty.bounding_box_maybe().is_none());
+}
+
+#[test]
+fn dynamic_aa_bb_tree_3d_test() {
+    let data = boxes(1.2);
+    let mut tree = DynamicAABBTree3D::new(NonNegative::new(0.1).unwrap());
+    let ids = data
+        .iter()
+        .cloned()
+        .map(|x| tree.insert(x))
+        .collect::<Vec<_>>();
+
+    assert_eq!(tree.len(), data.len());
+    // balanced via rotations, a degenerated tree would have a height close to the number of elements
+    assert!(tree.height() < 20);
+
+    let brute_force_pairs = |tree: &DynamicAABBTree3D<BoundingBox3D>| {
+        let mut all = Vec::new();
+        tree.for_each(&mut |id, x| all.push((id, x.clone())));
+        let mut result = Vec::new();
+        for i in 0..all.len() {
+            for j in i + 1..all.len() {
+                if all[i].1.collides_with(&all[j].1) {
+                    result.push((all[i].0, all[j].0))
+                }
+            }
+        }
+        result.sort();
+        result
+    };
+
+    let pairs = |tree: &DynamicAABBTree3D<BoundingBox3D>| {
+        let mut result = Vec::new();
+        tree.for_each_overlapping_pair(&mut |a, b| result.push((a.min(b), a.max(b))));
+        result.sort();
+        result
+    };
+
+    assert!(!brute_force_pairs(&tree).is_empty());
+    assert_eq!(pairs(&tree), brute_force_pairs(&tree));
+
+    // small and large movements
+    for (i, id) in ids.iter().enumerate() {
+        let offset = if i % 3 == 0 { 0.05 } else { 1.7 };
+        tree.move_by(*id, offset, -offset, 0.3).unwrap();
+    }
+    assert_eq!(pairs(&tree), brute_force_pairs(&tree));
+
+    for id in ids.iter().step_by(2) {
+        tree.remove(*id).unwrap();
+    }
+    assert_eq!(tree.len(), data.len() / 2);
+    assert!(tree.remove(ids[0]).is_err());
+    assert!(tree.get(ids[0]).is_none());
+    assert_eq!(pairs(&tree), brute_force_pairs(&tree));
+
+    let mut colliders = DynamicAABBTree3D::new(NonNegative::zero());
+    tree.for_each(&mut |_, x| {
+        colliders.insert(Collider3D::AABB(x.clone()));
+    });
+    assert_eq!(
+        colliders.colliding_pairs().len(),
+        brute_force_pairs(&tree).len()
+    );
+
+    let query =
+        BoundingBox3D::new(&Point3D::new(1.0, 2.0, 0.2), &Point3D::new(8.0, 5.0, 1.2)).unwrap();
+    let mut result = Vec::new();
+    tree.bb_colliding(&query, &mut result);
+    let mut expected = 0;
+    tree.for_each(&mut |_, x| {
+        if x.collides_with(&query) {
+            expected += 1
+        }
+    });
+    assert_eq!(result.len(), expected);
+
+    for id in ids.iter().skip(1).step_by(2) {
+        tree.remove(*id).unwrap();
+    }
+    assert!(tree.is_empty());
+    assert!(tree.bounding_box_maybe().is_none());
+}
+
+#[test]
+fn aa_bb_tree_3d_nearest_test() {
+    let data = boxes(0.5);
+    let sqr_dist = |point: &Point3D| {
+        let point = point.clone();
+        move |x: &BoundingBox3D| sqr_dist_3d(&x.center_bb(), &point)
+    };
+
+    for tree in [
+        AABBTree3D::new_sah(data.clone(), 2),
+        AABBTree3D::new(data.clone(), 10, 2),
+    ]
+    .iter()
+    {
+        for point in [
+            Point3D::new(3.3, 4.4, 1.1),
+            Point3D::new(-5.0, 20.0, 3.0),
+            Point3D::new(40.0, 0.0, -1.0),
+        ]
+        .iter()
+        {
+            let (nearest, d) = tree.nearest(point, &sqr_dist(point)).unwrap();
+            let expected = data
+                .iter()
+                .map(|x| sqr_dist(point)(x))
+                .fold(f64::MAX, f64::min);
+            assert_eq!(d, expected);
+            assert_eq!(sqr_dist(point)(nearest), expected);
+        }
+    }
+
+    let empty = AABBTree3D::<BoundingBox3D>::new_sah(Vec::new(), 2);
+    assert!(empty
+        .nearest(&Point3D::default(), &|_: &BoundingBox3D| 0.0)
+        .is_none());
+}
@@ -0,0 +1,409 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+#![deny(warnings)]
+
+mod common;
+
+use rust_3d::*;
+
+type Mesh = AttributedMesh3D<Point3D, PointCloud3D<Point3D>, Vec<usize>>;
+
+// 3 x 3 vertices with a scalar channel equal to x, uvs equal to (x, y) and a label per face
+fn grid() -> Mesh {
+    let mut mesh = Mesh::default();
+    for j in 0..3 {
+        for i in 0..3 {
+            mesh.add_vertex(Point3D::new(i as f64, j as f64, 0.0));
+        }
+    }
+    for j in 0..2 {
+        for i in 0..2 {
+            let v = j * 3 + i;
+            mesh.try_add_connection(VId(v), VId(v + 1), VId(v + 4))
+                .unwrap();
+            mesh.try_add_connection(VId(v), VId(v + 4), VId(v + 3))
+                .unwrap();
+        }
+    }
+
+    let positions: Vec<Point3D> = (0..9).map(|i| mesh.vertex(VId(i)).unwrap()).collect();
+    mesh.add_vertex_channel(
+        "x",
+        AttributeChannel::Scalar(positions.iter().map(|p| p.x).collect()),
+    )
+    .unwrap();
+    mesh.add_vertex_channel(
+        "uv",
+        AttributeChannel::Vector2(positions.iter().map(|p| [p.x, p.y]).collect()),
+    )
+    .unwrap();
+    mesh.add_face_channel("label", AttributeChannel::Label((0..8).collect()))
+        .unwrap();
+    mesh
+}
+
+// copy of a plain mesh with a scalar channel equal to x and the labels offset + i for the faces
+fn attributed(plain: &common::Mesh, offset: usize) -> Mesh {
+    let mut mesh = Mesh::default();
+    for i in 0..plain.num_vertices() {
+        mesh.add_vertex(plain.vertex(VId(i)).unwrap());
+    }
+    for i in 0..plain.num_faces() {
+        let f = plain.face_vertex_ids(FId(i)).unwrap();
+        mesh.try_add_connection(f.a, f.b, f.c).unwrap();
+    }
+    let xs = (0..mesh.num_vertices())
+        .map(|i| mesh.vertex(VId(i)).unwrap().x)
+        .collect();
+    mesh.add_vertex_channel("x", AttributeChannel::Scalar(xs))
+        .unwrap();
+    mesh.add_face_channel(
+        "label",
+        AttributeChannel::Label((offset..offset + plain.num_faces()).collect()),
+    )
+    .unwrap();
+    mesh
+}
+
+// the scalar channel x matches the positions
+fn assert_x_interpolated(mesh: &Mesh) {
+    let xs = scalars(mesh, "x");
+    assert_eq!(xs.len(), mesh.num_vertices());
+    for (i, x) in xs.iter().enumerate() {
+        assert!((x - mesh.vertex(VId(i)).unwrap().x).abs() < 1e-9);
+    }
+}
+
+fn scalars<'a>(mesh: &'a Mesh, name: &str) -> &'a [f64] {
+    match mesh.vertex_channel(name) {
+        Some(AttributeChannel::Scalar(x)) => x,
+        _ => panic!("expected scalar channel"),
+    }
+}
+
+fn labels<'a>(mesh: &'a Mesh, name: &str) -> &'a [usize] {
+    match mesh.face_channel(name) {
+        Some(AttributeChannel::Label(x)) => x,
+        _ => panic!("expected label channel"),
+    }
+}
+
+#[test]
+fn attributed_mesh_3d_channels_test() {
+    let mut mesh = grid();
+    assert_eq!(mesh.vertex_attributes().unwrap().names(), vec!["x", "uv"]);
+    assert_eq!(mesh.face_attributes().unwrap().len(), 8);
+
+    // channels must match the number of elements
+    assert!(mesh
+        .add_vertex_channel("short", AttributeChannel::Scalar(vec![0.0; 3]))
+        .is_err());
+    assert!(mesh
+        .add_face_channel(
+            "color",
+            AttributeChannel::Color(vec![Rgb::new(255, 0, 0); 8])
+        )
+        .is_ok());
+
+    // adding elements keeps the channels in sync
+    let v = mesh.add_vertex(Point3D::new(5.0, 5.0, 0.0));
+    mesh.try_add_connection(VId(8), VId(7), v).unwrap();
+    assert_eq!(scalars(&mesh, "x").len(), 10);
+    assert_eq!(scalars(&mesh, "x")[9], 0.0);
+    assert_eq!(labels(&mesh, "label").len(), 9);
+    assert!(mesh.try_add_connection(VId(8), VId(8), v).is_err());
+    assert_eq!(labels(&mesh, "label").len(), 9);
+
+    assert!(mesh.remove_vertex_channel("x").is_some());
+    assert!(mesh.remove_vertex_channel("x").is_none());
+    assert!(mesh.vertex_channel("x").is_none());
+
+    // plain meshes don't store attributes
+    let plain = Mesh3D::<Point3D, PointCloud3D<Point3D>, Vec<usize>>::default();
+    assert!(plain.vertex_attributes().is_none());
+}
+
+#[test]
+fn attributed_mesh_3d_subdivide_test() {
+    let mi = grid();
+    let mo: Mesh = subdivide::linear(&mi).unwrap();
+    assert_eq!(mo.num_vertices(), 25);
+
+    // x is linear, so interpolating it matches the positions of the new vertices
+    let xs = scalars(&mo, "x");
+    assert_eq!(xs.len(), 25);
+    for (i, x) in xs.iter().enumerate() {
+        assert_eq!(*x, mo.vertex(VId(i)).unwrap().x);
+    }
+    match mo.vertex_channel("uv") {
+        Some(AttributeChannel::Vector2(uvs)) => {
+            for (i, uv) in uvs.iter().enumerate() {
+                let p = mo.vertex(VId(i)).unwrap();
+                assert_eq!(*uv, [p.x, p.y]);
+            }
+        }
+        _ => panic!("expected uv channel"),
+    }
+    // each face is split into four
+    let ls = labels(&mo, "label");
+    assert_eq!(ls.len(), 32);
+    for (i, l) in ls.iter().enumerate() {
+        assert_eq!(*l, i / 4);
+    }
+
+    // searchable meshes pass the attributes on
+    let searchable = SearchableMesh::<_, Point3D, Vec<usize>>::new(mi);
+    let looped: Mesh = subdivide::loop_subdivision(&searchable).unwrap();
+    assert_eq!(scalars(&looped, "x").len(), looped.num_vertices());
+    assert_eq!(labels(&looped, "label").len(), looped.num_faces());
+    // the original vertices keep their values, even though they are moved
+    assert_eq!(&scalars(&looped, "x")[..9], scalars(searchable.mesh(), "x"));
+}
+
+#[test]
+fn attributed_mesh_3d_repair_test() {
+    // two triangles sharing an edge, but with duplicated vertices and a degenerate face
+    let mut mesh = Mesh::default();
+    mesh.add_face(
+        Point3D::new(0.0, 0.0, 0.0),
+        Point3D::new(1.0, 0.0, 0.0),
+        Point3D::new(0.0, 1.0, 0.0),
+    );
+    mesh.add_face(
+        Point3D::new(1.0, 0.0, 0.0),
+        Point3D::new(1.0, 0.0, 0.0),
+        Point3D::new(0.0, 1.0, 0.0),
+    );
+    mesh.add_face(
+        Point3D::new(0.0, 1.0, 0.0),
+        Point3D::new(1.0, 1.0, 0.0),
+        Point3D::new(1.0, 0.0, 0.0),
+    );
+    mesh.add_vertex_channel(
+        "s",
+        AttributeChannel::Scalar((0..9).map(|i| i as f64).collect()),
+    )
+    .unwrap();
+    mesh.add_face_channel("label", AttributeChannel::Label(vec![10, 11, 12]))
+        .unwrap();
+
+    let healed = heal_mesh(&mesh).unwrap();
+    assert_eq!(healed.num_vertices(), 4);
+    assert_eq!(healed.num_faces(), 2);
+    assert_eq!(labels(&healed, "label"), &[10, 12]);
+    // (1, 0, 0) merges the vertices 1, 3, 4 and 8, (0, 1, 0) merges 2, 5 and 6
+    let s = scalars(&healed, "s");
+    assert_eq!(s.len(), 4);
+    let expected = [
+        (Point3D::new(0.0, 0.0, 0.0), 0.0),
+        (Point3D::new(1.0, 0.0, 0.0), 4.0),
+        (Point3D::new(0.0, 1.0, 0.0), 13.0 / 3.0),
+        (Point3D::new(1.0, 1.0, 0.0), 7.0),
+    ];
+    for (i, x) in s.iter().enumerate() {
+        let p = healed.vertex(VId(i)).unwrap();
+        let (_, e) = expected.iter().find(|(q, _)| *q == p).unwrap();
+        assert!((x - e).abs() < 1e-9);
+    }
+
+    // flipping faces keeps their attributes
+    let mut flipped = Mesh::default();
+    for p in [
+        Point3D::new(0.0, 0.0, 0.0),
+        Point3D::new(1.0, 0.0, 0.0),
+        Point3D::new(0.0, 1.0, 0.0),
+        Point3D::new(1.0, 1.0, 0.0),
+    ]
+    .iter()
+    {
+        flipped.add_vertex(p.clone());
+    }
+    flipped.try_add_connection(VId(0), VId(1), VId(2)).unwrap();
+    flipped.try_add_connection(VId(1), VId(2), VId(3)).unwrap();
+    flipped
+        .add_face_channel("label", AttributeChannel::Label(vec![3, 4]))
+        .unwrap();
+    let unified = unify_faces(&flipped).unwrap();
+    assert_eq!(labels(&unified, "label"), &[3, 4]);
+}
+
+#[test]
+fn attributed_mesh_3d_split_non_manifold_test() {
+    // two triangles only sharing the vertex 0
+    let mut mesh = Mesh::default();
+    for p in [
+        Point3D::new(0.0, 0.0, 0.0),
+        Point3D::new(1.0, 0.0, 0.0),
+        Point3D::new(1.0, 1.0, 0.0),
+        Point3D::new(-1.0, 0.0, 0.0),
+        Point3D::new(-1.0, -1.0, 0.0),
+    ]
+    .iter()
+    {
+        mesh.add_vertex(p.clone());
+    }
+    mesh.try_add_connection(VId(0), VId(1), VId(2)).unwrap();
+    mesh.try_add_connection(VId(0), VId(3), VId(4)).unwrap();
+    mesh.add_vertex_channel(
+        "x",
+        AttributeChannel::Scalar(vec![0.0, 1.0, 1.0, -1.0, -1.0]),
+    )
+    .unwrap();
+    mesh.add_face_channel("label", AttributeChannel::Label(vec![5, 6]))
+        .unwrap();
+
+    let split = split_non_manifold_vertices(&mesh).unwrap();
+    assert_eq!(split.num_vertices(), 6);
+    assert_x_interpolated(&split);
+    assert_eq!(labels(&split, "label"), &[5, 6]);
+
+    let oriented = orient_faces_consistently(&split).unwrap();
+    assert_x_interpolated(&oriented);
+    assert_eq!(labels(&oriented, "label"), &[5, 6]);
+}
+
+#[test]
+fn attributed_mesh_3d_components_test() {
+    let mut plain = common::cube([0.0, 0.0, 0.0], 1.0, 1);
+    let other = common::cube([2.0, 0.0, 0.0], 1.0, 2);
+    let n = plain.num_vertices();
+    for i in 0..other.num_vertices() {
+        plain.add_vertex(other.vertex(VId(i)).unwrap());
+    }
+    for i in 0..other.num_faces() {
+        let f = other.face_vertex_ids(FId(i)).unwrap();
+        plain
+            .try_add_connection(VId(f.a.0 + n), VId(f.b.0 + n), VId(f.c.0 + n))
+            .unwrap();
+    }
+    let mesh = attributed(&plain, 0);
+
+    let components: Vec<Mesh> = split_components(&mesh).unwrap();
+    assert_eq!(components.len(), 2);
+    for component in components.iter() {
+        assert_x_interpolated(component);
+    }
+    assert_eq!(
+        labels(&components[0], "label"),
+        &(0..12).collect::<Vec<_>>()[..]
+    );
+    assert_eq!(
+        labels(&components[1], "label"),
+        &(12..60).collect::<Vec<_>>()[..]
+    );
+
+    let largest: Mesh = keep_largest_component(&mesh).unwrap();
+    assert_x_interpolated(&largest);
+    assert_eq!(labels(&largest, "label"), labels(&components[1], "label"));
+}
+
+#[test]
+fn attributed_mesh_3d_fill_holes_test() {
+    let mesh = attributed(&common::grid(4), 0);
+    let searchable = SearchableMesh::<_, Point3D, Vec<usize>>::new(mesh);
+    let filled: Mesh = fill_holes(&searchable, 12, false).unwrap();
+    assert!(filled.num_faces() > 18);
+
+    // the grid is planar, so the new vertices interpolate x exactly
+    assert_x_interpolated(&filled);
+    let ls = labels(&filled, "label");
+    assert_eq!(ls.len(), filled.num_faces());
+    assert_eq!(&ls[..18], &(0..18).collect::<Vec<_>>()[..]);
+    assert!(ls[18..].iter().all(|l| *l == 0));
+}
+
+#[test]
+fn attributed_mesh_3d_decimate_test() {
+    let mesh = attributed(&common::grid(5), 0);
+    let decimated = decimate_mesh(&mesh, 8, None, false).unwrap();
+    assert!(decimated.num_faces() <= 8);
+
+    let xs = scalars(&decimated, "x");
+    assert_eq!(xs.len(), decimated.num_vertices());
+    assert!(xs.iter().all(|x| *x >= 0.0 && *x <= 4.0));
+    let ls = labels(&decimated, "label");
+    assert_eq!(ls.len(), decimated.num_faces());
+    assert!(ls.iter().all(|l| *l < 32));
+}
+
+#[test]
+fn attributed_mesh_3d_remesh_test() {
+    let mesh = attributed(&common::grid(4), 0);
+    let remeshed: Mesh =
+        remesh_isotropic(&mesh, Positive::new(0.5).unwrap(), Rad(0.5), true, 2).unwrap();
+    // all vertices are resampled, so no channels are carried over
+    assert!(remeshed.vertex_channel("x").is_none());
+    assert!(remeshed.face_channel("label").is_none());
+}
+
+#[test]
+fn attributed_mesh_3d_clip_test() {
+    let mesh = attributed(&common::cube([0.0, 0.0, 0.0], 1.0, 2), 0);
+    let plane = Plane3D::new(
+        Point3D::new(0.3, 0.0, 0.0),
+        Norm3D::norm_y(),
+        Norm3D::norm_z(),
+    );
+    let (front, back): (Mesh, Mesh) = cut_mesh(&mesh, &plane, true).unwrap();
+    for part in [front, back].iter() {
+        // x is linear, so the edge points interpolate it exactly
+        assert_x_interpolated(part);
+        let ls = labels(part, "label");
+        assert_eq!(ls.len(), part.num_faces());
+        assert!(ls.iter().all(|l| *l < 48));
+    }
+}
+
+#[test]
+fn attributed_mesh_3d_offset_test() {
+    let mesh = attributed(&common::grid(3), 0);
+    let offset: Mesh = offset_mesh(&mesh, 0.5).unwrap();
+    // the grid is moved along z only
+    assert_x_interpolated(&offset);
+    assert_eq!(labels(&offset, "label"), labels(&mesh, "label"));
+
+    let shell: Mesh = shell_mesh(&mesh, Positive::new(0.5).unwrap()).unwrap();
+    assert_x_interpolated(&shell);
+    let ls = labels(&shell, "label");
+    assert_eq!(ls.len(), shell.num_faces());
+    // each face is followed by its copy on the inner side, then the walls follow
+    for f in 0..8 {
+        assert_eq!(ls[2 * f], f);
+        assert_eq!(ls[2 * f + 1], f);
+    }
+    assert!(ls[16..].iter().all(|l| *l == 0));
+}
+
+#[test]
+fn attributed_mesh_3d_boolean_test() {
+    let a = attributed(&common::cube([0.0, 0.0, 0.0], 1.0, 1), 0);
+    let b = attributed(&common::rotated_cube([0.5, 0.5, 0.5], 1.0, 1, 0.3), 100);
+    let union: Mesh = mesh_boolean(&a, &b, BooleanOperation::Union).unwrap();
+
+    // x is linear, so the intersection points interpolate it exactly
+    assert_x_interpolated(&union);
+    let ls = labels(&union, "label");
+    assert_eq!(ls.len(), union.num_faces());
+    assert!(ls.iter().any(|l| *l < 12));
+    assert!(ls.iter().any(|l| *l >= 100));
+    assert!(ls.iter().all(|l| *l < 12 || (*l >= 100 && *l < 112)));
+}
@@ -0,0 +1,127 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+
+use rust_3d::*;
+
+mod common;
+use common::*;
+
+#[test]
+fn clip_mesh_plane_test() {
+    let mesh = cube([0.0, 0.0, 0.0], 2.0, 2);
+
+    let open: Mesh = clip_mesh(&mesh, &plane_z(1.3), false).unwrap();
+    let diagnostics = diagnose_mesh(&open).unwrap();
+    assert!(diagnostics.is_valid_for_half_edge());
+    assert_eq!(diagnostics.boundary_edges.len(), 16);
+    for i in 0..open.num_vertices() {
+        assert!(open.vertex(VId(i)).unwrap().z <= 1.3 + 1e-9);
+    }
+
+    let capped: Mesh = clip_mesh(&mesh, &plane_z(1.3), true).unwrap();
+    assert_closed(&capped);
+    assert!((capped.signed_volume().unwrap() - 5.2).abs() < 1e-9);
+
+    // vertices on the plane
+    let capped: Mesh = clip_mesh(&mesh, &plane_z(1.0), true).unwrap();
+    assert_closed(&capped);
+    assert!((capped.signed_volume().unwrap() - 4.0).abs() < 1e-9);
+
+    let all: Mesh = clip_mesh(&mesh, &plane_z(5.0), true).unwrap();
+    assert_eq!(all.num_faces(), mesh.num_faces());
+    let none: Mesh = clip_mesh(&mesh, &plane_z(-1.0), true).unwrap();
+    assert_eq!(none.num_faces(), 0);
+}
+
+#[test]
+fn cut_mesh_test() {
+    let mesh = cube([0.0, 0.0, 0.0], 2.0, 3);
+    let plane = Plane3D::new(
+        Point3D::new(1.0, 1.0, 1.0),
+        Norm3D::new(Point3D::new(1.0, 0.0, 0.4)).unwrap(),
+        Norm3D::new(Point3D::new(0.2, 1.0, 0.0)).unwrap(),
+    );
+
+    let (below, above): (Mesh, Mesh) = cut_mesh(&mesh, &plane, true).unwrap();
+    assert_closed(&below);
+    assert_closed(&above);
+    assert!(below.signed_volume().unwrap() > 1.0 && above.signed_volume().unwrap() > 1.0);
+    assert!((below.signed_volume().unwrap() + above.signed_volume().unwrap() - 8.0).abs() < 1e-9);
+}
+
+#[test]
+fn cut_mesh_hole_test() {
+    let outer = cube([0.0, 0.0, 0.0], 3.0, 1);
+    let inner = cube([1.0, 1.0, 1.0], 1.0, 1);
+    let hollow: Mesh = mesh_boolean(&outer, &inner, BooleanOperation::Difference).unwrap();
+
+    let (below, above): (Mesh, Mesh) = cut_mesh(&hollow, &plane_z(1.5), true).unwrap();
+    assert_closed(&below);
+    assert_closed(&above);
+    assert!((below.signed_volume().unwrap() - 13.0).abs() < 1e-9);
+    assert!((above.signed_volume().unwrap() - 13.0).abs() < 1e-9);
+}
+
+#[test]
+fn clip_mesh_box_test() {
+    let mesh = cube([0.0, 0.0, 0.0], 2.0, 2);
+
+    let bb =
+        BoundingBox3D::new(&Point3D::new(1.5, 0.5, -1.0), &Point3D::new(3.0, 1.2, 1.1)).unwrap();
+    let clipped: Mesh = clip_mesh_box(&mesh, &bb, true).unwrap();
+    assert_closed(&clipped);
+    assert!((clipped.signed_volume().unwrap() - 0.5 * 0.7 * 1.1).abs() < 1e-9);
+
+    // a rotated box entirely within the mesh
+    let box_3d = BoxUnaligned3D::new_from_z_rotation(
+        &Point3D::new(1.0, 1.0, 1.0),
+        [
+            Positive::new(1.0).unwrap(),
+            Positive::new(0.8).unwrap(),
+            Positive::new(0.5).unwrap(),
+        ],
+        0.3,
+    );
+    let clipped: Mesh = clip_mesh_box_unaligned(&mesh, &box_3d, true).unwrap();
+    assert_closed(&clipped);
+    assert!((clipped.signed_volume().unwrap() - 0.4).abs() < 1e-9);
+
+    let clipped: Mesh = clip_mesh_box_unaligned(&mesh, &box_3d, false).unwrap();
+    assert_eq!(clipped.num_faces(), 0);
+}
+
+#[test]
+fn clip_mesh_open_test() {
+    let mut mesh = Mesh::default();
+    mesh.add_face(
+        Point3D::new(0.0, 0.0, 0.0),
+        Point3D::new(1.0, 0.0, 1.0),
+        Point3D::new(0.0, 1.0, 1.0),
+    );
+
+    let clipped: Mesh = clip_mesh(&mesh, &plane_z(0.5), false).unwrap();
+    assert_eq!(clipped.num_faces(), 1);
+    let result: Result<Mesh> = clip_mesh(&mesh, &plane_z(0.5), true);
+    assert!(result.is_err());
+}
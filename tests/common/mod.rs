@@ -0,0 +1,159 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//! Fixtures shared by the mesh tests
+
+#![allow(dead_code)]
+
+use rust_3d::*;
+
+use std::f64::consts::PI;
+
+pub type Mesh = Mesh3D<Point3D, PointCloud3D<Point3D>, Vec<usize>>;
+
+// box with each side split into n * n quads
+pub fn cube(min: [f64; 3], size: f64, n: usize) -> Mesh {
+    rotated_cube(min, size, n, 0.0)
+}
+
+// box with each side split into n * n quads, rotated around z
+pub fn rotated_cube(min: [f64; 3], size: f64, n: usize, angle: f64) -> Mesh {
+    let mut mesh = Mesh::default();
+    let (sin, cos) = angle.sin_cos();
+    let add = |mesh: &mut Mesh, p: [f64; 3]| {
+        let (x, y, z) = (
+            min[0] + p[0] * size,
+            min[1] + p[1] * size,
+            min[2] + p[2] * size,
+        );
+        mesh.add_vertex(Point3D::new(cos * x - sin * y, sin * x + cos * y, z))
+    };
+
+    // each side given by its origin and two directions spanning it (counter clockwise seen from outside)
+    let sides = [
+        ([0.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 0.0, 0.0]),
+        ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+        ([0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+        ([0.0, 1.0, 0.0], [0.0, 0.0, 1.0], [1.0, 0.0, 0.0]),
+        ([0.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]),
+        ([1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]),
+    ];
+    let mut ids = std::collections::HashMap::new();
+    for (origin, u, v) in sides.iter() {
+        let mut id = |mesh: &mut Mesh, i: usize, j: usize| {
+            let (s, t) = (i as f64 / n as f64, j as f64 / n as f64);
+            let p = [
+                origin[0] + s * u[0] + t * v[0],
+                origin[1] + s * u[1] + t * v[1],
+                origin[2] + s * u[2] + t * v[2],
+            ];
+            let key = (
+                (p[0] * n as f64).round() as i64,
+                (p[1] * n as f64).round() as i64,
+                (p[2] * n as f64).round() as i64,
+            );
+            *ids.entry(key).or_insert_with(|| add(mesh, p))
+        };
+        for i in 0..n {
+            for j in 0..n {
+                let a = id(&mut mesh, i, j);
+                let b = id(&mut mesh, i + 1, j);
+                let c = id(&mut mesh, i + 1, j + 1);
+                let d = id(&mut mesh, i, j + 1);
+                mesh.try_add_connection(a, b, c).unwrap();
+                mesh.try_add_connection(a, c, d).unwrap();
+            }
+        }
+    }
+    mesh
+}
+
+// unit uv sphere with the poles as vertices 0 and 1, followed by the rings from north to south
+// the faces around the north pole can be left out to create a hole
+pub fn sphere(n_rings: usize, n_segments: usize, with_cap: bool) -> Mesh {
+    let mut mesh = Mesh::default();
+    let north = mesh.add_vertex(Point3D::new(0.0, 0.0, 1.0));
+    let south = mesh.add_vertex(Point3D::new(0.0, 0.0, -1.0));
+    for r in 1..n_rings {
+        let theta = PI * r as f64 / n_rings as f64;
+        for s in 0..n_segments {
+            let phi = 2.0 * PI * s as f64 / n_segments as f64;
+            mesh.add_vertex(Point3D::new(
+                theta.sin() * phi.cos(),
+                theta.sin() * phi.sin(),
+                theta.cos(),
+            ));
+        }
+    }
+    let ring = |r: usize, s: usize| VId(2 + (r - 1) * n_segments + s % n_segments);
+    for s in 0..n_segments {
+        if with_cap {
+            mesh.try_add_connection(north, ring(1, s), ring(1, s + 1))
+                .unwrap();
+        }
+        mesh.try_add_connection(south, ring(n_rings - 1, s + 1), ring(n_rings - 1, s))
+            .unwrap();
+    }
+    for r in 1..n_rings - 1 {
+        for s in 0..n_segments {
+            mesh.try_add_connection(ring(r, s), ring(r + 1, s), ring(r + 1, s + 1))
+                .unwrap();
+            mesh.try_add_connection(ring(r, s), ring(r + 1, s + 1), ring(r, s + 1))
+                .unwrap();
+        }
+    }
+    mesh
+}
+
+pub fn plane_z(z: f64) -> Plane3D<Point3D, Norm3D> {
+    Plane3D::new(
+        Point3D::new(0.0, 0.0, z),
+        Norm3D::norm_x(),
+        Norm3D::norm_y(),
+    )
+}
+
+pub fn assert_closed(mesh: &Mesh) {
+    let diagnostics = diagnose_mesh(mesh).unwrap();
+    assert!(diagnostics.is_valid_for_half_edge());
+    assert!(diagnostics.is_closed());
+}
+
+// n x n vertices on the x/y plane
+pub fn grid(n: usize) -> Mesh {
+    let mut mesh = Mesh::default();
+    for j in 0..n {
+        for i in 0..n {
+            mesh.add_vertex(Point3D::new(i as f64, j as f64, 0.0));
+        }
+    }
+    for j in 0..n - 1 {
+        for i in 0..n - 1 {
+            let v = j * n + i;
+            mesh.try_add_connection(VId(v), VId(v + 1), VId(v + n + 1))
+                .unwrap();
+            mesh.try_add_connection(VId(v), VId(v + n + 1), VId(v + n))
+                .unwrap();
+        }
+    }
+    mesh
+}
@@ -0,0 +1,176 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+
+use rust_3d::*;
+
+mod common;
+use common::*;
+
+use std::f64::consts::PI;
+
+// sphere of the given radius, optionally with flipped faces
+fn scaled_sphere(
+    n_rings: usize,
+    n_segments: usize,
+    radius: f64,
+    flipped: bool,
+) -> SearchableMesh<Mesh, Point3D, Vec<usize>> {
+    let unit = sphere(n_rings, n_segments, true);
+    let mut mesh = Mesh::default();
+    for i in 0..unit.num_vertices() {
+        let p = unit.vertex(VId(i)).unwrap();
+        mesh.add_vertex(Point3D::new(radius * p.x, radius * p.y, radius * p.z));
+    }
+    for i in 0..unit.num_faces() {
+        let f = unit.face_vertex_ids(FId(i)).unwrap();
+        if flipped {
+            mesh.try_add_connection(f.a, f.c, f.b).unwrap();
+        } else {
+            mesh.try_add_connection(f.a, f.b, f.c).unwrap();
+        }
+    }
+    SearchableMesh::new(mesh)
+}
+
+// open cylinder of the given radius, made of rings of vertices along z
+fn cylinder(
+    n_rings: usize,
+    n_segments: usize,
+    radius: f64,
+) -> SearchableMesh<Mesh, Point3D, Vec<usize>> {
+    let mut mesh = Mesh::default();
+    for r in 0..n_rings {
+        for s in 0..n_segments {
+            let phi = 2.0 * PI * s as f64 / n_segments as f64;
+            mesh.add_vertex(Point3D::new(
+                radius * phi.cos(),
+                radius * phi.sin(),
+                -0.1 * r as f64,
+            ));
+        }
+    }
+    let ring = |r: usize, s: usize| VId(r * n_segments + s % n_segments);
+    for r in 0..n_rings - 1 {
+        for s in 0..n_segments {
+            mesh.try_add_connection(ring(r, s), ring(r + 1, s), ring(r + 1, s + 1))
+                .unwrap();
+            mesh.try_add_connection(ring(r, s), ring(r + 1, s + 1), ring(r, s + 1))
+                .unwrap();
+        }
+    }
+    SearchableMesh::new(mesh)
+}
+
+fn assert_near(value: f64, expected: f64, eps: f64) {
+    assert!((value - expected).abs() < eps, "{} != {}", value, expected);
+}
+
+#[test]
+fn curvature_sphere_test() {
+    let n_segments = 60;
+    let mesh = scaled_sphere(30, n_segments, 2.0, false);
+    let curvatures = vertex_curvatures(&mesh).unwrap();
+    assert_eq!(curvatures.len(), mesh.num_vertices());
+
+    // vertices around the equator
+    for c in curvatures[2 + 14 * n_segments..2 + 15 * n_segments].iter() {
+        assert_near(c.mean, 0.5, 0.02);
+        assert_near(c.gaussian, 0.25, 0.02);
+        assert_near(c.k1, 0.5, 0.05);
+        assert_near(c.k2, 0.5, 0.05);
+        assert_near(c.shape_index, 1.0, 0.1);
+    }
+
+    // convex everywhere
+    for c in curvatures.iter() {
+        assert!(c.gaussian > 0.0);
+        assert!(c.mean > 0.0);
+    }
+
+    let inverted = scaled_sphere(30, n_segments, 2.0, true);
+    let curvatures = vertex_curvatures(&inverted).unwrap();
+    for c in curvatures[2 + 14 * n_segments..2 + 15 * n_segments].iter() {
+        assert_near(c.mean, -0.5, 0.02);
+        assert_near(c.gaussian, 0.25, 0.02);
+        assert_near(c.shape_index, -1.0, 0.1);
+    }
+}
+
+#[test]
+fn curvature_cylinder_test() {
+    let n_segments = 60;
+    let mesh = cylinder(10, n_segments, 1.0);
+    let curvatures = vertex_curvatures(&mesh).unwrap();
+
+    for c in curvatures[5 * n_segments..6 * n_segments].iter() {
+        assert_near(c.mean, 0.5, 0.01);
+        assert_near(c.gaussian, 0.0, 0.01);
+        assert_near(c.k1, 1.0, 0.1);
+        assert_near(c.k2, 0.0, 0.1);
+        assert_near(c.shape_index, 0.5, 0.05);
+
+        // minimum curvature along the axis
+        let dir2 = c.dir2.as_ref().unwrap();
+        assert_near(dir2.z().abs(), 1.0, 1e-3);
+        let dir1 = c.dir1.as_ref().unwrap();
+        assert_near(dir1.z(), 0.0, 1e-3);
+    }
+
+    let colors = curvatures
+        .iter()
+        .map(|c| Rgb::heat_map(c.mean, -1.0, 1.0))
+        .collect::<Vec<_>>();
+    assert_eq!(colors.len(), mesh.num_vertices());
+}
+
+#[test]
+fn rgb_heat_map_test() {
+    assert_eq!(Rgb::heat_map(0.0, 0.0, 1.0), Rgb::new(0, 0, 255));
+    assert_eq!(Rgb::heat_map(0.5, 0.0, 1.0), Rgb::new(0, 255, 0));
+    assert_eq!(Rgb::heat_map(1.0, 0.0, 1.0), Rgb::new(255, 0, 0));
+    assert_eq!(Rgb::heat_map(7.0, 0.0, 1.0), Rgb::new(255, 0, 0));
+    assert_eq!(Rgb::heat_map(-7.0, 0.0, 1.0), Rgb::new(0, 0, 255));
+    assert_eq!(Rgb::heat_map(f64::NAN, 0.0, 1.0), Rgb::new(0, 255, 0));
+    assert_eq!(Rgb::heat_map(3.0, 1.0, 1.0), Rgb::new(0, 255, 0));
+}
+
+#[test]
+fn curvature_unused_vertex_test() {
+    let mut mesh = Mesh::default();
+    let a = mesh.add_vertex(Point3D::new(0.0, 0.0, 0.0));
+    let b = mesh.add_vertex(Point3D::new(1.0, 0.0, 0.0));
+    let c = mesh.add_vertex(Point3D::new(1.0, 1.0, 0.0));
+    let d = mesh.add_vertex(Point3D::new(0.0, 1.0, 0.0));
+    mesh.add_vertex(Point3D::new(5.0, 5.0, 5.0));
+    mesh.try_add_connection(a, b, c).unwrap();
+    mesh.try_add_connection(a, c, d).unwrap();
+    let mesh = SearchableMesh::<_, _, Vec<usize>>::new(mesh);
+
+    let curvatures = vertex_curvatures(&mesh).unwrap();
+    assert_eq!(curvatures.len(), 5);
+    let unused = &curvatures[4];
+    assert_eq!(unused.mean, 0.0);
+    assert_eq!(unused.gaussian, 0.0);
+    assert!(unused.dir1.is_none() && unused.dir2.is_none());
+}
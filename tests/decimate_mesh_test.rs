@@ -0,0 +1,111 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+
+use rust_3d::*;
+
+mod common;
+use common::*;
+
+fn norm(p: &Point3D) -> f64 {
+    (p.x() * p.x() + p.y() * p.y() + p.z() * p.z()).sqrt()
+}
+
+fn on_grid_boundary(p: &Point3D, n: usize) -> bool {
+    let max = (n - 1) as f64;
+    p.x() == 0.0 || p.y() == 0.0 || p.x() == max || p.y() == max
+}
+
+#[test]
+fn decimate_mesh_sphere_test() {
+    let mesh = sphere(20, 40, true);
+    assert_eq!(mesh.num_faces(), 2 * 40 * 19);
+
+    let result: Mesh = decimate_mesh(&mesh, 200, None, true).unwrap();
+    assert!(result.num_faces() <= 200);
+    assert!(result.num_faces() > 150);
+
+    for i in 0..result.num_vertices() {
+        let v = result.vertex(VId(i)).unwrap();
+        assert!((norm(&v) - 1.0).abs() < 0.1);
+    }
+
+    // all normals still point outwards
+    for i in 0..result.num_faces() {
+        let [a, b, c] = result.face_vertices(FId(i)).unwrap();
+        let n = normal_of_face(&a, &b, &c);
+        let center = center_3d(&center_3d(&a, &b), &c);
+        assert!(n.x() * center.x() + n.y() * center.y() + n.z() * center.z() > 0.0);
+    }
+
+    // a strict error limit stops early
+    let limited: Mesh = decimate_mesh(&mesh, 200, Some(NonNegative::zero()), true).unwrap();
+    assert!(limited.num_faces() > 200);
+
+    // the target can't be reached by an unlimited amount of collapses, but the mesh must stay closed
+    let tiny: Mesh = decimate_mesh(&mesh, 0, None, false).unwrap();
+    assert!(tiny.num_faces() >= 4);
+    assert!(tiny.num_faces() < 20);
+}
+
+#[test]
+fn decimate_mesh_boundary_test() {
+    let n = 11;
+    let mesh = grid(n);
+
+    // flat, so collapses are free
+    let result: Mesh =
+        decimate_mesh(&mesh, 0, Some(NonNegative::new(1e-9).unwrap()), true).unwrap();
+    assert!(result.num_faces() < mesh.num_faces() / 2);
+
+    // all boundary vertices are kept
+    let n_boundary = (0..result.num_vertices())
+        .filter(|i| on_grid_boundary(&result.vertex(VId(*i)).unwrap(), n))
+        .count();
+    assert_eq!(n_boundary, 4 * (n - 1));
+
+    for i in 0..result.num_vertices() {
+        let v = result.vertex(VId(i)).unwrap();
+        assert!(v.z().abs() < 1e-9);
+    }
+
+    // area and orientation unchanged
+    let mut area = 0.0;
+    for i in 0..result.num_faces() {
+        let [a, b, c] = result.face_vertices(FId(i)).unwrap();
+        let n = cross(&conn(&a, &b), &conn(&a, &c));
+        assert!(n.z() > 0.0);
+        area += 0.5 * n.z();
+    }
+    assert!((area - ((n - 1) * (n - 1)) as f64).abs() < 1e-6);
+
+    // without preservation the boundary may move, but only along itself
+    let result: Mesh = decimate_mesh(&mesh, 20, None, false).unwrap();
+    assert!(result.num_faces() <= 20);
+    for i in 0..result.num_vertices() {
+        let v = result.vertex(VId(i)).unwrap();
+        assert!(v.z().abs() < 1e-9);
+        assert!(v.x() > -0.01 && v.y() > -0.01);
+        assert!(v.x() < (n - 1) as f64 + 0.01 && v.y() < (n - 1) as f64 + 0.01);
+    }
+}
@@ -0,0 +1,93 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+
+use rust_3d::*;
+
+mod common;
+use common::*;
+
+use std::f64::consts::PI;
+
+fn boundary_loops(mesh: Mesh) -> Vec<Vec<VId>> {
+    let mut result = Vec::new();
+    SearchableMesh::<_, _, Vec<usize>>::new(mesh)
+        .boundary_loops(&mut result)
+        .unwrap();
+    result
+}
+
+fn assert_outwards(mesh: &Mesh) {
+    for i in 0..mesh.num_faces() {
+        let [a, b, c] = mesh.face_vertices(FId(i)).unwrap();
+        let n = normal_of_face(&a, &b, &c);
+        let center = center_3d(&center_3d(&a, &b), &c);
+        assert!(n.x() * center.x() + n.y() * center.y() + n.z() * center.z() > 0.0);
+    }
+}
+
+#[test]
+fn boundary_loops_test() {
+    assert!(boundary_loops(sphere(6, 8, true)).is_empty());
+
+    let loops = boundary_loops(sphere(6, 8, false));
+    assert_eq!(loops.len(), 1);
+    assert_eq!(loops[0].len(), 8);
+
+    // ordered along the boundary edges, which run opposite to the missing faces
+    let mut expected = (0..8).map(|s| VId(2 + (8 - s) % 8)).collect::<Vec<_>>();
+    let start = expected.iter().position(|x| *x == loops[0][0]).unwrap();
+    expected.rotate_left(start);
+    assert_eq!(loops[0], expected);
+}
+
+#[test]
+fn fill_holes_test() {
+    let n_segments = 16;
+    let mesh = sphere(8, n_segments, false);
+    let searchable = SearchableMesh::<_, _, Vec<usize>>::new(mesh.clone());
+
+    // hole too large
+    let unchanged: Mesh = fill_holes(&searchable, n_segments - 1, false).unwrap();
+    assert_eq!(unchanged.num_faces(), mesh.num_faces());
+    assert_eq!(boundary_loops(unchanged).len(), 1);
+
+    let filled: Mesh = fill_holes(&searchable, n_segments, false).unwrap();
+    assert_eq!(filled.num_vertices(), mesh.num_vertices());
+    assert_eq!(filled.num_faces(), mesh.num_faces() + n_segments - 2);
+    assert_outwards(&filled);
+    assert!(boundary_loops(filled).is_empty());
+
+    let faired: Mesh = fill_holes(&searchable, n_segments, true).unwrap();
+    assert!(faired.num_vertices() > mesh.num_vertices());
+    assert!(faired.num_faces() > mesh.num_faces() + n_segments - 2);
+    assert_outwards(&faired);
+
+    let ring_z = (PI / 8.0).cos();
+    for i in mesh.num_vertices()..faired.num_vertices() {
+        let v = faired.vertex(VId(i)).unwrap();
+        assert!((v.z() - ring_z).abs() < 1e-6);
+        assert!(v.x() * v.x() + v.y() * v.y() < 1.0);
+    }
+    assert!(boundary_loops(faired).is_empty());
+}
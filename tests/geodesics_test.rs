@@ -0,0 +1,197 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+#![deny(warnings)]
+
+use rust_3d::*;
+
+type Mesh = Mesh3D<Point3D, PointCloud3D<Point3D>, Vec<usize>>;
+type Searchable = SearchableMesh<Mesh, Point3D, Vec<usize>>;
+
+// n x n vertices with spacing 1, starting at offset
+fn grid(n: usize, offset: [f64; 2]) -> Mesh {
+    let mut mesh = Mesh::default();
+    add_grid(&mut mesh, n, offset);
+    mesh
+}
+
+fn add_grid(mesh: &mut Mesh, n: usize, offset: [f64; 2]) {
+    let start = mesh.num_vertices();
+    for j in 0..n {
+        for i in 0..n {
+            mesh.add_vertex(Point3D::new(
+                offset[0] + i as f64,
+                offset[1] + j as f64,
+                0.0,
+            ));
+        }
+    }
+    for j in 0..n - 1 {
+        for i in 0..n - 1 {
+            let v = start + j * n + i;
+            mesh.try_add_connection(VId(v), VId(v + 1), VId(v + n + 1))
+                .unwrap();
+            mesh.try_add_connection(VId(v), VId(v + n + 1), VId(v + n))
+                .unwrap();
+        }
+    }
+}
+
+fn path_length(path: &PointCloud3D<Point3D>) -> f64 {
+    path.data.windows(2).map(|w| dist_3d(&w[0], &w[1])).sum()
+}
+
+#[test]
+fn dijkstra_test() {
+    let n = 11;
+    let mesh = Searchable::new(grid(n, [0.0, 0.0]));
+
+    let distances = dijkstra_distances(&mesh, &[VId(0)]).unwrap();
+    assert_eq!(distances.len(), n * n);
+    let sqrt2 = 2.0f64.sqrt();
+    for j in 0..n {
+        for i in 0..n {
+            // diagonal steps first, then straight ones
+            let (a, b) = (i.min(j) as f64, (i as f64 - j as f64).abs());
+            assert!((distances[j * n + i] - (a * sqrt2 + b)).abs() < 1e-9);
+        }
+    }
+
+    let path = dijkstra_path(&mesh, VId(0), VId(n * n - 1)).unwrap();
+    assert_eq!(path.len(), n);
+    assert_eq!(path.data[0], Point3D::new(0.0, 0.0, 0.0));
+    assert_eq!(path.data[n - 1], Point3D::new(10.0, 10.0, 0.0));
+    assert!((path_length(&path) - distances[n * n - 1]).abs() < 1e-9);
+
+    let single = dijkstra_path(&mesh, VId(5), VId(5)).unwrap();
+    assert_eq!(single.len(), 1);
+
+    // multiple sources, each vertex measures to the closest one
+    let both = dijkstra_distances(&mesh, &[VId(0), VId(n - 1)]).unwrap();
+    let other = dijkstra_distances(&mesh, &[VId(n - 1)]).unwrap();
+    for (i, d) in both.iter().enumerate() {
+        assert!((d - distances[i].min(other[i])).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn heat_geodesics_test() {
+    let n = 21;
+    let center = 10 * n + 10;
+    let mesh = Searchable::new(grid(n, [0.0, 0.0]));
+
+    let distances = heat_geodesic_distances(&mesh, &[VId(center)]).unwrap();
+    assert_eq!(distances.len(), n * n);
+    assert_eq!(distances[center], 0.0);
+
+    // on a plane the geodesic distance is the euclidean one, the heat method approximates it within a few percent
+    let c = mesh.vertex(VId(center)).unwrap();
+    for (i, d) in distances.iter().enumerate() {
+        let expected = dist_3d(&c, &mesh.vertex(VId(i)).unwrap());
+        if expected > 0.0 && expected <= 8.0 {
+            assert!((d - expected).abs() < 0.1 * expected + 0.2);
+        }
+    }
+
+    // the heat method doesn't suffer from the metrication of edge paths
+    let dijkstra = dijkstra_distances(&mesh, &[VId(center)]).unwrap();
+    let v = 10 * n + 18; // (18, 10)
+    let w = 14 * n + 18; // (18, 14)
+    assert!((distances[v] - 8.0).abs() < 0.5);
+    assert!((distances[w] - 80.0f64.sqrt()).abs() < (dijkstra[w] - 80.0f64.sqrt()).abs());
+
+    let path = geodesic_path(&mesh, &distances, VId(w)).unwrap();
+    assert_eq!(path.data[0], Point3D::new(18.0, 14.0, 0.0));
+    assert_eq!(path.data[path.len() - 1], Point3D::new(10.0, 10.0, 0.0));
+    assert!(path_length(&path) < 1.1 * dijkstra[w]);
+
+    // multiple sources
+    let both = heat_geodesic_distances(&mesh, &[VId(0), VId(n * n - 1)]).unwrap();
+    assert_eq!(both[0], 0.0);
+    assert_eq!(both[n * n - 1], 0.0);
+    assert!((both[center] - 200.0f64.sqrt()).abs() < 1.0);
+}
+
+#[test]
+fn geodesics_disconnected_test() {
+    let mut mesh = grid(5, [0.0, 0.0]);
+    add_grid(&mut mesh, 5, [10.0, 0.0]);
+    let mesh = Searchable::new(mesh);
+
+    let dijkstra = dijkstra_distances(&mesh, &[VId(0)]).unwrap();
+    let heat = heat_geodesic_distances(&mesh, &[VId(0)]).unwrap();
+    for i in 0..25 {
+        assert!(dijkstra[i].is_finite());
+        assert!(heat[i].is_finite());
+        assert!(dijkstra[25 + i].is_infinite());
+        assert!(heat[25 + i].is_infinite());
+    }
+
+    match dijkstra_path(&mesh, VId(0), VId(30)) {
+        Err(ErrorKind::NoPath) => {}
+        _ => panic!("expected NoPath"),
+    }
+    match geodesic_path(&mesh, &heat, VId(30)) {
+        Err(ErrorKind::NoPath) => {}
+        _ => panic!("expected NoPath"),
+    }
+
+    // each component measures to its own source
+    let heat = heat_geodesic_distances(&mesh, &[VId(0), VId(25)]).unwrap();
+    assert_eq!(heat[25], 0.0);
+    assert!((heat[24] - heat[49]).abs() < 1e-6);
+}
+
+#[test]
+fn geodesics_invalid_test() {
+    let mesh = Searchable::new(grid(3, [0.0, 0.0]));
+    assert!(dijkstra_distances(&mesh, &[VId(9)]).is_err());
+    assert!(heat_geodesic_distances(&mesh, &[VId(9)]).is_err());
+    assert!(dijkstra_path(&mesh, VId(0), VId(9)).is_err());
+    assert!(geodesic_path(&mesh, &[0.0; 4], VId(0)).is_err());
+    assert!(geodesic_path(&mesh, &[0.0; 9], VId(9)).is_err());
+}
+
+#[test]
+fn geodesics_unused_vertex_test() {
+    let mut mesh = grid(4, [0.0, 0.0]);
+    let unused = mesh.add_vertex(Point3D::new(10.0, 10.0, 0.0));
+    let mesh = Searchable::new(mesh);
+
+    let dijkstra = dijkstra_distances(&mesh, &[VId(0)]).unwrap();
+    let heat = heat_geodesic_distances(&mesh, &[VId(0)]).unwrap();
+    assert_eq!(dijkstra.len(), 17);
+    assert!(dijkstra[unused.0].is_infinite());
+    assert!(heat[unused.0].is_infinite());
+    assert!((dijkstra[15] - 3.0 * 2.0f64.sqrt()).abs() < 1e-9);
+    assert!(heat[..16].iter().all(|d| d.is_finite()));
+
+    assert!(dijkstra_path(&mesh, VId(0), VId(15)).is_ok());
+    assert!(geodesic_path(&mesh, &heat, VId(15)).is_ok());
+    match dijkstra_path(&mesh, VId(0), unused) {
+        Err(ErrorKind::NoPath) => {}
+        _ => panic!("expected NoPath"),
+    }
+    match geodesic_path(&mesh, &heat, unused) {
+        Err(ErrorKind::NoPath) => {}
+        _ => panic!("expected NoPath"),
+    }
+}
@@ -0,0 +1,288 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+
+use rust_3d::*;
+
+mod common;
+use common::*;
+
+type HEMesh = HalfEdgeMesh3D<Point3D>;
+
+fn tetrahedron() -> Mesh {
+    let mut mesh = Mesh::default();
+    mesh.add_vertex(Point3D::new(0.0, 0.0, 0.0));
+    mesh.add_vertex(Point3D::new(1.0, 0.0, 0.0));
+    mesh.add_vertex(Point3D::new(0.0, 1.0, 0.0));
+    mesh.add_vertex(Point3D::new(0.0, 0.0, 1.0));
+    for [a, b, c] in [[0, 2, 1], [0, 1, 3], [1, 2, 3], [0, 3, 2]].iter() {
+        mesh.try_add_connection(VId(*a), VId(*b), VId(*c)).unwrap();
+    }
+    mesh
+}
+
+// the connectivity must match the one of a freshly built SearchableMesh
+fn assert_consistent(mesh: &HEMesh) {
+    for i in 0..mesh.num_edges() {
+        let e = EId(i);
+        let next = mesh.edge_next(e).unwrap();
+        let prev = mesh.edge_prev(e).unwrap();
+        assert_eq!(mesh.edge_prev(next), Some(e));
+        assert_eq!(mesh.edge_next(prev), Some(e));
+        assert_eq!(mesh.edge_head(e), mesh.edge_tail(next));
+        assert_eq!(mesh.edge_face(e), mesh.edge_face(next));
+        if let Some(twin) = mesh.edge_twin(e) {
+            assert_eq!(mesh.edge_twin(twin), Some(e));
+            assert_eq!(mesh.edge_tail(twin), mesh.edge_head(e));
+        }
+    }
+
+    let mut copy = Mesh::default();
+    for i in 0..mesh.num_vertices() {
+        copy.add_vertex(mesh.vertex(VId(i)).unwrap());
+    }
+    for i in 0..mesh.num_faces() {
+        let f = mesh.face_vertex_ids(FId(i)).unwrap();
+        copy.try_add_connection(f.a, f.b, f.c).unwrap();
+    }
+    assert!(diagnose_mesh(&copy).unwrap().is_valid_for_half_edge());
+
+    let searchable = SearchableMesh::<_, _, Vec<usize>>::new(copy);
+    for i in 0..mesh.num_edges() {
+        assert_eq!(mesh.edge_twin(EId(i)), searchable.edge_twin(EId(i)));
+    }
+    let mut cache = Vec::new();
+    for i in 0..mesh.num_vertices() {
+        let mut expected = Vec::new();
+        searchable
+            .edges_of_vertex(VId(i), &mut cache, &mut expected)
+            .unwrap();
+        let mut edges = Vec::new();
+        mesh.edges_of_vertex(VId(i), &mut cache, &mut edges)
+            .unwrap();
+        expected.sort();
+        edges.sort();
+        assert_eq!(edges, expected);
+        assert_eq!(
+            mesh.is_vertex_on_boundary(VId(i), &mut cache).unwrap(),
+            searchable
+                .is_vertex_on_boundary(VId(i), &mut cache)
+                .unwrap()
+        );
+    }
+}
+
+#[test]
+fn half_edge_mesh_3d_new_test() {
+    let mesh = HEMesh::new(&grid(4)).unwrap();
+    assert_eq!(mesh.num_vertices(), 16);
+    assert_eq!(mesh.num_faces(), 18);
+    assert_eq!(mesh.num_edges(), 54);
+    assert_consistent(&mesh);
+
+    let mut loops = Vec::new();
+    mesh.boundary_loops(&mut loops).unwrap();
+    assert_eq!(loops.len(), 1);
+    assert_eq!(loops[0].len(), 12);
+
+    // the same oriented edge within two faces
+    let mut invalid = grid(2);
+    let v = invalid.add_vertex(Point3D::new(0.5, -1.0, 0.0));
+    invalid.try_add_connection(VId(0), VId(1), v).unwrap();
+    assert!(HEMesh::new(&invalid).is_err());
+
+    let mut mesh = HEMesh::default();
+    let f = mesh.add_face(
+        Point3D::new(0.0, 0.0, 0.0),
+        Point3D::new(1.0, 0.0, 0.0),
+        Point3D::new(0.0, 1.0, 0.0),
+    );
+    assert_eq!(f, FId(0));
+    assert_eq!(mesh.num_vertices(), 3);
+    assert!(mesh.try_add_connection(VId(0), VId(1), VId(2)).is_err());
+    assert!(mesh.try_add_connection(VId(0), VId(0), VId(2)).is_err());
+    assert!(mesh.try_add_connection(VId(0), VId(1), VId(3)).is_err());
+    let d = mesh.add_vertex(Point3D::new(1.0, 1.0, 0.0));
+    mesh.try_add_connection(VId(2), VId(1), d).unwrap();
+    assert_consistent(&mesh);
+    assert!(mesh.edge_between(VId(1), VId(2)).is_some());
+    assert!(mesh.edge_between(VId(2), VId(1)).is_some());
+}
+
+#[test]
+fn half_edge_mesh_3d_bowtie_test() {
+    // faces only sharing a vertex can't be added
+    let mut mesh = HEMesh::default();
+    mesh.add_face(
+        Point3D::new(0.0, 0.0, 0.0),
+        Point3D::new(1.0, 0.0, 0.0),
+        Point3D::new(0.0, 1.0, 0.0),
+    );
+    let d = mesh.add_vertex(Point3D::new(-1.0, 0.0, 0.0));
+    let e = mesh.add_vertex(Point3D::new(0.0, -1.0, 0.0));
+    assert!(mesh.try_add_connection(VId(0), d, e).is_err());
+    assert_eq!(mesh.num_faces(), 1);
+    // attaching along a boundary edge of the vertex is fine
+    mesh.try_add_connection(VId(0), VId(2), d).unwrap();
+    mesh.try_add_connection(VId(0), d, e).unwrap();
+    assert_consistent(&mesh);
+
+    // neither at inner vertices
+    let mut mesh = HEMesh::new(&grid(3)).unwrap();
+    let d = mesh.add_vertex(Point3D::new(1.0, 1.0, 1.0));
+    let e = mesh.add_vertex(Point3D::new(2.0, 1.0, 1.0));
+    assert!(mesh.try_add_connection(VId(4), d, e).is_err());
+
+    // a fan around 0, where the faces are only connected once all are added
+    let mut fan = Mesh::default();
+    for p in [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [-1.0, 0.0], [0.0, -1.0]].iter() {
+        fan.add_vertex(Point3D::new(p[0], p[1], 0.0));
+    }
+    for [b, c] in [[1, 2], [3, 4], [2, 3], [4, 1]].iter() {
+        fan.try_add_connection(VId(0), VId(*b), VId(*c)).unwrap();
+    }
+    let mesh = HEMesh::new(&fan).unwrap();
+    assert_consistent(&mesh);
+
+    // but meshes with bowties are rejected
+    let mut bowtie = Mesh::default();
+    for p in [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [-1.0, 0.0], [0.0, -1.0]].iter() {
+        bowtie.add_vertex(Point3D::new(p[0], p[1], 0.0));
+    }
+    bowtie.try_add_connection(VId(0), VId(1), VId(2)).unwrap();
+    bowtie.try_add_connection(VId(0), VId(3), VId(4)).unwrap();
+    assert!(HEMesh::new(&bowtie).is_err());
+}
+
+#[test]
+fn half_edge_mesh_3d_flip_test() {
+    let mut mesh = HEMesh::new(&grid(2)).unwrap();
+    let diagonal = mesh.edge_between(VId(0), VId(3)).unwrap();
+    mesh.flip_edge(diagonal).unwrap();
+    assert_consistent(&mesh);
+    assert!(mesh.edge_between(VId(0), VId(3)).is_none());
+    assert!(mesh.edge_between(VId(1), VId(2)).is_some());
+    assert!(mesh.edge_between(VId(2), VId(1)).is_some());
+
+    // boundary edges can't be flipped
+    let boundary = mesh.edge_between(VId(0), VId(1)).unwrap();
+    assert!(mesh.flip_edge(boundary).is_err());
+    assert!(mesh.flip_edge(EId(100)).is_err());
+
+    // the flipped edge would already exist
+    let mut mesh = HEMesh::new(&tetrahedron()).unwrap();
+    assert!(mesh.flip_edge(EId(0)).is_err());
+    assert_consistent(&mesh);
+}
+
+#[test]
+fn half_edge_mesh_3d_split_test() {
+    let mut mesh = HEMesh::new(&grid(3)).unwrap();
+    let center = mesh.edge_between(VId(4), VId(5)).unwrap();
+    let v = mesh
+        .split_edge(center, Point3D::new(1.5, 1.0, 0.0))
+        .unwrap();
+    assert_eq!(v, VId(9));
+    assert_eq!(mesh.num_faces(), 10);
+    assert_consistent(&mesh);
+    assert!(mesh.edge_between(VId(4), VId(5)).is_none());
+    assert!(mesh.edge_between(VId(4), v).is_some());
+    assert!(mesh.edge_between(v, VId(5)).is_some());
+
+    let boundary = mesh.edge_between(VId(0), VId(1)).unwrap();
+    mesh.split_edge(boundary, Point3D::new(0.5, 0.0, 0.0))
+        .unwrap();
+    assert_eq!(mesh.num_faces(), 11);
+    assert_consistent(&mesh);
+
+    let v = mesh
+        .split_face(FId(3), Point3D::new(1.6, 0.3, 0.0))
+        .unwrap();
+    assert_eq!(mesh.num_faces(), 13);
+    let mut neighbours = Vec::new();
+    mesh.neighbours(v, &mut neighbours).unwrap();
+    assert_eq!(neighbours.len(), 3);
+    assert_consistent(&mesh);
+
+    assert!(mesh.split_face(FId(13), Point3D::default()).is_err());
+}
+
+#[test]
+fn half_edge_mesh_3d_collapse_test() {
+    let mut mesh = HEMesh::new(&grid(4)).unwrap();
+    let e = mesh.edge_between(VId(5), VId(6)).unwrap();
+    assert!(mesh.is_collapse_ok(e));
+    let v = mesh.collapse_edge(e, Point3D::new(1.5, 1.0, 0.0)).unwrap();
+    assert_eq!(mesh.num_vertices(), 15);
+    assert_eq!(mesh.num_faces(), 16);
+    // the last vertex took the place of the removed one
+    assert_eq!(v, VId(5));
+    assert_eq!(mesh.vertex(VId(6)).unwrap(), Point3D::new(3.0, 3.0, 0.0));
+    assert_eq!(mesh.vertex(v).unwrap(), Point3D::new(1.5, 1.0, 0.0));
+    assert_consistent(&mesh);
+
+    // an interior edge connecting two boundary vertices would pinch the mesh
+    let mut mesh = HEMesh::new(&grid(2)).unwrap();
+    let diagonal = mesh.edge_between(VId(0), VId(3)).unwrap();
+    assert!(!mesh.is_collapse_ok(diagonal));
+    assert!(mesh.collapse_edge(diagonal, Point3D::default()).is_err());
+    assert_consistent(&mesh);
+
+    // would fold the tetrahedron flat
+    let mut mesh = HEMesh::new(&tetrahedron()).unwrap();
+    assert!(!mesh.is_collapse_ok(EId(0)));
+    assert!(mesh.collapse_edge(EId(0), Point3D::default()).is_err());
+    assert_eq!(mesh.num_faces(), 4);
+}
+
+#[test]
+fn half_edge_mesh_3d_remove_test() {
+    let mut mesh = HEMesh::new(&grid(5)).unwrap();
+    mesh.remove_vertex(VId(12)).unwrap();
+    assert_eq!(mesh.num_vertices(), 24);
+    assert_eq!(mesh.num_faces(), 26);
+    assert_consistent(&mesh);
+
+    let mut loops = Vec::new();
+    mesh.boundary_loops(&mut loops).unwrap();
+    assert_eq!(loops.len(), 2);
+
+    let corner = mesh.edge_between(VId(3), VId(4)).unwrap();
+    mesh.remove_face(mesh.edge_face(corner).unwrap()).unwrap();
+    assert_eq!(mesh.num_faces(), 25);
+    assert_consistent(&mesh);
+
+    assert!(mesh.remove_face(FId(25)).is_err());
+    assert!(mesh.remove_vertex(VId(24)).is_err());
+
+    let mut mesh = HEMesh::new(&grid(2)).unwrap();
+    mesh.change_vertex(VId(3), Point3D::new(2.0, 2.0, 1.0))
+        .unwrap();
+    let bb = mesh.bounding_box_maybe().unwrap();
+    assert_eq!(bb.max_p(), Point3D::new(2.0, 2.0, 1.0));
+    for i in (0..4).rev() {
+        mesh.remove_vertex(VId(i)).unwrap();
+    }
+    assert_eq!(mesh.num_faces(), 0);
+    assert!(mesh.bounding_box_maybe().is_none());
+}
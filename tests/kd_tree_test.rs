@@ -0,0 +1,90 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+
+use rust_3d::*;
+
+fn points() -> PointCloud3D<Point3D> {
+    let mut pc = PointCloud3D::new();
+    for i in 0..20 {
+        for j in 0..20 {
+            for k in 0..6 {
+                let x = (i * i) as f64 * 0.05 + 0.013 * k as f64;
+                let y = j as f64 * 0.4 + 0.007 * i as f64;
+                let z = k as f64 * 0.9 + 0.011 * j as f64;
+                pc.push(Point3D::new(x, y, z));
+            }
+        }
+    }
+    pc
+}
+
+fn sorted_sqr_dists(search: &Point3D, ps: &[Point3D]) -> Vec<f64> {
+    let mut result = ps
+        .iter()
+        .map(|p| sqr_dist_3d(search, p))
+        .collect::<Vec<_>>();
+    result.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    result
+}
+
+#[test]
+fn kd_tree_knearest_approx_test() {
+    let pc = points();
+    let mut tree = KdTree::default();
+    tree.build(pc.clone()).unwrap();
+
+    let searches = [
+        Point3D::new(0.0, 0.0, 0.0),
+        Point3D::new(3.3, 2.1, 1.7),
+        Point3D::new(-5.0, 12.0, 3.0),
+        Point3D::new(19.0, 4.0, 20.0),
+    ];
+
+    for search in searches.iter() {
+        let exact = sorted_sqr_dists(search, &pc.data);
+
+        for n in [1, 5, 25].iter() {
+            // epsilon of 0 and no limit yields the exact result
+            let mut result = Vec::new();
+            tree.knearest_approx(search, *n, NonNegative::zero(), None, &mut result);
+            assert_eq!(sorted_sqr_dists(search, &result), exact[0..*n].to_vec());
+
+            let epsilon = NonNegative::new(0.5).unwrap();
+            let mut result = Vec::new();
+            tree.knearest_approx(search, *n, epsilon, None, &mut result);
+            assert_eq!(result.len(), *n);
+            for (i, d) in sorted_sqr_dists(search, &result).iter().enumerate() {
+                assert!(d.sqrt() <= 1.5 * exact[i].sqrt() + 1e-9);
+            }
+
+            // a limited number of checks still yields results
+            let mut result = Vec::new();
+            tree.knearest_approx(search, *n, epsilon, Some(*n), &mut result);
+            assert_eq!(result.len(), *n);
+        }
+
+        let nearest: Option<Point3D> = tree.nearest_approx(search, NonNegative::zero(), None);
+        assert_eq!(sqr_dist_3d(search, &nearest.unwrap()), exact[0]);
+    }
+}
@@ -0,0 +1,185 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+
+use rust_3d::*;
+
+mod common;
+use common::*;
+
+fn boolean(a: &Mesh, b: &Mesh, operation: BooleanOperation) -> Mesh {
+    let result = mesh_boolean(a, b, operation).unwrap();
+    assert_closed(&result);
+    result
+}
+
+#[test]
+fn mesh_boolean_cubes_test() {
+    let a = cube([0.0, 0.0, 0.0], 2.0, 1);
+    let b = cube([1.0, 0.7, 0.6], 2.0, 1);
+    assert!((a.signed_volume().unwrap() - 8.0).abs() < 1e-9);
+
+    let intersection = 1.0 * 1.3 * 1.4;
+    let eps = 1e-9;
+    assert!(
+        (boolean(&a, &b, BooleanOperation::Union)
+            .signed_volume()
+            .unwrap()
+            - (16.0 - intersection))
+            .abs()
+            < eps
+    );
+    assert!(
+        (boolean(&a, &b, BooleanOperation::Intersection)
+            .signed_volume()
+            .unwrap()
+            - intersection)
+            .abs()
+            < eps
+    );
+    assert!(
+        (boolean(&a, &b, BooleanOperation::Difference)
+            .signed_volume()
+            .unwrap()
+            - (8.0 - intersection))
+            .abs()
+            < eps
+    );
+    assert!(
+        (boolean(&b, &a, BooleanOperation::Difference)
+            .signed_volume()
+            .unwrap()
+            - (8.0 - intersection))
+            .abs()
+            < eps
+    );
+
+    // all vertices of the intersection are within both cubes
+    let result = boolean(&a, &b, BooleanOperation::Intersection);
+    for i in 0..result.num_vertices() {
+        let p = result.vertex(VId(i)).unwrap();
+        assert!(p.x > 1.0 - eps && p.x < 2.0 + eps);
+        assert!(p.y > 0.7 - eps && p.y < 2.0 + eps);
+        assert!(p.z > 0.6 - eps && p.z < 2.0 + eps);
+    }
+}
+
+#[test]
+fn mesh_boolean_tessellated_test() {
+    // many faces of a intersect each face of b, b is rotated
+    let a = cube([0.0, 0.0, 0.0], 2.0, 4);
+    let b = rotated_cube([0.9, 0.3, 0.7], 1.5, 2, 0.4);
+    let (va, vb) = (a.signed_volume().unwrap(), b.signed_volume().unwrap());
+
+    let union = boolean(&a, &b, BooleanOperation::Union)
+        .signed_volume()
+        .unwrap();
+    let intersection = boolean(&a, &b, BooleanOperation::Intersection)
+        .signed_volume()
+        .unwrap();
+    let difference = boolean(&a, &b, BooleanOperation::Difference)
+        .signed_volume()
+        .unwrap();
+
+    assert!(intersection > 0.1 && intersection < vb);
+    assert!((union - (va + vb - intersection)).abs() < 1e-9);
+    assert!((difference - (va - intersection)).abs() < 1e-9);
+}
+
+#[test]
+fn mesh_boolean_degenerate_test() {
+    // coplanar faces are resolved by a tiny offset
+    let a = cube([0.0, 0.0, 0.0], 2.0, 1);
+    let b = cube([1.0, 0.0, 0.0], 2.0, 1);
+    let eps = 1e-5;
+    assert!(
+        (boolean(&a, &b, BooleanOperation::Union)
+            .signed_volume()
+            .unwrap()
+            - 12.0)
+            .abs()
+            < eps
+    );
+    assert!(
+        (boolean(&a, &b, BooleanOperation::Intersection)
+            .signed_volume()
+            .unwrap()
+            - 4.0)
+            .abs()
+            < eps
+    );
+    assert!(
+        (boolean(&a, &b, BooleanOperation::Difference)
+            .signed_volume()
+            .unwrap()
+            - 4.0)
+            .abs()
+            < eps
+    );
+
+    // identical meshes
+    assert!(
+        (boolean(&a, &a, BooleanOperation::Union)
+            .signed_volume()
+            .unwrap()
+            - 8.0)
+            .abs()
+            < eps
+    );
+}
+
+#[test]
+fn mesh_boolean_disjoint_test() {
+    let a = cube([0.0, 0.0, 0.0], 2.0, 1);
+    let inner = cube([0.5, 0.5, 0.5], 1.0, 1);
+    let far = cube([5.0, 0.0, 0.0], 1.0, 1);
+
+    let union = boolean(&a, &far, BooleanOperation::Union);
+    assert_eq!(union.num_faces(), 24);
+    assert!((union.signed_volume().unwrap() - 9.0).abs() < 1e-9);
+
+    let intersection: Mesh = mesh_boolean(&a, &far, BooleanOperation::Intersection).unwrap();
+    assert_eq!(intersection.num_faces(), 0);
+
+    // a cavity within a
+    let hollow = boolean(&a, &inner, BooleanOperation::Difference);
+    assert_eq!(hollow.num_faces(), 24);
+    assert_eq!(diagnose_mesh(&hollow).unwrap().n_components, 2);
+    assert!((hollow.signed_volume().unwrap() - 7.0).abs() < 1e-9);
+
+    let intersection = boolean(&a, &inner, BooleanOperation::Intersection);
+    assert!((intersection.signed_volume().unwrap() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn mesh_boolean_invalid_test() {
+    let a = cube([0.0, 0.0, 0.0], 2.0, 1);
+    let mut open = Mesh::default();
+    open.add_face(
+        Point3D::new(0.0, 0.0, 0.0),
+        Point3D::new(1.0, 0.0, 0.0),
+        Point3D::new(0.0, 1.0, 0.0),
+    );
+    let result: Result<Mesh> = mesh_boolean(&a, &open, BooleanOperation::Union);
+    assert!(result.is_err());
+}
@@ -0,0 +1,140 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+
+use rust_3d::*;
+
+type Mesh = Mesh3D<Point3D, PointCloud3D<Point3D>, Vec<usize>>;
+
+fn add_octahedron(mesh: &mut Mesh, offset: f64) {
+    let mut ids = Vec::new();
+    for p in [
+        [1.0, 0.0, 0.0],
+        [-1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, -1.0, 0.0],
+        [0.0, 0.0, 1.0],
+        [0.0, 0.0, -1.0],
+    ]
+    .iter()
+    {
+        ids.push(mesh.add_vertex(Point3D::new(p[0] + offset, p[1], p[2])));
+    }
+    for [a, b, c] in [
+        [0, 2, 4],
+        [2, 1, 4],
+        [1, 3, 4],
+        [3, 0, 4],
+        [2, 0, 5],
+        [1, 2, 5],
+        [3, 1, 5],
+        [0, 3, 5],
+    ]
+    .iter()
+    {
+        mesh.try_add_connection(ids[*a], ids[*b], ids[*c]).unwrap();
+    }
+}
+
+// triangle, octahedron, unused vertex, octahedron
+fn shells() -> Mesh {
+    let mut mesh = Mesh::default();
+    let a = mesh.add_vertex(Point3D::new(0.0, 5.0, 0.0));
+    let b = mesh.add_vertex(Point3D::new(1.0, 5.0, 0.0));
+    let c = mesh.add_vertex(Point3D::new(0.0, 6.0, 0.0));
+    mesh.try_add_connection(a, b, c).unwrap();
+    add_octahedron(&mut mesh, 0.0);
+    mesh.add_vertex(Point3D::new(0.0, -5.0, 0.0));
+    add_octahedron(&mut mesh, 10.0);
+    mesh
+}
+
+#[test]
+fn mesh_components_labels_test() {
+    let mesh = shells();
+
+    let (labels, n) = face_components(&mesh).unwrap();
+    assert_eq!(n, 3);
+    assert_eq!(labels[0], 0);
+    assert!(labels[1..9].iter().all(|x| *x == 1));
+    assert!(labels[9..17].iter().all(|x| *x == 2));
+
+    let (labels, n) = vertex_components(&mesh).unwrap();
+    assert_eq!(n, 3);
+    assert_eq!(labels[0], Some(0));
+    assert_eq!(labels[3], Some(1));
+    assert_eq!(labels[9], None);
+    assert_eq!(labels[10], Some(2));
+}
+
+#[test]
+fn mesh_components_split_test() {
+    let mesh = shells();
+
+    let parts: Vec<Mesh> = split_components(&mesh).unwrap();
+    assert_eq!(parts.len(), 3);
+    assert_eq!(parts[0].num_faces(), 1);
+    assert_eq!(parts[0].num_vertices(), 3);
+    assert_eq!(parts[1].num_faces(), 8);
+    assert_eq!(parts[1].num_vertices(), 6);
+    assert_eq!(parts[2].num_faces(), 8);
+    assert_eq!(parts[2].num_vertices(), 6);
+    assert_eq!(
+        parts[2].vertex(VId(0)).unwrap(),
+        Point3D::new(11.0, 0.0, 0.0)
+    );
+    assert!(diagnose_mesh(&parts[2]).unwrap().is_closed());
+
+    let largest: Mesh = keep_largest_component(&mesh).unwrap();
+    assert_eq!(largest.num_faces(), 8);
+    assert_eq!(largest.vertex(VId(0)).unwrap(), Point3D::new(1.0, 0.0, 0.0));
+
+    let filtered: Mesh = remove_small_components(&mesh, 2).unwrap();
+    assert_eq!(filtered.num_faces(), 16);
+    assert_eq!(filtered.num_vertices(), 12);
+    assert_eq!(diagnose_mesh(&filtered).unwrap().n_components, 2);
+
+    let empty: Mesh = remove_small_components(&mesh, 9).unwrap();
+    assert_eq!(empty.num_faces(), 0);
+}
+
+#[test]
+fn mesh_components_non_manifold_test() {
+    // three triangles sharing a single edge and one separate triangle
+    let mut mesh = Mesh::default();
+    let a = mesh.add_vertex(Point3D::new(0.0, 0.0, 0.0));
+    let b = mesh.add_vertex(Point3D::new(1.0, 0.0, 0.0));
+    for p in [[0.5, 1.0, 0.0], [0.5, -1.0, 0.0], [0.5, 0.0, 1.0]].iter() {
+        let c = mesh.add_vertex(Point3D::new(p[0], p[1], p[2]));
+        mesh.try_add_connection(a, b, c).unwrap();
+    }
+    let d = mesh.add_vertex(Point3D::new(5.0, 0.0, 0.0));
+    let e = mesh.add_vertex(Point3D::new(6.0, 0.0, 0.0));
+    let f = mesh.add_vertex(Point3D::new(5.0, 1.0, 0.0));
+    mesh.try_add_connection(d, e, f).unwrap();
+
+    let (labels, n) = face_components(&mesh).unwrap();
+    assert_eq!(n, 2);
+    assert_eq!(labels, vec![0, 0, 0, 1]);
+    assert_eq!(diagnose_mesh(&mesh).unwrap().n_components, n);
+}
@@ -0,0 +1,129 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+
+use rust_3d::*;
+
+mod common;
+use common::*;
+
+// deterministic pseudo random points within [-1, 3]^3
+fn points(n: usize) -> Vec<Point3D> {
+    let mut state = 12345u64;
+    let mut next = || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 11) as f64 / (1u64 << 53) as f64 * 4.0 - 1.0
+    };
+    (0..n)
+        .map(|_| Point3D::new(next(), next(), next()))
+        .collect()
+}
+
+#[test]
+fn closest_point_on_triangle_test() {
+    let a = Point3D::new(0.0, 0.0, 0.0);
+    let b = Point3D::new(2.0, 0.0, 0.0);
+    let c = Point3D::new(0.0, 2.0, 0.0);
+
+    let check = |p: Point3D, expected: Point3D| {
+        let closest = closest_point_on_triangle_3d(&p, &a, &b, &c);
+        assert!(dist_3d(&closest, &expected) < 1e-12);
+        assert!((dist_point_triangle_3d(&p, &a, &b, &c) - dist_3d(&p, &expected)).abs() < 1e-12);
+    };
+
+    // face, corners and edges
+    check(Point3D::new(0.5, 0.5, 3.0), Point3D::new(0.5, 0.5, 0.0));
+    check(Point3D::new(-1.0, -1.0, 1.0), a.clone());
+    check(Point3D::new(3.0, -0.5, 0.0), b.clone());
+    check(Point3D::new(-0.1, 5.0, -2.0), c.clone());
+    check(Point3D::new(1.0, -1.0, 0.0), Point3D::new(1.0, 0.0, 0.0));
+    check(Point3D::new(-1.0, 1.5, 1.0), Point3D::new(0.0, 1.5, 0.0));
+    check(Point3D::new(2.0, 2.0, 0.0), Point3D::new(1.0, 1.0, 0.0));
+
+    // degenerate triangles
+    let d = Point3D::new(1.0, 0.0, 0.0);
+    let closest = closest_point_on_triangle_3d(&Point3D::new(1.5, 1.0, 0.0), &a, &d, &b);
+    assert!(dist_3d(&closest, &Point3D::new(1.5, 0.0, 0.0)) < 1e-12);
+
+    let face = TriFace3D::new(
+        Point3D::new(0.0, 0.0, 0.0),
+        Point3D::new(2.0, 0.0, 1.0),
+        Point3D::new(0.0, 2.0, 1.0),
+    )
+    .unwrap();
+    let p = Point3D::new(-1.0, -1.0, 0.0);
+    assert!((*face.distance(&p) - 2.0f64.sqrt()).abs() < 1e-12);
+    assert!(dist_3d(&face.closest_point(&p), face.a()) < 1e-12);
+}
+
+#[test]
+fn cloud_to_mesh_distance_test() {
+    let mesh = cube([0.0, 0.0, 0.0], 2.0, 3);
+    let cloud = PointCloud3D { data: points(200) };
+
+    let report = cloud_to_mesh_distance(&cloud, &mesh).unwrap();
+    assert_eq!(report.distances.len(), 200);
+    for (p, d) in cloud.data.iter().zip(report.distances.iter()) {
+        let expected = (0..mesh.num_faces())
+            .map(|i| {
+                let [a, b, c] = mesh.face_vertices(FId(i)).unwrap();
+                dist_point_triangle_3d(p, &a, &b, &c)
+            })
+            .fold(f64::MAX, f64::min);
+        assert!((d - expected).abs() < 1e-12);
+    }
+    assert!(report.min <= report.mean && report.mean <= report.rms && report.rms <= report.max);
+
+    let query = MeshDistanceQuery::new(&mesh).unwrap();
+    let (_, closest) = query.closest_point(&Point3D::new(3.0, 3.0, 3.0)).unwrap();
+    assert!(dist_3d(&closest, &Point3D::new(2.0, 2.0, 2.0)) < 1e-12);
+    assert!((query.distance(&Point3D::new(1.0, 0.3, 1.2)).unwrap() - 0.3).abs() < 1e-12);
+
+    let empty = PointCloud3D::<Point3D>::new();
+    assert!(cloud_to_mesh_distance(&empty, &mesh).is_err());
+    assert!(cloud_to_mesh_distance(&cloud, &Mesh::default()).is_err());
+}
+
+#[test]
+fn mesh_to_mesh_distance_test() {
+    let a = cube([0.0, 0.0, 0.0], 2.0, 2);
+    let b = cube([0.0, 0.0, 0.1], 2.0, 1);
+
+    let report = mesh_to_mesh_distance(&a, &a, 3).unwrap();
+    assert!(report.hausdorff < 1e-12);
+
+    let report = mesh_to_mesh_distance(&a, &b, 1).unwrap();
+    assert!((report.hausdorff - 0.1).abs() < 1e-12);
+    assert_eq!(report.a_to_b.distances.len(), a.num_vertices());
+    assert_eq!(report.b_to_a.distances.len(), b.num_vertices());
+
+    // samples on the edges and faces
+    let report = mesh_to_mesh_distance(&a, &b, 4).unwrap();
+    assert!((report.hausdorff - 0.1).abs() < 1e-12);
+    assert_eq!(report.b_to_a.distances.len(), 8 + 18 * 3 + 12 * 3);
+    assert!(report.mean > 0.0 && report.mean <= report.rms && report.rms <= report.hausdorff);
+
+    assert!(mesh_to_mesh_distance(&a, &Mesh::default(), 1).is_err());
+}
@@ -52,3 +52,171 @@ fn mesh_io_test() {
     assert!(m.num_faces() == 1152);
     assert!(m.num_vertices() == 576);
 }
+
+#[test]
+fn mesh_io_normals_test() {
+    let mut m = Mesh3D::<Point3D, PointCloud3D<Point3D>, Vec<usize>>::default();
+    load_ply_mesh(
+        &mut BufReader::new(File::open("tests/data/torus_only_vertex_data.ply").unwrap()),
+        &mut m,
+    )
+    .unwrap();
+    let nm = NormalMesh3D::with_normals(m, &NormalWeighting::Angle).unwrap();
+
+    let mut buffer = Vec::new();
+    save_ply_ascii_with_normals(&mut buffer, &nm, nm.normals()).unwrap();
+    assert!(String::from_utf8_lossy(&buffer).contains("property float nx"));
+    let mut loaded = Mesh3D::<Point3D, PointCloud3D<Point3D>, Vec<usize>>::default();
+    load_ply_mesh(&mut buffer.as_slice(), &mut loaded).unwrap();
+    assert_eq!(loaded.num_faces(), 1152);
+    assert_eq!(loaded.num_vertices(), 576);
+
+    for precision in [Precision::P32, Precision::P64].iter() {
+        let mut buffer = Vec::new();
+        save_ply_binary_with_normals(&mut buffer, &nm, precision, nm.normals()).unwrap();
+        let mut loaded = Mesh3D::<Point3D, PointCloud3D<Point3D>, Vec<usize>>::default();
+        load_ply_mesh(&mut buffer.as_slice(), &mut loaded).unwrap();
+        assert_eq!(loaded.num_faces(), 1152);
+        assert_eq!(
+            loaded.vertex(VId(7)),
+            nm.vertex(VId(7)).map(|x| {
+                match precision {
+                    Precision::P32 => Point3D::new(
+                        x.x() as f32 as f64,
+                        x.y() as f32 as f64,
+                        x.z() as f32 as f64,
+                    ),
+                    Precision::P64 => x,
+                }
+            })
+        );
+    }
+
+    let mut buffer = Vec::new();
+    save_obj_with_normals(&mut buffer, &nm, nm.normals()).unwrap();
+    assert!(String::from_utf8_lossy(&buffer).contains("vn "));
+    let mut loaded = Mesh3D::<Point3D, PointCloud3D<Point3D>, Vec<usize>>::default();
+    load_obj_mesh(&mut buffer.as_slice(), &mut loaded).unwrap();
+    assert_eq!(loaded.num_faces(), 1152);
+    assert_eq!(loaded.num_vertices(), 576);
+    assert_eq!(loaded.face_vertex_ids(FId(5)), nm.face_vertex_ids(FId(5)));
+
+    let mut buffer = Vec::new();
+    save_obj(&mut buffer, &nm).unwrap();
+    let mut loaded = Mesh3D::<Point3D, PointCloud3D<Point3D>, Vec<usize>>::default();
+    load_obj_mesh(&mut buffer.as_slice(), &mut loaded).unwrap();
+    assert_eq!(loaded.num_faces(), 1152);
+
+    assert!(save_obj_with_normals(&mut Vec::new(), &nm, &nm.normals()[1..]).is_err());
+    assert!(save_ply_ascii_with_normals(&mut Vec::new(), &nm, &[]).is_err());
+
+    // stl only stores face normals
+    let mut buffer = Vec::new();
+    save_stl_ascii(&mut buffer, &nm).unwrap();
+    assert!(String::from_utf8_lossy(&buffer).contains("facet normal"));
+}
+
+#[test]
+fn mesh_io_poly_test() {
+    type Poly = PolyMesh3D<Point3D, PointCloud3D<Point3D>, Vec<usize>>;
+
+    // a cube of quads, with an n-gon and texture / normal indices
+    let obj = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 0 0 1\nv 1 0 1\nv 1 1 1\nv 0 1 1\n\
+        f 1 4 3 2\nf 5/1 6/2 7/3 8/4\nf 1//1 2//1 6//1 5//1\nf 2 3 7 6\nf 3 4 8 7\nf 4 1 5 8\n\
+        v 0.5 0.5 2\nf 5 6 7 9 8\n";
+    let mut m = Poly::default();
+    load_obj_poly_mesh(obj.as_bytes(), &mut m).unwrap();
+    assert_eq!(m.num_vertices(), 9);
+    assert_eq!(m.num_faces(), 7);
+    assert_eq!(m.face_size(FId(0)), Some(4));
+    assert_eq!(m.face_size(FId(6)), Some(5));
+    assert_eq!(
+        m.face_vertex_ids(FId(1)).unwrap(),
+        FaceN::new(vec![VId(4), VId(5), VId(6), VId(7)])
+    );
+
+    let check = |loaded: &Poly| {
+        assert_eq!(loaded.num_vertices(), m.num_vertices());
+        assert_eq!(loaded.num_faces(), m.num_faces());
+        for i in 0..m.num_faces() {
+            assert_eq!(loaded.face_vertex_ids(FId(i)), m.face_vertex_ids(FId(i)));
+        }
+        for i in 0..m.num_vertices() {
+            assert_eq!(loaded.vertex(VId(i)), m.vertex(VId(i)));
+        }
+    };
+
+    let mut buffer = Vec::new();
+    save_obj_poly_mesh(&mut buffer, &m).unwrap();
+    let mut loaded = Poly::default();
+    load_obj_poly_mesh(buffer.as_slice(), &mut loaded).unwrap();
+    check(&loaded);
+
+    let mut buffer = Vec::new();
+    save_off_poly_mesh(&mut buffer, &m).unwrap();
+    let mut loaded = Poly::default();
+    load_off_poly_mesh(buffer.as_slice(), &mut loaded).unwrap();
+    check(&loaded);
+
+    let mut buffer = Vec::new();
+    save_ply_ascii_poly_mesh(&mut buffer, &m).unwrap();
+    let mut loaded = Poly::default();
+    load_ply_poly_mesh(buffer.as_slice(), &mut loaded).unwrap();
+    check(&loaded);
+
+    // binary .ply with a single quad
+    let mut buffer = b"ply\nformat binary_little_endian 1.0\nelement vertex 4\n\
+        property float x\nproperty float y\nproperty float z\nelement face 1\n\
+        property list uchar int vertex_indices\nend_header\n"
+        .to_vec();
+    for [x, y] in [[0.0f32, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]].iter() {
+        buffer.extend_from_slice(&x.to_le_bytes());
+        buffer.extend_from_slice(&y.to_le_bytes());
+        buffer.extend_from_slice(&0.0f32.to_le_bytes());
+    }
+    buffer.push(4);
+    for i in 0..4i32 {
+        buffer.extend_from_slice(&i.to_le_bytes());
+    }
+    let mut loaded = Poly::default();
+    load_ply_poly_mesh(buffer.as_slice(), &mut loaded).unwrap();
+    assert_eq!(loaded.num_vertices(), 4);
+    assert_eq!(
+        loaded.face_quad(FId(0)),
+        Some(Face4::new(VId(0), VId(1), VId(2), VId(3)))
+    );
+
+    // the vertex count of large n-gons exceeds uchar
+    let mut large = Poly::default();
+    let n = 300;
+    let vids: Vec<VId> = (0..n)
+        .map(|i| {
+            let a = i as f64 / n as f64 * 2.0 * std::f64::consts::PI;
+            large.add_vertex(Point3D::new(a.cos(), a.sin(), 0.0))
+        })
+        .collect();
+    large.try_add_face(&vids).unwrap();
+    let mut buffer = Vec::new();
+    save_ply_ascii_poly_mesh(&mut buffer, &large).unwrap();
+    assert!(String::from_utf8_lossy(&buffer).contains("property list uint uint vertex_indices"));
+    let mut loaded = Poly::default();
+    load_ply_poly_mesh(buffer.as_slice(), &mut loaded).unwrap();
+    assert_eq!(
+        loaded.face_vertex_ids(FId(0)),
+        large.face_vertex_ids(FId(0))
+    );
+
+    // invalid indices
+    let mut loaded = Poly::default();
+    assert!(load_obj_poly_mesh(
+        "v 0 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 4\n".as_bytes(),
+        &mut loaded
+    )
+    .is_err());
+    let mut loaded = Poly::default();
+    assert!(load_off_poly_mesh(
+        "OFF\n3 1 0\n0 0 0\n1 0 0\n1 1 0\n3 0 1 1\n".as_bytes(),
+        &mut loaded
+    )
+    .is_err());
+}
@@ -0,0 +1,208 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+
+use rust_3d::*;
+
+type Mesh = Mesh3D<Point3D, PointCloud3D<Point3D>, Vec<usize>>;
+
+const OCTAHEDRON_FACES: [[usize; 3]; 8] = [
+    [0, 2, 4],
+    [2, 1, 4],
+    [1, 3, 4],
+    [3, 0, 4],
+    [2, 0, 5],
+    [1, 2, 5],
+    [3, 1, 5],
+    [0, 3, 5],
+];
+
+// faces with the given indices are flipped
+fn octahedron(flipped: &[usize]) -> Mesh {
+    let mut mesh = Mesh::default();
+    mesh.add_vertex(Point3D::new(1.0, 0.0, 0.0));
+    mesh.add_vertex(Point3D::new(-1.0, 0.0, 0.0));
+    mesh.add_vertex(Point3D::new(0.0, 1.0, 0.0));
+    mesh.add_vertex(Point3D::new(0.0, -1.0, 0.0));
+    mesh.add_vertex(Point3D::new(0.0, 0.0, 1.0));
+    mesh.add_vertex(Point3D::new(0.0, 0.0, -1.0));
+
+    for (i, [a, b, c]) in OCTAHEDRON_FACES.iter().enumerate() {
+        if flipped.contains(&i) {
+            mesh.try_add_connection(VId(*a), VId(*c), VId(*b)).unwrap();
+        } else {
+            mesh.try_add_connection(VId(*a), VId(*b), VId(*c)).unwrap();
+        }
+    }
+    mesh
+}
+
+// two tetrahedra touching at a single vertex
+fn bowtie() -> Mesh {
+    let mut mesh = Mesh::default();
+    mesh.add_vertex(Point3D::new(0.0, 0.0, 0.0));
+    for s in [1.0, -1.0].iter() {
+        let a = mesh.add_vertex(Point3D::new(*s, 0.0, 0.0));
+        let b = mesh.add_vertex(Point3D::new(*s, *s, 0.0));
+        let c = mesh.add_vertex(Point3D::new(*s, 0.0, 1.0));
+        mesh.try_add_connection(VId(0), b, a).unwrap();
+        mesh.try_add_connection(VId(0), a, c).unwrap();
+        mesh.try_add_connection(VId(0), c, b).unwrap();
+        mesh.try_add_connection(a, b, c).unwrap();
+    }
+    mesh
+}
+
+fn assert_outwards(mesh: &Mesh) {
+    for i in 0..mesh.num_faces() {
+        let [a, b, c] = mesh.face_vertices(FId(i)).unwrap();
+        let n = normal_of_face(&a, &b, &c);
+        let center = center_3d(&center_3d(&a, &b), &c);
+        assert!(n.x() * center.x() + n.y() * center.y() + n.z() * center.z() > 0.0);
+    }
+}
+
+#[test]
+fn diagnose_mesh_test() {
+    let diagnostics = diagnose_mesh(&octahedron(&[])).unwrap();
+    assert!(diagnostics.is_manifold());
+    assert!(diagnostics.is_consistently_oriented());
+    assert!(diagnostics.is_closed());
+    assert!(diagnostics.is_valid_for_half_edge());
+    assert_eq!(diagnostics.n_components, 1);
+
+    let diagnostics = diagnose_mesh(&octahedron(&[3])).unwrap();
+    assert!(diagnostics.is_manifold());
+    assert_eq!(
+        diagnostics.inconsistent_edges,
+        vec![(VId(0), VId(3)), (VId(0), VId(4)), (VId(3), VId(4))]
+    );
+
+    let diagnostics = diagnose_mesh(&bowtie()).unwrap();
+    assert!(diagnostics.non_manifold_edges.is_empty());
+    assert_eq!(diagnostics.non_manifold_vertices, vec![VId(0)]);
+    assert!(diagnostics.is_consistently_oriented());
+    assert!(diagnostics.is_closed());
+    assert_eq!(diagnostics.n_components, 2);
+
+    // three faces sharing an edge
+    let mut fin = Mesh::default();
+    for p in [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.5, 1.0, 0.0],
+        [0.5, -1.0, 0.0],
+        [0.5, 0.0, 1.0],
+    ]
+    .iter()
+    {
+        fin.add_vertex(Point3D::new(p[0], p[1], p[2]));
+    }
+    fin.try_add_connection(VId(0), VId(1), VId(2)).unwrap();
+    fin.try_add_connection(VId(1), VId(0), VId(3)).unwrap();
+    fin.try_add_connection(VId(1), VId(0), VId(4)).unwrap();
+
+    let diagnostics = diagnose_mesh(&fin).unwrap();
+    assert_eq!(diagnostics.non_manifold_edges, vec![(VId(0), VId(1))]);
+    assert_eq!(diagnostics.boundary_edges.len(), 6);
+    assert!(!diagnostics.is_closed());
+    assert!(!diagnostics.is_valid_for_half_edge());
+
+    let split = split_non_manifold_vertices(&fin).unwrap();
+    let diagnostics = diagnose_mesh(&split).unwrap();
+    assert!(diagnostics.is_manifold());
+    assert_eq!(split.num_faces(), 3);
+    assert!(split.num_vertices() > 5);
+}
+
+#[test]
+fn split_non_manifold_vertices_test() {
+    let mesh = bowtie();
+    let split = split_non_manifold_vertices(&mesh).unwrap();
+    assert_eq!(split.num_vertices(), mesh.num_vertices() + 1);
+    assert_eq!(split.num_faces(), mesh.num_faces());
+
+    let diagnostics = diagnose_mesh(&split).unwrap();
+    assert!(diagnostics.is_valid_for_half_edge());
+    assert!(diagnostics.is_closed());
+    assert_eq!(diagnostics.n_components, 2);
+
+    // the copy is at the same position
+    assert_eq!(
+        split.vertex(VId(split.num_vertices() - 1)).unwrap(),
+        mesh.vertex(VId(0)).unwrap()
+    );
+
+    // manifold meshes are unchanged
+    let octahedron = octahedron(&[]);
+    let split = split_non_manifold_vertices(&octahedron).unwrap();
+    assert_eq!(split.num_vertices(), octahedron.num_vertices());
+}
+
+#[test]
+fn orient_faces_consistently_test() {
+    for flipped in [vec![3], vec![0, 5, 6], (0..8).collect::<Vec<_>>()].iter() {
+        let mesh = octahedron(flipped);
+        let oriented = orient_faces_consistently(&mesh).unwrap();
+        assert!(diagnose_mesh(&oriented).unwrap().is_consistently_oriented());
+        assert_outwards(&oriented);
+    }
+
+    // open grid, the first face keeps its orientation
+    let mut grid = Mesh::default();
+    for j in 0..3 {
+        for i in 0..3 {
+            grid.add_vertex(Point3D::new(i as f64, j as f64, 0.0));
+        }
+    }
+    for j in 0..2 {
+        for i in 0..2 {
+            let v = j * 3 + i;
+            grid.try_add_connection(VId(v), VId(v + 1), VId(v + 4))
+                .unwrap();
+            if i == j {
+                grid.try_add_connection(VId(v), VId(v + 3), VId(v + 4))
+                    .unwrap();
+            } else {
+                grid.try_add_connection(VId(v), VId(v + 4), VId(v + 3))
+                    .unwrap();
+            }
+        }
+    }
+    assert!(!diagnose_mesh(&grid).unwrap().is_consistently_oriented());
+
+    let oriented = orient_faces_consistently(&grid).unwrap();
+    assert!(diagnose_mesh(&oriented).unwrap().is_consistently_oriented());
+    for i in 0..oriented.num_faces() {
+        let [a, b, c] = oriented.face_vertices(FId(i)).unwrap();
+        assert!(normal_of_face(&a, &b, &c).z() > 0.0);
+    }
+
+    // afterwards the half edge structure finds all twins
+    let searchable = SearchableMesh::<_, _, Vec<usize>>::new(
+        orient_faces_consistently(&octahedron(&[2, 4])).unwrap(),
+    );
+    for e in 0..searchable.num_edges() {
+        assert!(searchable.edge_twin(EId(e)).is_some());
+    }
+}
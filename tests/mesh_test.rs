@@ -82,3 +82,71 @@ fn mesh_test() {
         Some([p1, p2, p3]) => assert!(p1.x() == 1.0 && p2.x() == 2.0 && p3.x() == 3.0),
     };
 }
+
+#[test]
+fn mesh_properties_test() {
+    type Mesh = Mesh3D<Point3D, PointCloud3D<Point3D>, Vec<usize>>;
+    let (min, size) = ([1.0, 2.0, 3.0], [2.0, 3.0, 4.0]);
+
+    let mut mesh = Mesh::default();
+    for i in 0..8 {
+        mesh.add_vertex(Point3D::new(
+            min[0] + size[0] * (i & 1) as f64,
+            min[1] + size[1] * ((i >> 1) & 1) as f64,
+            min[2] + size[2] * ((i >> 2) & 1) as f64,
+        ));
+    }
+    let faces = [
+        [0, 2, 1],
+        [1, 2, 3],
+        [0, 1, 5],
+        [0, 5, 4],
+        [1, 3, 7],
+        [1, 7, 5],
+        [3, 2, 6],
+        [3, 6, 7],
+        [2, 0, 4],
+        [2, 4, 6],
+    ];
+    for [a, b, c] in faces.iter() {
+        mesh.try_add_connection(VId(*a), VId(*b), VId(*c)).unwrap();
+    }
+    // the top is tessellated more finely, moving the average of the vertices
+    let top = [4, 5, 7, 6];
+    let center = mesh.add_vertex(Point3D::new(1.5, 2.5, 7.0));
+    for k in 0..4 {
+        mesh.try_add_connection(VId(top[k]), VId(top[(k + 1) % 4]), center)
+            .unwrap();
+    }
+
+    assert!(mesh.is_watertight().unwrap());
+    assert!((mesh.area().unwrap() - 52.0).abs() < 1e-9);
+    assert!((mesh.signed_volume().unwrap() - 24.0).abs() < 1e-9);
+
+    let expected = Point3D::new(2.0, 3.5, 5.0);
+    let centroid = mesh.volume_centroid().unwrap();
+    assert!(dist_3d(&centroid, &expected) < 1e-9);
+    let centroid = mesh.area_centroid().unwrap();
+    assert!((centroid.x - 2.0).abs() < 1e-9 && (centroid.y - 3.5).abs() < 1e-9);
+    assert!((centroid.z - (6.0 * 3.0 + 6.0 * 7.0 + 40.0 * 5.0) / 52.0).abs() < 1e-9);
+
+    let inertia = mesh.inertia_tensor().unwrap();
+    let expected = [[50.0, 0.0, 0.0], [0.0, 40.0, 0.0], [0.0, 0.0, 26.0]];
+    for (row, expected_row) in inertia.data.iter().zip(expected.iter()) {
+        for (x, e) in row.iter().zip(expected_row.iter()) {
+            assert!((x - e).abs() < 1e-9);
+        }
+    }
+
+    let mut open = Mesh::default();
+    open.add_face(
+        Point3D::new(0.0, 0.0, 0.0),
+        Point3D::new(1.0, 0.0, 0.0),
+        Point3D::new(0.0, 1.0, 0.0),
+    );
+    assert!(!open.is_watertight().unwrap());
+    assert!((open.area().unwrap() - 0.5).abs() < 1e-9);
+    assert!(open.volume_centroid().is_err());
+    assert!(open.inertia_tensor().is_err());
+    assert!(Mesh::default().area_centroid().is_err());
+}
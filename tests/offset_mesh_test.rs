@@ -0,0 +1,107 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+
+use rust_3d::*;
+
+mod common;
+use common::*;
+
+fn face_normal(mesh: &Mesh, f: usize) -> Point3D {
+    let [a, b, c] = mesh.face_vertices(FId(f)).unwrap();
+    cross(&conn(&a, &b), &conn(&a, &c))
+}
+
+#[test]
+fn offset_mesh_test() {
+    let mesh = cube([0.0, 0.0, 0.0], 2.0, 2);
+
+    // flat sides, edges and corners keep the distance
+    let grown: Mesh = offset_mesh(&mesh, 0.1).unwrap();
+    assert_closed(&grown);
+    assert_eq!(grown.num_vertices(), mesh.num_vertices());
+    assert!((grown.signed_volume().unwrap() - 2.2f64.powi(3)).abs() < 1e-9);
+    let bb = grown.bounding_box_maybe().unwrap();
+    assert!(dist_3d(&bb.min_p(), &Point3D::new(-0.1, -0.1, -0.1)) < 1e-9);
+
+    let shrunk: Mesh = offset_mesh(&mesh, -0.1).unwrap();
+    assert!((shrunk.signed_volume().unwrap() - 1.8f64.powi(3)).abs() < 1e-9);
+
+    let same: Mesh = offset_mesh(&mesh, 0.0).unwrap();
+    assert!((same.signed_volume().unwrap() - 8.0).abs() < 1e-9);
+}
+
+#[test]
+fn offset_mesh_self_intersection_test() {
+    // offsetting a thin plate inwards by more than half its thickness would flip faces
+    let mut plate = cube([0.0, 0.0, 0.0], 1.0, 4);
+    for i in 0..plate.num_vertices() {
+        let mut p = plate.vertex(VId(i)).unwrap();
+        p.z *= 0.2;
+        plate.change_vertex(VId(i), p).unwrap();
+    }
+
+    let shrunk: Mesh = offset_mesh(&plate, -0.15).unwrap();
+    for f in 0..plate.num_faces() {
+        assert!(face_normal(&plate, f).dot(&face_normal(&shrunk, f)) > 0.0);
+    }
+}
+
+#[test]
+fn shell_mesh_test() {
+    let mesh = cube([0.0, 0.0, 0.0], 2.0, 2);
+    let shell: Mesh = shell_mesh(&mesh, Positive::new(0.2).unwrap()).unwrap();
+    assert_closed(&shell);
+    assert_eq!(diagnose_mesh(&shell).unwrap().n_components, 2);
+    assert!((shell.signed_volume().unwrap() - (8.0 - 1.6f64.powi(3))).abs() < 1e-9);
+
+    // open surfaces are closed along their boundary
+    let mut plane = Mesh::default();
+    for (x, y) in [(0.0, 0.0), (2.0, 0.0), (2.0, 3.0), (0.0, 3.0)].iter() {
+        plane.add_vertex(Point3D::new(*x, *y, 1.0));
+    }
+    plane.try_add_connection(VId(0), VId(1), VId(2)).unwrap();
+    plane.try_add_connection(VId(0), VId(2), VId(3)).unwrap();
+
+    let solid: Mesh = shell_mesh(&plane, Positive::new(0.5).unwrap()).unwrap();
+    assert_closed(&solid);
+    assert!(solid.is_watertight().unwrap());
+    assert_eq!(solid.num_faces(), 2 + 2 + 4 * 2);
+    assert!((solid.signed_volume().unwrap() - 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn offset_mesh_invalid_test() {
+    let mut mesh = Mesh::default();
+    for (x, y) in [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)].iter() {
+        mesh.add_vertex(Point3D::new(*x, *y, 0.0));
+    }
+    mesh.try_add_connection(VId(0), VId(1), VId(2)).unwrap();
+    // same direction of the shared edge
+    mesh.try_add_connection(VId(1), VId(2), VId(3)).unwrap();
+
+    let result: Result<Mesh> = offset_mesh(&mesh, 1.0);
+    assert!(result.is_err());
+    let result: Result<Mesh> = shell_mesh(&mesh, Positive::new(1.0).unwrap());
+    assert!(result.is_err());
+}
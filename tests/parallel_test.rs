@@ -0,0 +1,133 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+#![cfg(feature = "rayon")]
+
+use rust_3d::*;
+
+fn points() -> PointCloud3D<Point3D> {
+    let mut pc = PointCloud3D::new();
+    for i in 0..30 {
+        for j in 0..30 {
+            for k in 0..5 {
+                let x = i as f64 + 0.01 * (j * k) as f64;
+                let y = j as f64 * 0.7 + 0.03 * k as f64;
+                let z = k as f64 * 1.3 + 0.02 * i as f64;
+                pc.push(Point3D::new(x, y, z));
+            }
+        }
+    }
+    pc.push(Point3D::new(-100.0, -100.0, -100.0));
+    pc
+}
+
+#[test]
+fn build_par_test() {
+    let pc = points();
+
+    let mut kd = KdTree::default();
+    kd.build(pc.clone()).unwrap();
+    let mut kd_par = KdTree::default();
+    kd_par.build_par(pc.clone()).unwrap();
+    assert!(kd == kd_par);
+
+    let mut oc = OcTree::default();
+    oc.build(pc.clone()).unwrap();
+    let mut oc_par = OcTree::default();
+    oc_par.build_par(pc.clone()).unwrap();
+    assert!(oc == oc_par);
+
+    let bbs = pc
+        .data
+        .iter()
+        .map(|p| {
+            BoundingBox3D::new(
+                &Point3D::new(p.x() - 0.2, p.y() - 0.2, p.z() - 0.2),
+                &Point3D::new(p.x() + 0.2, p.y() + 0.2, p.z() + 0.2),
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+    let aabb = AABBTree3D::new(bbs.clone(), 20, 4);
+    let aabb_par = AABBTree3D::new_par(bbs, 20, 4);
+    let query =
+        BoundingBox3D::new(&Point3D::new(3.0, 4.0, 1.0), &Point3D::new(9.0, 7.0, 3.0)).unwrap();
+    let (mut result, mut result_par) = (Vec::new(), Vec::new());
+    aabb.bb_colliding(&query, &mut result);
+    aabb_par.bb_colliding(&query, &mut result_par);
+    assert!(!result.is_empty());
+    assert_eq!(result.len(), result_par.len());
+}
+
+#[test]
+fn batch_query_test() {
+    let pc = points();
+    let mut kd = KdTree::default();
+    kd.build(pc.clone()).unwrap();
+
+    let searches = pc.data.iter().step_by(7).cloned().collect::<Vec<_>>();
+    let batch: Vec<Vec<Point3D>> = knearest_batch(&kd, &searches, 5);
+    let nearest: Vec<Option<Point3D>> = nearest_batch(&kd, &searches);
+
+    assert_eq!(batch.len(), searches.len());
+    for (i, search) in searches.iter().enumerate() {
+        let mut expected = Vec::new();
+        kd.knearest(search, 5, &mut expected);
+        assert_eq!(batch[i], expected);
+        assert_eq!(nearest[i], Some(search.clone()));
+    }
+}
+
+#[test]
+fn filter_par_test() {
+    let pc = points();
+    let mut kd = KdTree::default();
+    kd.build(pc.clone()).unwrap();
+
+    let filter = FilterOutlier3D::new(kd.clone(), Positive::new(1.0).unwrap(), 2);
+    let filter_seq = FilterOutlier3D::new(kd, Positive::new(1.0).unwrap(), 2);
+
+    let mut view = View::Full;
+    FilterRandomAccessible::new(filter_seq).filter(&pc, &mut view);
+    let mut view_par = View::Full;
+    filter.filter_par(&pc, &mut view_par);
+
+    match (&view, &view_par) {
+        (View::Restricted(a), View::Restricted(b)) => {
+            assert_eq!(a, b);
+            assert_eq!(a.len(), pc.len() - 1);
+        }
+        _ => panic!("outlier wasn't removed"),
+    }
+
+    let filter_box = FilterRandomAccessible::new(FilterBox3D::new(Box3D {
+        center: Point3D::new(10.0, 10.0, 3.0),
+        size_x: Positive::new(4.0).unwrap(),
+        size_y: Positive::new(4.0).unwrap(),
+        size_z: Positive::new(4.0).unwrap(),
+    }));
+    let mut view_seq = view.clone();
+    filter_box.filter(&pc, &mut view_seq);
+    filter_box.filter_par(&pc, &mut view_par);
+    assert!(view_seq == view_par);
+}
@@ -0,0 +1,194 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+#![deny(warnings)]
+
+use rust_3d::*;
+
+use std::f64::consts::PI;
+
+type Mesh = Mesh3D<Point3D, PointCloud3D<Point3D>, Vec<usize>>;
+type Searchable = SearchableMesh<Mesh, Point3D, Vec<usize>>;
+
+// n x n vertices with spacing 1, optionally without the faces of the center cell
+fn grid(n: usize, hole: bool) -> Searchable {
+    let mut mesh = Mesh::default();
+    for j in 0..n {
+        for i in 0..n {
+            mesh.add_vertex(Point3D::new(i as f64, j as f64, 0.0));
+        }
+    }
+    for j in 0..n - 1 {
+        for i in 0..n - 1 {
+            if hole && i == n / 2 && j == n / 2 {
+                continue;
+            }
+            let v = j * n + i;
+            mesh.try_add_connection(VId(v), VId(v + 1), VId(v + n + 1))
+                .unwrap();
+            mesh.try_add_connection(VId(v), VId(v + n + 1), VId(v + n))
+                .unwrap();
+        }
+    }
+    Searchable::new(mesh)
+}
+
+// cap of the unit sphere around the north pole, up to the given polar angle
+fn sphere_cap(n_rings: usize, n_segments: usize, max_angle: f64) -> Searchable {
+    let mut mesh = Mesh::default();
+    mesh.add_vertex(Point3D::new(0.0, 0.0, 1.0));
+    for k in 1..=n_rings {
+        let theta = max_angle * k as f64 / n_rings as f64;
+        for i in 0..n_segments {
+            let phi = 2.0 * PI * i as f64 / n_segments as f64;
+            mesh.add_vertex(Point3D::new(
+                theta.sin() * phi.cos(),
+                theta.sin() * phi.sin(),
+                theta.cos(),
+            ));
+        }
+    }
+    let ring = |k: usize, i: usize| VId(1 + (k - 1) * n_segments + i % n_segments);
+    for i in 0..n_segments {
+        mesh.try_add_connection(VId(0), ring(1, i), ring(1, i + 1))
+            .unwrap();
+        for k in 1..n_rings {
+            mesh.try_add_connection(ring(k, i), ring(k + 1, i), ring(k + 1, i + 1))
+                .unwrap();
+            mesh.try_add_connection(ring(k, i), ring(k + 1, i + 1), ring(k, i + 1))
+                .unwrap();
+        }
+    }
+    Searchable::new(mesh)
+}
+
+fn cube() -> Searchable {
+    let mut mesh = Mesh::default();
+    for i in 0..8 {
+        let c = |bit: usize| if i & bit == 0 { 0.0 } else { 1.0 };
+        mesh.add_vertex(Point3D::new(c(1), c(2), c(4)));
+    }
+    for [a, b, c] in [
+        [0, 2, 3],
+        [0, 3, 1],
+        [4, 5, 7],
+        [4, 7, 6],
+        [0, 1, 5],
+        [0, 5, 4],
+        [2, 6, 7],
+        [2, 7, 3],
+        [0, 4, 6],
+        [0, 6, 2],
+        [1, 3, 7],
+        [1, 7, 5],
+    ]
+    .iter()
+    {
+        mesh.try_add_connection(VId(*a), VId(*b), VId(*c)).unwrap();
+    }
+    Searchable::new(mesh)
+}
+
+#[test]
+fn lscm_test() {
+    // a plane is mapped onto itself up to rotation and translation
+    let n = 6;
+    let mesh = grid(n, false);
+    let uvs: Vec<Point2D> = lscm_parameterization(&mesh).unwrap();
+    assert_eq!(uvs.len(), n * n);
+    for i in 0..n * n {
+        for j in 0..n * n {
+            let d_3d = dist_3d(&mesh.vertex(VId(i)).unwrap(), &mesh.vertex(VId(j)).unwrap());
+            assert!((dist_2d(&uvs[i], &uvs[j]) - d_3d).abs() < 1e-6);
+        }
+    }
+    for d in parameterization_distortion(&mesh, &uvs).unwrap().iter() {
+        assert!((d.max_stretch - 1.0).abs() < 1e-6);
+        assert!((d.min_stretch - 1.0).abs() < 1e-6);
+        assert!((d.conformal - 1.0).abs() < 1e-6);
+        assert!((d.area - 1.0).abs() < 1e-6);
+    }
+
+    // a curved patch can't be flattened without distortion, but its angles are mostly kept
+    let mesh = sphere_cap(8, 24, 1.0);
+    let uvs: Vec<Point2D> = lscm_parameterization(&mesh).unwrap();
+    let distortions = parameterization_distortion(&mesh, &uvs).unwrap();
+    assert_eq!(distortions.len(), mesh.num_faces());
+    let mean_conformal =
+        distortions.iter().map(|d| d.conformal).sum::<f64>() / distortions.len() as f64;
+    assert!(mean_conformal < 1.05);
+    assert!(distortions.iter().all(|d| d.area > 0.0));
+}
+
+#[test]
+fn harmonic_parameterization_test() {
+    let n = 7;
+    let mesh = grid(n, false);
+    let uniform: Vec<Point2D> =
+        harmonic_parameterization(&mesh, &LaplacianWeighting::Uniform).unwrap();
+    let cotangent: Vec<Point2D> =
+        harmonic_parameterization(&mesh, &LaplacianWeighting::Cotangent).unwrap();
+
+    for uvs in [&uniform, &cotangent].iter() {
+        assert_eq!(uvs.len(), n * n);
+        for j in 0..n {
+            for i in 0..n {
+                let r = *uvs[j * n + i].abs();
+                if i == 0 || j == 0 || i == n - 1 || j == n - 1 {
+                    assert!((r - 1.0).abs() < 1e-9);
+                } else {
+                    assert!(r < 1.0);
+                }
+            }
+        }
+        // the boundary keeps its orientation, so no face is flipped
+        let distortions = parameterization_distortion(&mesh, uvs).unwrap();
+        assert!(distortions.iter().all(|d| d.area > 0.0));
+    }
+
+    // symmetric meshes have their center at the center of the circle
+    let center = *uniform[(n / 2) * n + n / 2].abs();
+    assert!(center < 1e-6);
+
+    let mesh = sphere_cap(6, 16, 1.2);
+    let uvs: Vec<Point2D> = harmonic_parameterization(&mesh, &LaplacianWeighting::Uniform).unwrap();
+    assert!(*uvs[0].abs() < 1e-6);
+    let distortions = parameterization_distortion(&mesh, &uvs).unwrap();
+    assert!(distortions.iter().all(|d| d.area > 0.0));
+}
+
+#[test]
+fn parameterization_invalid_test() {
+    let not_disks = [cube(), grid(5, true)];
+    for mesh in not_disks.iter() {
+        match lscm_parameterization::<Point2D, _, _>(mesh) {
+            Err(ErrorKind::MeshNotDisk) => {}
+            _ => panic!("expected MeshNotDisk"),
+        }
+        match harmonic_parameterization::<Point2D, _, _>(mesh, &LaplacianWeighting::Uniform) {
+            Err(ErrorKind::MeshNotDisk) => {}
+            _ => panic!("expected MeshNotDisk"),
+        }
+    }
+
+    let mesh = grid(3, false);
+    assert!(parameterization_distortion(&mesh, &vec![Point2D::default(); 8]).is_err());
+}
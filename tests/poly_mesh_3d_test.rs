@@ -0,0 +1,127 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+#![deny(warnings)]
+
+use rust_3d::*;
+
+type Poly = PolyMesh3D<Point3D, PointCloud3D<Point3D>, Vec<usize>>;
+type Mesh = Mesh3D<Point3D, PointCloud3D<Point3D>, Vec<usize>>;
+
+fn signed_area_z(mesh: &Mesh) -> f64 {
+    (0..mesh.num_faces())
+        .map(|i| {
+            let [a, b, c] = mesh.face_vertices(FId(i)).unwrap();
+            0.5 * ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x))
+        })
+        .sum()
+}
+
+#[test]
+fn poly_mesh_3d_test() {
+    let mut m = Poly::default();
+    // L-shaped hexagon and a quad next to it
+    for [x, y] in [
+        [0.0, 0.0],
+        [2.0, 0.0],
+        [2.0, 1.0],
+        [1.0, 1.0],
+        [1.0, 2.0],
+        [0.0, 2.0],
+        [3.0, 0.0],
+        [3.0, 1.0],
+    ]
+    .iter()
+    {
+        m.add_vertex(Point3D::new(*x, *y, 0.0));
+    }
+    let hexagon: Vec<VId> = (0..6).map(VId).collect();
+    assert_eq!(m.try_add_face(&hexagon).unwrap(), FId(0));
+    assert_eq!(
+        m.try_add_quad(Face4::new(VId(1), VId(6), VId(7), VId(2)))
+            .unwrap(),
+        FId(1)
+    );
+
+    assert!(m.try_add_face(&[VId(0), VId(1)]).is_err());
+    assert!(m.try_add_face(&[VId(0), VId(1), VId(8)]).is_err());
+    assert!(m.try_add_face(&[VId(0), VId(1), VId(2), VId(1)]).is_err());
+    assert!(m
+        .try_add_quad(Face4::new(VId(0), VId(1), VId(2), VId(1)))
+        .is_err());
+    assert_eq!(m.num_faces(), 2);
+    assert_eq!(m.face_size(FId(0)), Some(6));
+    assert_eq!(m.face_size(FId(2)), None);
+    assert_eq!(
+        m.face_quad(FId(1)),
+        Some(Face4::new(VId(1), VId(6), VId(7), VId(2)))
+    );
+    assert_eq!(m.face_quad(FId(0)), None);
+    assert_eq!(m.face_quad(FId(2)), None);
+    assert_eq!(
+        m.face_vertices(FId(1)).unwrap()[1],
+        Point3D::new(3.0, 0.0, 0.0)
+    );
+
+    // n - 2 triangles per face, covering the same area with the same orientation
+    let tri: Mesh = m.triangulate().unwrap();
+    assert_eq!(tri.num_vertices(), 8);
+    assert_eq!(tri.num_faces(), 4 + 2);
+    assert!((signed_area_z(&tri) - 4.0).abs() < 1e-9);
+    for i in 0..tri.num_faces() {
+        let [a, b, c] = tri.face_vertices(FId(i)).unwrap();
+        assert!((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x) > 0.0);
+    }
+
+    let back = Poly::from_mesh(&tri).unwrap();
+    assert_eq!(back.num_faces(), 6);
+    assert_eq!(back.face_size(FId(0)), Some(3));
+}
+
+#[test]
+fn triangulate_polygon_3d_test() {
+    assert!(triangulate_polygon_3d::<Point3D>(&[]).is_empty());
+    assert_eq!(
+        triangulate_polygon_3d(&[
+            Point3D::new(0.0, 0.0, 0.0),
+            Point3D::new(1.0, 0.0, 0.0),
+            Point3D::new(0.0, 1.0, 0.0),
+        ]),
+        vec![[0, 1, 2]]
+    );
+
+    // concave polygon within a tilted plane, clockwise when viewed from above
+    let polygon: Vec<Point3D> = [[0.0, 0.0], [0.0, 2.0], [1.0, 1.0], [2.0, 2.0], [2.0, 0.0]]
+        .iter()
+        .map(|[x, y]| Point3D::new(*x, *y, 0.5 * x))
+        .collect();
+    let triangles = triangulate_polygon_3d(&polygon);
+    assert_eq!(triangles.len(), 3);
+    let mut area = 0.0;
+    for [a, b, c] in triangles.iter() {
+        let (pa, pb, pc) = (&polygon[*a], &polygon[*b], &polygon[*c]);
+        let z = (pb.x - pa.x) * (pc.y - pa.y) - (pb.y - pa.y) * (pc.x - pa.x);
+        // orientation kept, the reflex corner is never the tip of an ear
+        assert!(z < 0.0);
+        area += 0.5 * z;
+    }
+    assert!((area + 3.0).abs() < 1e-9);
+}
@@ -0,0 +1,202 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+
+use rust_3d::*;
+
+use std::f64::consts::PI;
+
+type Mesh = Mesh3D<Point3D, PointCloud3D<Point3D>, Vec<usize>>;
+
+fn grid(n: usize, size: f64) -> Mesh {
+    let mut mesh = Mesh::default();
+    for j in 0..n {
+        for i in 0..n {
+            let (x, y) = (i as f64, j as f64);
+            mesh.add_vertex(Point3D::new(x * size, y * size, 0.0));
+        }
+    }
+    for j in 0..n - 1 {
+        for i in 0..n - 1 {
+            let v = j * n + i;
+            mesh.try_add_connection(VId(v), VId(v + 1), VId(v + n + 1))
+                .unwrap();
+            mesh.try_add_connection(VId(v), VId(v + n + 1), VId(v + n))
+                .unwrap();
+        }
+    }
+    mesh
+}
+
+fn cube() -> Mesh {
+    let mut mesh = Mesh::default();
+    for i in 0..8 {
+        let c = |bit: usize| if i & bit == 0 { -1.0 } else { 1.0 };
+        mesh.add_vertex(Point3D::new(c(1), c(2), c(4)));
+    }
+    let quads = [
+        [0, 2, 3, 1],
+        [4, 5, 7, 6],
+        [0, 1, 5, 4],
+        [2, 6, 7, 3],
+        [0, 4, 6, 2],
+        [1, 3, 7, 5],
+    ];
+    for [a, b, c, d] in quads.iter() {
+        mesh.try_add_connection(VId(*a), VId(*b), VId(*c)).unwrap();
+        mesh.try_add_connection(VId(*a), VId(*c), VId(*d)).unwrap();
+    }
+    mesh
+}
+
+fn edge_lengths(mesh: &Mesh) -> Vec<f64> {
+    let mut result = Vec::new();
+    for i in 0..mesh.num_faces() {
+        let [a, b, c] = mesh.face_vertices(FId(i)).unwrap();
+        result.push(dist_3d(&a, &b));
+        result.push(dist_3d(&b, &c));
+        result.push(dist_3d(&c, &a));
+    }
+    result
+}
+
+fn area(mesh: &Mesh) -> f64 {
+    (0..mesh.num_faces())
+        .map(|i| {
+            let [a, b, c] = mesh.face_vertices(FId(i)).unwrap();
+            0.5 * *cross(&conn(&a, &b), &conn(&a, &c)).abs()
+        })
+        .sum()
+}
+
+#[test]
+fn remesh_isotropic_plane_test() {
+    let input = grid(3, 5.0);
+    let target = Positive::new(1.0).unwrap();
+
+    let result: Mesh = remesh_isotropic(&input, target, Rad(PI / 4.0), false, 5).unwrap();
+
+    assert!(result.num_faces() > 100);
+    let lengths = edge_lengths(&result);
+    let mean = lengths.iter().sum::<f64>() / lengths.len() as f64;
+    assert!(mean > 0.8 && mean < 1.2);
+    assert!(lengths.iter().all(|x| *x < 1.5));
+
+    // the surface and its outline remain
+    assert!((area(&result) - 100.0).abs() < 1e-6);
+    for i in 0..result.num_vertices() {
+        let p = result.vertex(VId(i)).unwrap();
+        assert!(p.z().abs() < 1e-9);
+        assert!(p.x() > -1e-9 && p.x() < 10.0 + 1e-9);
+        assert!(p.y() > -1e-9 && p.y() < 10.0 + 1e-9);
+    }
+
+    let diagnostics = diagnose_mesh(&result).unwrap();
+    assert!(diagnostics.is_valid_for_half_edge());
+    assert_eq!(diagnostics.n_components, 1);
+}
+
+#[test]
+fn remesh_isotropic_boundary_test() {
+    let input = grid(11, 1.0);
+    let target = Positive::new(0.5).unwrap();
+
+    let preserved: Mesh = remesh_isotropic(&input, target, Rad(PI / 4.0), true, 3).unwrap();
+    let resampled: Mesh = remesh_isotropic(&input, target, Rad(PI / 4.0), false, 3).unwrap();
+
+    let boundary = |mesh: &Mesh| {
+        let searchable = SearchableMesh::<_, _, Vec<usize>>::new(mesh.clone());
+        let mut loops = Vec::new();
+        searchable.boundary_loops(&mut loops).unwrap();
+        assert_eq!(loops.len(), 1);
+        let mut points = loops[0]
+            .iter()
+            .map(|v| {
+                let p = mesh.vertex(*v).unwrap();
+                (p.x(), p.y())
+            })
+            .collect::<Vec<_>>();
+        points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        points
+    };
+
+    // the boundary is kept as is
+    assert_eq!(boundary(&preserved), boundary(&input));
+    // or refined to the target length
+    assert_eq!(boundary(&resampled).len(), 2 * boundary(&input).len());
+
+    for mesh in [preserved, resampled].iter() {
+        assert!(mesh.num_faces() > input.num_faces());
+        assert!((area(mesh) - 100.0).abs() < 1e-6);
+        assert!(diagnose_mesh(mesh).unwrap().is_valid_for_half_edge());
+    }
+}
+
+#[test]
+fn remesh_isotropic_feature_test() {
+    let input = cube();
+    let target = Positive::new(0.25).unwrap();
+
+    let result: Mesh = remesh_isotropic(&input, target, Rad(PI / 4.0), true, 5).unwrap();
+
+    assert!(result.num_faces() > 200);
+    let diagnostics = diagnose_mesh(&result).unwrap();
+    assert!(diagnostics.is_valid_for_half_edge());
+    assert!(diagnostics.is_closed());
+
+    // all vertices stay on the surface and the sharp edges remain
+    let mut n_corners = 0;
+    let mut n_on_edges = 0;
+    for i in 0..result.num_vertices() {
+        let p = result.vertex(VId(i)).unwrap();
+        let n_extreme = [p.x(), p.y(), p.z()]
+            .iter()
+            .filter(|x| (x.abs() - 1.0).abs() < 1e-9)
+            .count();
+        assert!(n_extreme > 0);
+        match n_extreme {
+            3 => n_corners += 1,
+            2 => n_on_edges += 1,
+            _ => (),
+        }
+    }
+    assert_eq!(n_corners, 8);
+    assert!(n_on_edges >= 12 * 7);
+    assert!((area(&result) - 24.0).abs() < 1e-6);
+
+    // without features the corners are cut
+    let smooth: Mesh = remesh_isotropic(&input, target, Rad(PI), true, 5).unwrap();
+    assert!(area(&smooth) < 24.0 - 1e-3);
+}
+
+#[test]
+fn remesh_isotropic_invalid_test() {
+    let mut mesh = grid(2, 1.0);
+    // the same oriented edge within two faces
+    let v = mesh.add_vertex(Point3D::new(0.5, -1.0, 0.0));
+    mesh.try_add_connection(VId(0), VId(1), v).unwrap();
+
+    let result: Result<Mesh> =
+        remesh_isotropic(&mesh, Positive::new(0.5).unwrap(), Rad(PI / 4.0), true, 1);
+    assert!(result.is_err());
+}
@@ -0,0 +1,119 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+
+use rust_3d::*;
+
+mod common;
+use common::*;
+
+type Contour = SliceContour<Point2D, Point3D>;
+
+fn area(contour: &Contour) -> f64 {
+    let polygon = &contour.polygon_2d;
+    (0..polygon.num_segments())
+        .map(|i| {
+            let (p, q) = polygon.segment_vertices(SId(i)).unwrap();
+            p.x * q.y - q.x * p.y
+        })
+        .sum::<f64>()
+        / 2.0
+}
+
+#[test]
+fn slice_mesh_cube_test() {
+    let mesh = cube([0.0, 0.0, 0.0], 2.0, 1);
+
+    let contours: Vec<Contour> = slice_mesh(&mesh, &plane_z(1.0)).unwrap();
+    assert_eq!(contours.len(), 1);
+    assert!(!contours[0].is_hole);
+    assert!((area(&contours[0]) - 4.0).abs() < 1e-9);
+
+    let polygon = &contours[0].polygon_3d;
+    assert_eq!(polygon.num_vertices(), polygon.num_segments());
+    for i in 0..polygon.num_vertices() {
+        let p = polygon.vertex(VId(i)).unwrap();
+        assert!((p.z - 1.0).abs() < 1e-9);
+    }
+
+    // vertices on the plane
+    let mesh = cube([0.0, 0.0, 0.0], 2.0, 2);
+    let contours: Vec<Contour> = slice_mesh(&mesh, &plane_z(1.0)).unwrap();
+    assert_eq!(contours.len(), 1);
+    assert!((area(&contours[0]) - 4.0).abs() < 1e-9);
+    assert_eq!(contours[0].polygon_2d.num_vertices(), 8);
+
+    let contours: Vec<Contour> = slice_mesh(&mesh, &plane_z(3.0)).unwrap();
+    assert!(contours.is_empty());
+}
+
+#[test]
+fn slice_mesh_hole_test() {
+    let outer = cube([0.0, 0.0, 0.0], 3.0, 1);
+    let inner = cube([1.0, 1.0, 1.0], 1.0, 1);
+    let hollow: Mesh = mesh_boolean(&outer, &inner, BooleanOperation::Difference).unwrap();
+
+    let mut contours: Vec<Contour> = slice_mesh(&hollow, &plane_z(1.5)).unwrap();
+    contours.sort_by_key(|x| x.is_hole);
+    assert_eq!(contours.len(), 2);
+    assert!(!contours[0].is_hole);
+    assert!(contours[1].is_hole);
+    assert!((area(&contours[0]) - 9.0).abs() < 1e-9);
+    assert!((area(&contours[1]) + 1.0).abs() < 1e-9);
+
+    // flipping the plane mirrors the 2D contours, but keeps the classification
+    let plane = Plane3D::new(
+        Point3D::new(0.0, 0.0, 1.5),
+        Norm3D::norm_y(),
+        Norm3D::norm_x(),
+    );
+    let contours: Vec<Contour> = slice_mesh(&hollow, &plane).unwrap();
+    assert_eq!(contours.iter().filter(|x| x.is_hole).count(), 1);
+    for contour in contours.iter() {
+        assert_eq!(contour.is_hole, area(contour) < 0.0);
+    }
+}
+
+#[test]
+fn slice_mesh_stack_test() {
+    let mesh = cube([0.0, 0.0, 0.0], 2.0, 2);
+    let stack: Vec<Vec<Contour>> =
+        slice_mesh_stack(&mesh, &plane_z(-0.25), Positive::new(0.5).unwrap(), 6).unwrap();
+    let counts = stack.iter().map(|x| x.len()).collect::<Vec<_>>();
+    assert_eq!(counts, vec![0, 1, 1, 1, 1, 0]);
+    for contour in stack.iter().flatten() {
+        assert!((area(contour) - 4.0).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn slice_mesh_open_test() {
+    let mut mesh = Mesh::default();
+    mesh.add_face(
+        Point3D::new(0.0, 0.0, 0.0),
+        Point3D::new(1.0, 0.0, 1.0),
+        Point3D::new(0.0, 1.0, 1.0),
+    );
+    let result: Result<Vec<Contour>> = slice_mesh(&mesh, &plane_z(0.5));
+    assert!(result.is_err());
+}
@@ -0,0 +1,234 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+
+use rust_3d::*;
+
+mod common;
+use common::*;
+
+// deterministic noise in [-1.0, 1.0]
+fn noise(i: usize) -> f64 {
+    ((i * 2_654_435_761) % 1000) as f64 / 500.0 - 1.0
+}
+
+fn noisy_sphere(amplitude: f64) -> SearchableMesh<Mesh, Point3D, Vec<usize>> {
+    let n_segments = 40;
+    let mut mesh = sphere(20, n_segments, true);
+    // the rings are moved along their normals, the poles are kept
+    for i in 2..mesh.num_vertices() {
+        let p = mesh.vertex(VId(i)).unwrap();
+        let radius = 1.0 + amplitude * noise(i - 2 + n_segments);
+        mesh.change_vertex(
+            VId(i),
+            Point3D::new(radius * p.x, radius * p.y, radius * p.z),
+        )
+        .unwrap();
+    }
+    SearchableMesh::new(mesh)
+}
+
+fn noisy_grid(n: usize) -> SearchableMesh<Mesh, Point3D, Vec<usize>> {
+    let mut mesh = Mesh::default();
+    for j in 0..n {
+        for i in 0..n {
+            mesh.add_vertex(Point3D::new(i as f64, j as f64, 0.1 * noise(j * n + i)));
+        }
+    }
+    for j in 0..n - 1 {
+        for i in 0..n - 1 {
+            let v = j * n + i;
+            mesh.try_add_connection(VId(v), VId(v + 1), VId(v + n + 1))
+                .unwrap();
+            mesh.try_add_connection(VId(v), VId(v + n + 1), VId(v + n))
+                .unwrap();
+        }
+    }
+    SearchableMesh::new(mesh)
+}
+
+// mean and standard deviation of the distances to the origin
+fn radius_stats<M>(mesh: &M) -> (f64, f64)
+where
+    M: IsMesh<Point3D, Face3>,
+{
+    let radii = (0..mesh.num_vertices())
+        .map(|i| *mesh.vertex(VId(i)).unwrap().abs())
+        .collect::<Vec<_>>();
+    let n = radii.len() as f64;
+    let mean = radii.iter().sum::<f64>() / n;
+    let variance = radii.iter().map(|r| (r - mean) * (r - mean)).sum::<f64>() / n;
+    (mean, variance.sqrt())
+}
+
+// mean distance between the vertices of two meshes
+fn mean_distance<M>(a: &M, b: &M) -> f64
+where
+    M: IsMesh<Point3D, Face3>,
+{
+    (0..a.num_vertices())
+        .map(|i| dist_3d(&a.vertex(VId(i)).unwrap(), &b.vertex(VId(i)).unwrap()))
+        .sum::<f64>()
+        / a.num_vertices() as f64
+}
+
+#[test]
+fn laplacian_smoothing_test() {
+    for weighting in [LaplacianWeighting::Uniform, LaplacianWeighting::Cotangent].iter() {
+        let mut mesh = noisy_sphere(0.05);
+        let mut clean = noisy_sphere(0.0);
+        let (mean_before, _) = radius_stats(&mesh);
+        let noise_before = mean_distance(&mesh, &clean);
+
+        // the noise is removed, compared to the smoothed clean mesh
+        laplacian_smoothing(&mut mesh, weighting, 0.5, 10, true).unwrap();
+        laplacian_smoothing(&mut clean, weighting, 0.5, 10, true).unwrap();
+        assert!(mean_distance(&mesh, &clean) < 0.3 * noise_before);
+
+        // laplacian smoothing shrinks
+        let (mean_after, _) = radius_stats(&mesh);
+        assert!(mean_after < mean_before - 0.005);
+    }
+
+    // no iterations, no changes
+    let mut mesh = noisy_sphere(0.05);
+    let stats = radius_stats(&mesh);
+    laplacian_smoothing(&mut mesh, &LaplacianWeighting::Uniform, 0.5, 0, true).unwrap();
+    assert_eq!(radius_stats(&mesh), stats);
+}
+
+#[test]
+fn taubin_smoothing_test() {
+    for weighting in [LaplacianWeighting::Uniform, LaplacianWeighting::Cotangent].iter() {
+        let mut mesh = noisy_sphere(0.05);
+        let mut clean = noisy_sphere(0.0);
+        let (mean_before, _) = radius_stats(&mesh);
+        let noise_before = mean_distance(&mesh, &clean);
+
+        taubin_smoothing(&mut mesh, weighting, 0.5, -0.53, 10, true).unwrap();
+        taubin_smoothing(&mut clean, weighting, 0.5, -0.53, 10, true).unwrap();
+        assert!(mean_distance(&mesh, &clean) < 0.5 * noise_before);
+
+        // taubin smoothing doesn't shrink
+        let (mean_after, _) = radius_stats(&mesh);
+        assert!((mean_after - mean_before).abs() < 0.005);
+    }
+}
+
+#[test]
+fn smoothing_boundary_test() {
+    let n = 10;
+    let original = noisy_grid(n);
+    let on_boundary = |p: &Point3D| {
+        p.x() == 0.0 || p.y() == 0.0 || p.x() == (n - 1) as f64 || p.y() == (n - 1) as f64
+    };
+    let z_deviation = |mesh: &SearchableMesh<Mesh, Point3D, Vec<usize>>| {
+        (0..mesh.num_vertices())
+            .map(|i| mesh.vertex(VId(i)).unwrap())
+            .filter(|p| !on_boundary(p))
+            .map(|p| p.z().abs())
+            .sum::<f64>()
+    };
+
+    let mut pinned = original.clone();
+    laplacian_smoothing(&mut pinned, &LaplacianWeighting::Cotangent, 0.5, 5, true).unwrap();
+    assert!(z_deviation(&pinned) < 0.5 * z_deviation(&original));
+
+    for i in 0..original.num_vertices() {
+        let before = original.vertex(VId(i)).unwrap();
+        let after = pinned.vertex(VId(i)).unwrap();
+        assert_eq!(on_boundary(&before), before == after);
+    }
+
+    // without pinning the boundary shrinks inwards
+    let mut free = original.clone();
+    laplacian_smoothing(&mut free, &LaplacianWeighting::Uniform, 0.5, 5, false).unwrap();
+    assert!(free.vertex(VId(0)).unwrap().x() > 0.0);
+}
+
+#[test]
+fn smoothing_unused_vertex_test() {
+    let n = 5;
+    let mut mesh = noisy_grid(n).mesh().clone();
+    let unused = mesh.add_vertex(Point3D::new(10.0, 10.0, 10.0));
+    let mut mesh = SearchableMesh::<_, _, Vec<usize>>::new(mesh);
+
+    laplacian_smoothing(&mut mesh, &LaplacianWeighting::Cotangent, 0.5, 3, true).unwrap();
+    taubin_smoothing(
+        &mut mesh,
+        &LaplacianWeighting::Uniform,
+        0.5,
+        -0.53,
+        3,
+        false,
+    )
+    .unwrap();
+    assert_eq!(mesh.vertex(unused).unwrap(), Point3D::new(10.0, 10.0, 10.0));
+}
+
+#[test]
+fn smoothing_obtuse_test() {
+    // fan around the origin, the face (0, 1, 2) is obtuse at 2
+    let positions = [
+        Point3D::new(0.0, 0.0, 0.0),
+        Point3D::new(1.0, 0.0, 0.0),
+        Point3D::new(0.5, 0.25, 0.0),
+        Point3D::new(-1.0, 0.0, 0.0),
+        Point3D::new(0.0, -1.0, 0.0),
+    ];
+    let mut mesh = Mesh::default();
+    for p in positions.iter() {
+        mesh.add_vertex(p.clone());
+    }
+    for [b, c] in [[1, 2], [2, 3], [3, 4], [4, 1]].iter() {
+        mesh.try_add_connection(VId(0), VId(*b), VId(*c)).unwrap();
+    }
+    let mut mesh = SearchableMesh::<_, _, Vec<usize>>::new(mesh);
+    laplacian_smoothing(&mut mesh, &LaplacianWeighting::Cotangent, 1.0, 1, true).unwrap();
+
+    // cotangent of the angle at p within the triangle (p, a, b)
+    let cot_at = |p: usize, a: usize, b: usize| {
+        let u = conn(&positions[p], &positions[a]);
+        let v = conn(&positions[p], &positions[b]);
+        u.dot(&v) / *cross(&u, &v).abs()
+    };
+    // the weight of 1 sums a negative and a positive cotangent
+    let weights = [
+        (1, cot_at(2, 0, 1) + cot_at(4, 0, 1)),
+        (2, cot_at(1, 0, 2) + cot_at(3, 0, 2)),
+        (3, cot_at(2, 0, 3) + cot_at(4, 0, 3)),
+        (4, cot_at(3, 0, 4) + cot_at(1, 0, 4)),
+    ];
+    assert!(cot_at(2, 0, 1) < 0.0);
+    assert!(weights[0].1 > 0.0);
+
+    let sum: f64 = weights.iter().map(|(_, w)| w).sum();
+    let (mut x, mut y) = (0.0, 0.0);
+    for (n, w) in weights.iter() {
+        x += positions[*n].x * w / sum;
+        y += positions[*n].y * w / sum;
+    }
+    let moved = mesh.vertex(VId(0)).unwrap();
+    assert!((moved.x - x).abs() < 1e-9);
+    assert!((moved.y - y).abs() < 1e-9);
+}
@@ -0,0 +1,194 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+
+use rust_3d::*;
+
+type Mesh = Mesh3D<Point3D, PointCloud3D<Point3D>, Vec<usize>>;
+
+fn octahedron() -> Mesh {
+    let mut mesh = Mesh::default();
+    mesh.add_vertex(Point3D::new(1.0, 0.0, 0.0));
+    mesh.add_vertex(Point3D::new(-1.0, 0.0, 0.0));
+    mesh.add_vertex(Point3D::new(0.0, 1.0, 0.0));
+    mesh.add_vertex(Point3D::new(0.0, -1.0, 0.0));
+    mesh.add_vertex(Point3D::new(0.0, 0.0, 1.0));
+    mesh.add_vertex(Point3D::new(0.0, 0.0, -1.0));
+
+    for [a, b, c] in [
+        [0, 2, 4],
+        [2, 1, 4],
+        [1, 3, 4],
+        [3, 0, 4],
+        [2, 0, 5],
+        [1, 2, 5],
+        [3, 1, 5],
+        [0, 3, 5],
+    ]
+    .iter()
+    {
+        mesh.try_add_connection(VId(*a), VId(*b), VId(*c)).unwrap();
+    }
+    mesh
+}
+
+// 3x3 vertices on the x/y plane, forming 8 faces
+fn grid() -> Mesh {
+    let mut mesh = Mesh::default();
+    for j in 0..3 {
+        for i in 0..3 {
+            mesh.add_vertex(Point3D::new(i as f64, j as f64, 0.0));
+        }
+    }
+    for j in 0..2 {
+        for i in 0..2 {
+            let v = j * 3 + i;
+            mesh.try_add_connection(VId(v), VId(v + 1), VId(v + 4))
+                .unwrap();
+            mesh.try_add_connection(VId(v), VId(v + 4), VId(v + 3))
+                .unwrap();
+        }
+    }
+    mesh
+}
+
+fn norm(p: &Point3D) -> f64 {
+    (p.x() * p.x() + p.y() * p.y() + p.z() * p.z()).sqrt()
+}
+
+#[test]
+fn boundary_test() {
+    let closed = SearchableMesh::<_, _, Vec<usize>>::new(octahedron());
+    let mut cache = Vec::new();
+    for i in 0..closed.num_vertices() {
+        assert!(!closed.is_vertex_on_boundary(VId(i), &mut cache).unwrap());
+    }
+    for i in 0..closed.num_edges() {
+        assert!(!closed.is_edge_on_boundary(EId(i)));
+    }
+
+    let mut neighbours = Vec::new();
+    closed
+        .vertex_neighbours(VId(4), &mut cache, &mut neighbours)
+        .unwrap();
+    assert_eq!(neighbours, vec![VId(0), VId(1), VId(2), VId(3)]);
+
+    let open = SearchableMesh::<_, _, Vec<usize>>::new(grid());
+    for i in 0..open.num_vertices() {
+        assert_eq!(
+            open.is_vertex_on_boundary(VId(i), &mut cache).unwrap(),
+            i != 4
+        );
+    }
+    assert!(open.is_vertex_on_boundary(VId(9), &mut cache).is_err());
+
+    let n_boundary_edges = (0..open.num_edges())
+        .filter(|i| open.is_edge_on_boundary(EId(*i)))
+        .count();
+    assert_eq!(n_boundary_edges, 8);
+}
+
+#[test]
+fn loop_subdivision_test() {
+    let mi = SearchableMesh::<_, _, Vec<usize>>::new(octahedron());
+    let mo: Mesh = subdivide::loop_subdivision(&mi).unwrap();
+
+    assert_eq!(mo.num_vertices(), 6 + 12);
+    assert_eq!(mo.num_faces(), 4 * 8);
+
+    // all vertices move towards the center
+    let d0 = norm(&mo.vertex(VId(0)).unwrap());
+    for i in 0..mo.num_vertices() {
+        let d = norm(&mo.vertex(VId(i)).unwrap());
+        assert!(d > 0.4 && d < 1.0);
+        if i < 6 {
+            assert!((d - d0).abs() < 1e-9);
+        }
+    }
+
+    // planar meshes stay planar, boundaries move only along themselves
+    let mi = SearchableMesh::<_, _, Vec<usize>>::new(grid());
+    let mo: Mesh = subdivide::loop_subdivision(&mi).unwrap();
+    assert_eq!(mo.num_vertices(), 9 + 16);
+    assert_eq!(mo.num_faces(), 32);
+    for i in 0..mo.num_vertices() {
+        let p = mo.vertex(VId(i)).unwrap();
+        assert!(p.z() == 0.0);
+    }
+    // boundary vertex in the middle of an edge stays on it
+    assert!(mo.vertex(VId(1)).unwrap() == Point3D::new(1.0, 0.0, 0.0));
+    // corners are moved inwards along the boundary
+    let corner = mo.vertex(VId(0)).unwrap();
+    assert!(corner.x() > 0.0 && corner.y() > 0.0);
+}
+
+#[test]
+fn butterfly_test() {
+    let mi = SearchableMesh::<_, _, Vec<usize>>::new(octahedron());
+    let mo: Mesh = subdivide::butterfly(&mi).unwrap();
+
+    assert_eq!(mo.num_vertices(), 6 + 12);
+    assert_eq!(mo.num_faces(), 4 * 8);
+
+    // interpolating, the original vertices are kept
+    for i in 0..6 {
+        assert!(mo.vertex(VId(i)) == mi.vertex(VId(i)));
+    }
+    // new vertices are moved outwards compared to the edge centers
+    for i in 6..mo.num_vertices() {
+        let d = norm(&mo.vertex(VId(i)).unwrap());
+        assert!(d > 0.5f64.sqrt() + 0.1);
+    }
+
+    let mi = SearchableMesh::<_, _, Vec<usize>>::new(grid());
+    let mo: Mesh = subdivide::butterfly(&mi).unwrap();
+    assert_eq!(mo.num_vertices(), 9 + 16);
+    for i in 0..mo.num_vertices() {
+        let p = mo.vertex(VId(i)).unwrap();
+        assert!(p.z().abs() < 1e-9);
+        // the four point scheme rounds the corners of the boundary slightly
+        assert!(p.x() > -0.1 && p.x() < 2.1 && p.y() > -0.1 && p.y() < 2.1);
+    }
+}
+
+#[test]
+fn subdivide_unused_vertex_test() {
+    let mut mesh = grid();
+    let unused = mesh.add_vertex(Point3D::new(5.0, 5.0, 5.0));
+    let mi = SearchableMesh::<_, _, Vec<usize>>::new(mesh);
+
+    let mut cache = Vec::new();
+    let mut neighbours = Vec::new();
+    assert!(!mi.is_vertex_on_boundary(unused, &mut cache).unwrap());
+    mi.vertex_neighbours(unused, &mut cache, &mut neighbours)
+        .unwrap();
+    assert!(neighbours.is_empty());
+
+    let mo: Mesh = subdivide::loop_subdivision(&mi).unwrap();
+    assert_eq!(mo.num_vertices(), 10 + 16);
+    assert_eq!(mo.vertex(unused).unwrap(), Point3D::new(5.0, 5.0, 5.0));
+
+    let mo: Mesh = subdivide::butterfly(&mi).unwrap();
+    assert_eq!(mo.num_vertices(), 10 + 16);
+    assert_eq!(mo.vertex(unused).unwrap(), Point3D::new(5.0, 5.0, 5.0));
+}
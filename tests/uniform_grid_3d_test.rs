@@ -0,0 +1,238 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+
+use rust_3d::*;
+
+fn points() -> Vec<Point3D> {
+    let mut result = Vec::new();
+    for i in 0..15 {
+        for j in 0..12 {
+            for k in 0..5 {
+                // uneven spacing to get cells with differing counts
+                let x = (i * i) as f64 * 0.07 - 3.0;
+                let y = j as f64 * 0.33;
+                let z = k as f64 * 0.4 + j as f64 * 0.01;
+                result.push(Point3D::new(x, y, z));
+            }
+        }
+    }
+    result
+}
+
+fn sorted(mut ps: Vec<Point3D>) -> Vec<Point3D> {
+    ps.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    ps
+}
+
+#[test]
+fn uniform_grid_3d_test() {
+    let data = points();
+    let grid = UniformGrid3D::with_data(Positive::new(0.5).unwrap(), data.clone());
+    let mut tree = KdTree::<Point3D>::default();
+    tree.build(PointCloud3D { data: data.clone() }).unwrap();
+
+    assert_eq!(grid.len(), data.len());
+
+    let searches = [
+        Point3D::new(0.0, 0.0, 0.0),
+        Point3D::new(2.3, 1.7, 0.4),
+        Point3D::new(-10.0, 20.0, 5.0),
+        Point3D::new(11.0, 3.0, -1.0),
+    ];
+
+    for search in searches.iter() {
+        let sphere = Sphere {
+            center: search.clone(),
+            radius: Positive::new(1.3).unwrap(),
+        };
+        let mut expected = Vec::new();
+        tree.in_sphere(&sphere, &mut expected);
+        let mut result = Vec::new();
+        grid.in_sphere(&sphere, &mut result);
+        assert_eq!(sorted(result), sorted(expected));
+
+        let box_3d = Box3D {
+            center: search.clone(),
+            size_x: Positive::new(2.0).unwrap(),
+            size_y: Positive::new(0.7).unwrap(),
+            size_z: Positive::new(1.1).unwrap(),
+        };
+        let mut expected = Vec::new();
+        tree.in_box(&box_3d, &mut expected);
+        let mut result = Vec::new();
+        grid.in_box(&box_3d, &mut result);
+        assert_eq!(sorted(result), sorted(expected));
+
+        for n in [1, 7, 30].iter() {
+            let mut result = Vec::new();
+            grid.knearest(search, *n, &mut result);
+            assert_eq!(result.len(), *n);
+
+            // compare distances, since the order of equally distant positions is undefined
+            let mut dists_expected = data
+                .iter()
+                .map(|p| sqr_dist_3d(search, p))
+                .collect::<Vec<_>>();
+            dists_expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            dists_expected.truncate(*n);
+            let dists = result
+                .iter()
+                .map(|p| sqr_dist_3d(search, p))
+                .collect::<Vec<_>>();
+            assert_eq!(dists, dists_expected);
+        }
+
+        let nearest: Option<Point3D> = grid.nearest(search);
+        let min_dist = data
+            .iter()
+            .map(|p| sqr_dist_3d(search, p))
+            .fold(f64::MAX, f64::min);
+        assert_eq!(sqr_dist_3d(search, &nearest.unwrap()), min_dist);
+    }
+
+    let mut all = Vec::new();
+    grid.knearest(&Point3D::new(0.0, 0.0, 0.0), 10000, &mut all);
+    assert_eq!(all.len(), data.len());
+}
+
+#[test]
+fn uniform_grid_3d_incremental_test() {
+    let mut grid = UniformGrid3D::new(Positive::new(1.0).unwrap());
+    assert!(grid.is_empty());
+    let nearest: Option<Point3D> = grid.nearest(&Point3D::new(0.0, 0.0, 0.0));
+    assert!(nearest.is_none());
+
+    grid.insert(Point3D::new(0.5, 0.5, 0.5));
+    grid.insert(Point3D::new(-0.5, 0.5, 0.5));
+    grid.insert(Point3D::new(100.5, 0.5, 0.5));
+    assert_eq!(grid.len(), 3);
+    assert_eq!(grid.n_cells(), 3);
+
+    let nearest: Option<Point3D> = grid.nearest(&Point3D::new(90.0, 0.0, 0.0));
+    assert_eq!(nearest, Some(Point3D::new(100.5, 0.5, 0.5)));
+}
+
+#[test]
+fn uniform_grid_3d_far_query_test() {
+    // small cells, queries far outside of the occupied range
+    let data: Vec<Point3D> = (0..100)
+        .map(|i| {
+            Point3D::new(
+                (i % 10) as f64 * 0.1,
+                (i / 10) as f64 * 0.1,
+                (i % 7) as f64 * 0.1,
+            )
+        })
+        .collect();
+    let grid = UniformGrid3D::with_data(Positive::new(0.1).unwrap(), data.clone());
+
+    let searches = [
+        Point3D::new(50.0, 0.0, 0.0),
+        Point3D::new(-100.0, 100.0, 0.3),
+        Point3D::new(1e6, -1e6, 1e6),
+        Point3D::new(0.45, 0.45, 1e5),
+    ];
+
+    for search in searches.iter() {
+        let mut result = Vec::new();
+        grid.knearest(search, 5, &mut result);
+        let mut dists_expected = data
+            .iter()
+            .map(|p| sqr_dist_3d(search, p))
+            .collect::<Vec<_>>();
+        dists_expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        dists_expected.truncate(5);
+        let dists = result
+            .iter()
+            .map(|p| sqr_dist_3d(search, p))
+            .collect::<Vec<_>>();
+        assert_eq!(dists, dists_expected);
+    }
+}
+
+#[test]
+fn uniform_grid_3d_sparse_test() {
+    // few points far apart with tiny cells, the occupied range spans ~10^9 cells
+    let data = vec![
+        Point3D::new(0.0, 0.0, 0.0),
+        Point3D::new(10.0, 10.0, 10.0),
+        Point3D::new(10.0, 0.0, 0.0),
+    ];
+    let grid = UniformGrid3D::with_data(Positive::new(0.01).unwrap(), data.clone());
+    let search = Point3D::new(5.0, 5.0, 5.0);
+
+    let mut result = Vec::new();
+    grid.knearest(&search, 2, &mut result);
+    assert_eq!(result.len(), 2);
+    assert!((sqr_dist_3d(&search, &result[1]) - 75.0).abs() < 1e-9);
+
+    let nearest: Option<Point3D> = grid.nearest(&Point3D::new(9.0, 1.0, 1.0));
+    assert_eq!(nearest, Some(Point3D::new(10.0, 0.0, 0.0)));
+
+    let mut result = Vec::new();
+    grid.in_sphere(
+        &Sphere {
+            center: search.clone(),
+            radius: Positive::new(8.7).unwrap(),
+        },
+        &mut result,
+    );
+    assert_eq!(sorted(result), sorted(data));
+
+    let mut result = Vec::new();
+    grid.in_box(
+        &Box3D {
+            center: Point3D::new(5.0, 0.0, 0.0),
+            size_x: Positive::new(12.0).unwrap(),
+            size_y: Positive::new(1.0).unwrap(),
+            size_z: Positive::new(1.0).unwrap(),
+        },
+        &mut result,
+    );
+    assert_eq!(result.len(), 2);
+}
+
+#[test]
+fn uniform_grid_3d_filter_outlier_test() {
+    let mut data = points();
+    data.push(Point3D::new(50.0, 50.0, 50.0));
+    data.push(Point3D::new(-50.0, 0.0, 0.0));
+    let pc = PointCloud3D { data: data.clone() };
+
+    let grid = UniformGrid3D::with_data(Positive::new(0.5).unwrap(), data);
+    let filter = FilterOutlier3D::new(grid, Positive::new(0.5).unwrap(), 2);
+    let filter_pc = FilterRandomAccessible::new(filter);
+
+    let mut view = View::Full;
+    filter_pc.filter(&pc, &mut view);
+
+    match view {
+        View::Full => panic!("outliers weren't removed"),
+        View::Restricted(indices) => {
+            assert_eq!(indices.len(), pc.len() - 2);
+            assert!(!indices.contains(&(pc.len() - 1)));
+            assert!(!indices.contains(&(pc.len() - 2)));
+        }
+    }
+}
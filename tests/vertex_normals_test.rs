@@ -0,0 +1,128 @@
+/*
+Copyright 2020 Martin Buck
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"),
+to deal in the Software without restriction, including without limitation the
+rights to use, copy, modify, merge, publish, distribute, sublicense,
+and/or sell copies of the Software, and to permit persons to whom the Software
+is furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall
+be included all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT,
+TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE
+OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+#![deny(warnings)]
+
+use rust_3d::*;
+
+type Mesh = Mesh3D<Point3D, PointCloud3D<Point3D>, Vec<usize>>;
+
+// unit cube centered at the origin, faces pointing outwards
+fn cube() -> Mesh {
+    let mut mesh = Mesh::default();
+    for i in 0..8 {
+        mesh.add_vertex(Point3D::new(
+            if i & 1 == 0 { -0.5 } else { 0.5 },
+            if i & 2 == 0 { -0.5 } else { 0.5 },
+            if i & 4 == 0 { -0.5 } else { 0.5 },
+        ));
+    }
+    for [a, b, c, d] in [
+        [0, 2, 3, 1],
+        [4, 5, 7, 6],
+        [0, 1, 5, 4],
+        [2, 6, 7, 3],
+        [0, 4, 6, 2],
+        [1, 3, 7, 5],
+    ]
+    .iter()
+    {
+        mesh.try_add_connection(VId(*a), VId(*b), VId(*c)).unwrap();
+        mesh.try_add_connection(VId(*a), VId(*c), VId(*d)).unwrap();
+    }
+    mesh
+}
+
+fn assert_near(n: &Norm3D, x: f64, y: f64, z: f64) {
+    assert!((n.x() - x).abs() < 1e-9, "{:?}", n);
+    assert!((n.y() - y).abs() < 1e-9, "{:?}", n);
+    assert!((n.z() - z).abs() < 1e-9, "{:?}", n);
+}
+
+#[test]
+fn vertex_normals_test() {
+    let mesh = cube();
+    let s = 1.0 / 3.0f64.sqrt();
+
+    // angle weighting is independent of the triangulation
+    let normals = vertex_normals(&mesh, &NormalWeighting::Angle).unwrap();
+    assert_eq!(normals.len(), 8);
+    for (i, n) in normals.iter().enumerate() {
+        let v = mesh.vertex(VId(i)).unwrap();
+        assert_near(
+            n,
+            v.x().signum() * s,
+            v.y().signum() * s,
+            v.z().signum() * s,
+        );
+    }
+
+    // corner 0 is part of two triangles on each side
+    let uniform = vertex_normals(&mesh, &NormalWeighting::Uniform).unwrap();
+    assert_near(&uniform[0], -s, -s, -s);
+    // corner 1 is part of one triangle on the -y and -z sides, but two on the +x side
+    assert!(uniform[1].x().abs() > uniform[1].y().abs());
+    assert!((uniform[1].y() - uniform[1].z()).abs() < 1e-9);
+
+    let area = vertex_normals(&mesh, &NormalWeighting::Area).unwrap();
+    assert!(area[1].x().abs() > area[1].z().abs());
+    for n in area.iter() {
+        assert!(n.x().abs() > 0.0 && n.y().abs() > 0.0 && n.z().abs() > 0.0);
+    }
+}
+
+#[test]
+fn split_creases_test() {
+    let mesh = cube();
+
+    let smooth = split_creases(&mesh, Rad(2.0)).unwrap();
+    assert_eq!(smooth.num_vertices(), 8);
+
+    let split = split_creases(&mesh, Rad(0.5)).unwrap();
+    assert_eq!(split.num_vertices(), 24);
+    assert_eq!(split.num_faces(), 12);
+
+    let normal_mesh = NormalMesh3D::with_creases(&mesh, Rad(0.5), &NormalWeighting::Area).unwrap();
+    assert_eq!(normal_mesh.num_vertices(), 24);
+    for i in 0..normal_mesh.num_faces() {
+        let face_normal = normal_mesh.face_normal(FId(i)).unwrap();
+        let vids = normal_mesh.face_vertex_ids(FId(i)).unwrap();
+        for v in [vids.a, vids.b, vids.c].iter() {
+            let n = normal_mesh.vertex_normal(*v).unwrap();
+            assert_near(&n, face_normal.x(), face_normal.y(), face_normal.z());
+        }
+    }
+    assert!(normal_mesh.vertex_normal(VId(24)).is_none());
+
+    assert!(NormalMesh3D::new(mesh.clone(), vec![Norm3D::norm_z(); 7]).is_err());
+    let mut normal_mesh = NormalMesh3D::new(mesh, vec![Norm3D::norm_z(); 8]).unwrap();
+    normal_mesh
+        .change_vertex_normal(VId(3), Norm3D::norm_x())
+        .unwrap();
+    assert_eq!(normal_mesh.normals()[3], Norm3D::norm_x());
+    assert!(normal_mesh
+        .change_vertex_normal(VId(8), Norm3D::norm_x())
+        .is_err());
+
+    let (mesh, normals): (Mesh, Vec<Norm3D>) = normal_mesh.into();
+    assert_eq!(mesh.num_vertices(), normals.len());
+}